@@ -9,6 +9,7 @@ out vec4 outColor;
 
 uniform sampler2D fonts_texture;
 uniform sampler2D images_texture;
+uniform sampler2D fallback_font_texture;
 
 float median(float r, float g, float b) {
     return max(min(r, g), min(max(r, g), b));
@@ -17,14 +18,19 @@ float median(float r, float g, float b) {
 void main() {
     int flags = int(color.a * 255.0);
     int is_font = flags & 0x1;
+    int use_fallback = (flags >> 1) & 0x1;
 
     // Image sampling
     vec2 images_texcoord = uv / vec2(textureSize(images_texture, 0));
     vec4 color_sample = texture(images_texture, images_texcoord);
     
     // Msdf font sampling
-    vec2 fonts_texcoord = uv / vec2(textureSize(fonts_texture, 0));
-    vec4 font_sample = texture(fonts_texture, fonts_texcoord);
+    vec4 font_sample;
+    if (use_fallback == 0) {
+        font_sample = texture(fonts_texture, uv / vec2(textureSize(fonts_texture, 0)));
+    } else {
+        font_sample = texture(fallback_font_texture, uv / vec2(textureSize(fallback_font_texture, 0)));
+    }
 
     float dist = median(font_sample.r, font_sample.g, font_sample.b);
     float dx = dFdx(uv.x);
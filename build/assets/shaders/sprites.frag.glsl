@@ -33,5 +33,9 @@ void main() {
     color.rgb = mix(color.rgb, mix(color.rgb, outline_color, outline_mask), show_outline);
     color.a = mix(color.a, mix(color.a, 1.0, outline_mask), show_outline);
 
+    // Transparency (used by particles)
+    float transparency = float((data >> 8) & 255) / 255.0;
+    color *= 1.0 - transparency;
+
     outColor = color;
 }
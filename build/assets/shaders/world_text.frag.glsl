@@ -0,0 +1,39 @@
+#version 300 es
+
+precision highp float;
+
+in vec2 uv;
+in vec4 color;
+
+out vec4 outColor;
+
+uniform sampler2D font_texture;
+uniform sampler2D fallback_font_texture;
+
+float median(float r, float g, float b) {
+    return max(min(r, g), min(max(r, g), b));
+}
+
+void main() {
+    // Bit 0 of the alpha channel selects the font, the other bits are the opacity of the text
+    int alpha = int(color.a * 255.0);
+    int use_fallback = alpha & 0x1;
+    float text_opacity = float(alpha >> 1) / 127.0;
+
+    vec4 font_sample;
+    if (use_fallback == 0) {
+        font_sample = texture(font_texture, uv / vec2(textureSize(font_texture, 0)));
+    } else {
+        font_sample = texture(fallback_font_texture, uv / vec2(textureSize(fallback_font_texture, 0)));
+    }
+
+    float dist = median(font_sample.r, font_sample.g, font_sample.b);
+    float w = fwidth(dist) / 1.5;
+    float opacity = smoothstep(0.5 - w, 0.5 + w, dist);
+
+    // Dark outline around the glyphs so the text can be read over any terrain
+    float outline = smoothstep(0.3 - w, 0.3 + w, dist);
+    vec3 rgb = mix(vec3(0.0), color.rgb, opacity);
+
+    outColor = vec4(rgb, 1.0) * (outline * text_opacity);
+}
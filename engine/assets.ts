@@ -41,6 +41,8 @@ export class EngineAssets {
     textures_by_id: Texture[] = [];
 
     csv: Map<string, string> = new Map();
+    layouts: Map<string, string> = new Map();
+    layouts_paths: Map<string, string> = new Map();
     shaders: Map<string, Shader> = new Map();
     fonts: Map<string, Font> = new Map();
 
@@ -60,6 +62,18 @@ export class EngineAssets {
         return true
     }
 
+    /// Returns the names of the gui layouts in `paths`
+    find_layouts_by_path(paths: string[]): string[] {
+        let names: string[] = [];
+        for (let [name, path] of this.layouts_paths.entries()) {
+            if (paths.indexOf(path) != -1) {
+                names.push(name);
+            }
+        }
+
+        return names;
+    }
+
     async reload_assets(assets: string[]): Promise<boolean> {
         let reload_list: Promise<boolean>[] = [];
 
@@ -70,6 +84,10 @@ export class EngineAssets {
                     reload_list.push(this.reload_texture(asset))
                     break;
                 }
+                case "layout": {
+                    reload_list.push(this.reload_layout(asset))
+                    break;
+                }
             }
         }
 
@@ -127,6 +145,12 @@ export class EngineAssets {
                     asset_loading_promises.push(this.load_csv(name, path));
                     break;
                 }
+                case "LAYOUT": {
+                    const name = args[1];
+                    const path = args[2];
+                    asset_loading_promises.push(this.load_layout(name, path));
+                    break;
+                }
                 case "SHADER": {
                     const name = args[1];
                     const vertex_path = args[2];
@@ -196,6 +220,30 @@ export class EngineAssets {
         return true;
     }
 
+    private async load_layout(name: string, path: string): Promise<boolean> {
+        const layout_text = await fetch_text(path);
+        if (!layout_text) {
+            return false;
+        }
+
+        this.layouts.set(name, layout_text);
+        this.layouts_paths.set(name, path);
+
+        return true;
+    }
+
+    private async reload_layout(path: string): Promise<boolean> {
+        console.log(`Reloading ${path}`);
+
+        const [name] = this.find_layouts_by_path([path]);
+        if (name) {
+            return await this.load_layout(name, path);
+        } else {
+            console.log(`Failed to reload ${path}: layout not found`)
+            return true;
+        }
+    }
+
     private async load_shader(name: string, vertex_path: string, fragment_path: string): Promise<boolean> {
         const [vertex_text, fragment_text] = await Promise.all([
             fetch_text(vertex_path),
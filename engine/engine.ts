@@ -10,14 +10,22 @@ import { Error, get_last_error } from "./error";
 const UPDATE_MOUSE_POSITION = 0b001;
 const UPDATE_MOUSE_BUTTONS  = 0b010;
 const UPDATE_KEYS           = 0b100;
+const UPDATE_TEXT           = 0b1000;
+const UPDATE_MOUSE_WHEEL    = 0b10000;
 
 // Matches `MouseButton` in `game\src\inputs.rs`
 const MOUSE_BUTTON_LEFT = 0;
 const MOUSE_BUTTON_RIGHT = 1;
 const MOUSE_BUTTON_CENTER = 2;
 
+// Local storage keys of the user settings
+const UI_SCALE_SETTING = "ui_scale";
+const LANGUAGE_SETTING = "language";
+
 class InputState {
     updates: number = 0;
+
+    // Mouse position in view pixels. The canvas size is the element size multiplied by the device pixel ratio.
     mouse_position: number[] = [0.0, 0.0];
 
     // true: button was pressed, false: button was released, null: button state wasn't changed
@@ -25,7 +33,13 @@ class InputState {
     right_mouse_button: boolean|null = null;
     center_mouse_button: boolean|null = null;
 
+    // Vertical mouse wheel scrolling since the last update, in css pixels
+    mouse_wheel: number = 0.0;
+
     keys: Map<string, boolean> = new Map();
+
+    // Printable characters typed since the last update
+    text: string = "";
 }
 
 class Engine {
@@ -84,7 +98,7 @@ function start_game_client(engine: Engine): boolean {
     init.set_assets_bundle(engine.assets.raw_bundle);
 
     const size = engine.renderer.canvas_size();
-    init.set_initial_window_size(size.width, size.height);
+    init.set_initial_window_size(size.width, size.height, window.devicePixelRatio);
 
     const seed = new BigUint64Array(1);
     crypto.getRandomValues(seed);
@@ -94,6 +108,10 @@ function start_game_client(engine: Engine): boolean {
         init.upload_text_asset(name, json);
     }
 
+    for (let [name, layout] of engine.assets.layouts.entries()) {
+        init.upload_text_asset(name, layout);
+    }
+
     for (let [name, font] of engine.assets.fonts.entries()) {
         init.upload_font_asset(name, new Uint8Array(font.atlas_data));
     }
@@ -101,6 +119,15 @@ function start_game_client(engine: Engine): boolean {
     engine.game.instance = game.DemoGame.initialize(init);
 
     if (engine.game.instance) { 
+        const ui_scale = parseFloat(localStorage.getItem(UI_SCALE_SETTING) ?? "");
+        if (!isNaN(ui_scale)) {
+            engine.game.instance.set_ui_scale(ui_scale);
+        }
+
+        // Falls back to the default language if the game has no text for the browser language
+        const language = localStorage.getItem(LANGUAGE_SETTING) ?? navigator.language.split("-")[0];
+        engine.game.instance.set_language(language);
+
         return true
     } else {
         set_last_error(engine.game.module.get_last_error());
@@ -108,19 +135,23 @@ function start_game_client(engine: Engine): boolean {
     }
 }
 
+function set_mouse_position(input_state: InputState, event: MouseEvent) {
+    const dpr = window.devicePixelRatio;
+    input_state.mouse_position[0] = event.clientX * dpr;
+    input_state.mouse_position[1] = event.clientY * dpr;
+}
+
 function init_handlers(engine: Engine) {
     const canvas = engine.renderer.canvas();
     const input_state = engine.input;
 
     canvas.addEventListener("mousemove", (event) => { 
-        input_state.mouse_position[0] = event.clientX;
-        input_state.mouse_position[1] = event.clientY;
+        set_mouse_position(input_state, event);
         input_state.updates |= UPDATE_MOUSE_POSITION;
     })
 
     canvas.addEventListener("mousedown", (event) => {
-        input_state.mouse_position[0] = event.clientX;
-        input_state.mouse_position[1] = event.clientY;
+        set_mouse_position(input_state, event);
         input_state.updates |= UPDATE_MOUSE_BUTTONS;
 
         if (event.button === 0) { input_state.left_mouse_button = true; }
@@ -131,8 +162,7 @@ function init_handlers(engine: Engine) {
     })
 
     canvas.addEventListener("mouseup", (event) => {
-        input_state.mouse_position[0] = event.clientX;
-        input_state.mouse_position[1] = event.clientY;
+        set_mouse_position(input_state, event);
         input_state.updates |= UPDATE_MOUSE_BUTTONS;
 
         if (event.button === 0) { input_state.left_mouse_button = false; }
@@ -144,9 +174,20 @@ function init_handlers(engine: Engine) {
 
     canvas.addEventListener("contextmenu", (event) => { event.preventDefault(); });
 
+    canvas.addEventListener("wheel", (event) => {
+        input_state.mouse_wheel += event.deltaY;
+        input_state.updates |= UPDATE_MOUSE_WHEEL;
+        event.preventDefault();
+    }, { passive: false });
+
     window.addEventListener("keydown", (event) => {
         input_state.keys.set(event.code, true);
         input_state.updates |= UPDATE_KEYS;
+
+        if (event.key.length === 1 && !event.ctrlKey && !event.metaKey) {
+            input_state.text += event.key;
+            input_state.updates |= UPDATE_TEXT;
+        }
     });
     window.addEventListener("keyup", (event) => {
         // console.log(event.code);
@@ -210,7 +251,8 @@ function on_file_changed(engine: Engine, message: WebSocketMessage) {
             engine.reload = true;
             break;
         }
-        case "png": {
+        case "png":
+        case "layout": {
             engine.reload_assets.push(message.data);
             engine.reload = true;
             break;
@@ -251,7 +293,7 @@ function handle_resize(engine: Engine) {
     if (engine.renderer.handle_resize()) {
         const game = engine.game.instance;
         const canvas_size = engine.renderer.canvas_size();
-        game.update_view_size(canvas_size.width, canvas_size.height);
+        game.update_view_size(canvas_size.width, canvas_size.height, window.devicePixelRatio);
     }
 }
 
@@ -287,7 +329,17 @@ function game_input_updates(engine: Engine) {
         }
     }
 
+    if ((inputs.updates & UPDATE_MOUSE_WHEEL) > 0) {
+        game.update_mouse_wheel(inputs.mouse_wheel);
+        inputs.mouse_wheel = 0.0;
+    }
+
+    if ((inputs.updates & UPDATE_TEXT) > 0) {
+        game.update_text_input(inputs.text);
+    }
+
     inputs.keys.clear();
+    inputs.text = "";
     inputs.updates = 0;
 }
 
@@ -320,6 +372,20 @@ export function render(engine: Engine) {
     engine.renderer.render();
 }
 
+//
+// Settings
+//
+
+// Sets the scale of the gui chosen by the user and saves it for the next sessions
+export function set_ui_scale(engine: Engine, scale: number) {
+    if (!engine.game.instance) {
+        return;
+    }
+
+    engine.game.instance.set_ui_scale(scale);
+    localStorage.setItem(UI_SCALE_SETTING, scale.toString());
+}
+
 //
 // Reload
 //
@@ -330,6 +396,16 @@ async function reload_assets(engine: Engine): Promise<boolean> {
         return false;
     }
 
+    // Gui layouts are parsed by the game client
+    const game = engine.game.instance;
+    for (let name of engine.assets.find_layouts_by_path(engine.reload_assets)) {
+        const layout = engine.assets.layouts.get(name) as string;
+        if (!game.reload_text_asset(name, layout)) {
+            set_last_error(engine.game.module.get_last_error());
+            return false;
+        }
+    }
+
     return engine.renderer.reload_assets(engine.reload_assets);
 }
 
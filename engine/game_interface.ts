@@ -2,12 +2,14 @@
 
 import { DemoGame } from "../build/game/game";
 
-const OUTPUT_INDEX_SIZE: number = 64;  // size_of(OutputIndex)
+const OUTPUT_INDEX_SIZE: number = 80;  // size_of(OutputIndex)
 const DRAW_UPDATE_SIZE: number = 16;   // size_of(DrawUpdate)
 export const SPRITE_DATA_SIZE: number = 36;       // size_of(SpriteData) && size_of(ProjectileSpriteData)
 const TERRAIN_CHUNK_TEXT_COORD_SIZE: number = 32; // size_of(TerrainChunkTexcoord)
 export const GUI_VERTEX_SIZE: number = 20;        // size_of(GuiVertex)
 export const DEBUG_VERTEX_SIZE: number = 12;      // size_of(DebugVertex)
+export const OVERLAY_VERTEX_SIZE: number = 12;    // size_of(DebugVertex)
+export const WORLD_TEXT_VERTEX_SIZE: number = 20; // size_of(GuiVertex)
 
 const OUTPUT_INDEX_DRAW_UPDATES_OFFSET: number = 4;
 const OUTPUT_INDEX_DRAW_UPDATES_COUNT_OFFSET: number = 8;
@@ -30,7 +32,13 @@ const OUTPUT_INDEX_GUI_VERTEX_COUNT_OFFSET: number = 48;
 const OUTPUT_INDEX_DEBUG_VERTEX_OFFSET: number = 52;
 const OUTPUT_INDEX_DEBUG_VERTEX_COUNT_OFFSET: number = 56;
 
-const OUTPUT_INDEX_VALIDATION_INDEX: number = 60;
+const OUTPUT_INDEX_OVERLAY_VERTEX_OFFSET: number = 60;
+const OUTPUT_INDEX_OVERLAY_VERTEX_COUNT_OFFSET: number = 64;
+
+const OUTPUT_INDEX_WORLD_TEXT_VERTEX_OFFSET: number = 68;
+const OUTPUT_INDEX_WORLD_TEXT_VERTEX_COUNT_OFFSET: number = 72;
+
+const OUTPUT_INDEX_VALIDATION_INDEX: number = 76;
 
 const DRAW_UPDATE_GRAPHICS_MODULE_OFFSET: number = 0;
 
@@ -47,6 +55,8 @@ export enum DrawUpdateType {
     UpdateGui = 5,
     DrawProjectileSprites = 6,
     DrawDebugInfo = 7,
+    DrawWorldOverlay = 8,
+    DrawWorldText = 9,
 }
 
 export class EngineGameDrawUpdate {
@@ -70,8 +80,12 @@ export class EngineGameDrawUpdate {
     view_y: number;
 
     // Gui update params
-    gui_indices_count: number;
     gui_vertex_count: number;
+    gui_update_offset: number;
+    gui_update_count: number;
+
+    // World overlay params
+    overlay_ground_vertex_count: number;
 }
 
 export class EngineGameInstanceUpdates {
@@ -129,8 +143,9 @@ export class EngineGameInstanceUpdates {
                 break;
             }
             case DrawUpdateType.UpdateGui: {
-                draw.gui_indices_count = draw_update_view.getUint32(4, true);
-                draw.gui_vertex_count = draw_update_view.getUint32(8, true);
+                draw.gui_vertex_count = draw_update_view.getUint32(4, true);
+                draw.gui_update_offset = draw_update_view.getUint32(8, true);
+                draw.gui_update_count = draw_update_view.getUint32(12, true);
                 break;
             }
             case DrawUpdateType.DrawProjectileSprites: {
@@ -142,6 +157,13 @@ export class EngineGameInstanceUpdates {
             case DrawUpdateType.DrawDebugInfo: {
                 break;
             }
+            case DrawUpdateType.DrawWorldOverlay: {
+                draw.overlay_ground_vertex_count = draw_update_view.getUint32(4, true);
+                break;
+            }
+            case DrawUpdateType.DrawWorldText: {
+                break;
+            }
             default: {
                 console.error("Error: Received unknown draw update type");
             }
@@ -178,10 +200,12 @@ export class EngineGameInstanceUpdates {
         return this.buffer.slice(gui_indices_base, gui_indices_base+(gui_indices_count*INDEX_SIZE));
     }
 
-    get_gui_vertex_data(): ArrayBuffer {
+    get_gui_vertex_data(offset: number, count: number): ArrayBuffer {
         const gui_vertex_base = this.index.getUint32(OUTPUT_INDEX_GUI_VERTEX_OFFSET, true);
         const gui_vertex_count = this.index.getUint32(OUTPUT_INDEX_GUI_VERTEX_COUNT_OFFSET, true);
-        return this.buffer.slice(gui_vertex_base, gui_vertex_base+(gui_vertex_count*GUI_VERTEX_SIZE));
+        const begin = gui_vertex_base + (Math.min(offset, gui_vertex_count) * GUI_VERTEX_SIZE);
+        const end = gui_vertex_base + (Math.min(offset + count, gui_vertex_count) * GUI_VERTEX_SIZE);
+        return this.buffer.slice(begin, end);
     }
 
     get_debug_vertex_count(): number {
@@ -194,6 +218,26 @@ export class EngineGameInstanceUpdates {
         return this.buffer.slice(debug_vertex_base, debug_vertex_base+(debug_vertex_count*DEBUG_VERTEX_SIZE));
     }
 
+    get_overlay_vertex_count(): number {
+        return this.index.getUint32(OUTPUT_INDEX_OVERLAY_VERTEX_COUNT_OFFSET, true);
+    }
+
+    get_overlay_vertex_data(): ArrayBuffer {
+        const overlay_vertex_base = this.index.getUint32(OUTPUT_INDEX_OVERLAY_VERTEX_OFFSET, true);
+        const overlay_vertex_count = this.index.getUint32(OUTPUT_INDEX_OVERLAY_VERTEX_COUNT_OFFSET, true);
+        return this.buffer.slice(overlay_vertex_base, overlay_vertex_base+(overlay_vertex_count*OVERLAY_VERTEX_SIZE));
+    }
+
+    get_world_text_vertex_count(): number {
+        return this.index.getUint32(OUTPUT_INDEX_WORLD_TEXT_VERTEX_COUNT_OFFSET, true);
+    }
+
+    get_world_text_vertex_data(): ArrayBuffer {
+        const world_text_vertex_base = this.index.getUint32(OUTPUT_INDEX_WORLD_TEXT_VERTEX_OFFSET, true);
+        const world_text_vertex_count = this.index.getUint32(OUTPUT_INDEX_WORLD_TEXT_VERTEX_COUNT_OFFSET, true);
+        return this.buffer.slice(world_text_vertex_base, world_text_vertex_base+(world_text_vertex_count*WORLD_TEXT_VERTEX_SIZE));
+    }
+
 }
 
 export class EngineGameInstance {
@@ -1,6 +1,6 @@
 import { EngineGameInstance, EngineGameInstanceUpdates, EngineGameDrawUpdate, DrawUpdateType,
     SPRITE_DATA_SIZE, TERRAIN_CHUNK_STRIDE, TERRAIN_CHUNK_SIZE_BYTES, GUI_VERTEX_SIZE, 
-    DEBUG_VERTEX_SIZE} from "../game_interface";
+    DEBUG_VERTEX_SIZE, OVERLAY_VERTEX_SIZE, WORLD_TEXT_VERTEX_SIZE} from "../game_interface";
 import { EngineAssets, Texture } from "../assets";
 import { set_last_error } from "../error";
 import { Size, file_extension } from "../helpers";
@@ -71,6 +71,7 @@ class RendererShaders {
     gui_color_attrloc: number;
     gui_view_size: WebGLUniformLocation;
     gui_font_texture: WebGLUniformLocation;
+    gui_fallback_font_texture: WebGLUniformLocation;
     gui_image_texture: WebGLUniformLocation;
     gui: WebGLProgram;
 
@@ -79,6 +80,15 @@ class RendererShaders {
     debug_view_position: WebGLUniformLocation;
     debug_view_size: WebGLUniformLocation;
     debug: WebGLProgram;
+
+    world_text_position_attrloc: number;
+    world_text_uv_attrloc: number;
+    world_text_color_attrloc: number;
+    world_text_view_position: WebGLUniformLocation;
+    world_text_view_size: WebGLUniformLocation;
+    world_text_font_texture: WebGLUniformLocation;
+    world_text_fallback_font_texture: WebGLUniformLocation;
+    world_text: WebGLProgram;
 }
 
 class TerrainChunkData {
@@ -106,6 +116,7 @@ class RendererBuffers {
     gui_indices_capacity: number = 0;
     gui_vertex_capacity: number = 0;
     gui_indices_len: number = 0;
+    gui_indices_uploaded: number = 0;
     gui_vertex_len: number = 0;
     gui_vao: WebGLVertexArrayObject;
 
@@ -113,6 +124,17 @@ class RendererBuffers {
     debug_vertex_capacity: number = 0;
     debug_vertex_len: number = 0;
     debug_vao: WebGLVertexArrayObject;
+
+    overlay_vertex: WebGLBuffer;
+    overlay_vertex_capacity: number = 0;
+    overlay_vertex_len: number = 0;
+    overlay_ground_vertex_len: number = 0;
+    overlay_vao: WebGLVertexArrayObject;
+
+    world_text_vertex: WebGLBuffer;
+    world_text_vertex_capacity: number = 0;
+    world_text_vertex_len: number = 0;
+    world_text_vao: WebGLVertexArrayObject;
 }
 
 class RendererCanvas {
@@ -138,6 +160,7 @@ export class WebGL2Backend {
     textures: RendererTexture[];
     terrain_texture: RendererTexture;
     font_texture: RendererTexture;
+    fallback_font_texture: RendererTexture;
     gui_texture: RendererTexture;
 
     shaders: RendererShaders;
@@ -251,6 +274,9 @@ export class WebGL2Backend {
         ctx.useProgram(this.shaders.debug);
         ctx.uniform2f(this.shaders.debug_view_size, canvas.width, canvas.height);
 
+        ctx.useProgram(this.shaders.world_text);
+        ctx.uniform2f(this.shaders.world_text_view_size, canvas.width, canvas.height);
+
         return true;
     }
 
@@ -511,26 +537,38 @@ export class WebGL2Backend {
         const ctx = this.ctx;
         const buffers = this.buffers;
 
-        buffers.gui_indices_len = draw_update.gui_indices_count;
         buffers.gui_vertex_len = draw_update.gui_vertex_count;
+        buffers.gui_indices_len = (buffers.gui_vertex_len / 4) * 6;
 
         if (buffers.gui_indices_len == 0){
             return;
         }
 
+        // Recreating the buffers loses their content, so everything must be uploaded again
+        let update_offset = draw_update.gui_update_offset;
+        let update_count = draw_update.gui_update_count;
         if (buffers.gui_vertex_len > buffers.gui_vertex_capacity) {
             const capacity = buffers.gui_vertex_len + 500;
             this.setup_gui_vertex(capacity);
             this.setup_gui_vao();
+            buffers.gui_indices_uploaded = 0;
+            update_offset = 0;
+            update_count = buffers.gui_vertex_len;
         }
 
-        const gui_indices_data = updates.get_gui_indices_data();
-        ctx.bindBuffer(ctx.ELEMENT_ARRAY_BUFFER, buffers.gui_indices);
-        ctx.bufferSubData(ctx.ELEMENT_ARRAY_BUFFER, 0, gui_indices_data);
+        // Indices only depend on the number of sprites. They only need to be uploaded when the gui grows
+        if (buffers.gui_indices_len > buffers.gui_indices_uploaded) {
+            const gui_indices_data = updates.get_gui_indices_data();
+            ctx.bindBuffer(ctx.ELEMENT_ARRAY_BUFFER, buffers.gui_indices);
+            ctx.bufferSubData(ctx.ELEMENT_ARRAY_BUFFER, 0, gui_indices_data);
+            buffers.gui_indices_uploaded = gui_indices_data.byteLength / 2;
+        }
 
-        const gui_vertex_data = updates.get_gui_vertex_data();
-        ctx.bindBuffer(ctx.ARRAY_BUFFER, buffers.gui_vertex);
-        ctx.bufferSubData(ctx.ARRAY_BUFFER, 0, gui_vertex_data);
+        if (update_count > 0) {
+            const gui_vertex_data = updates.get_gui_vertex_data(update_offset, update_count);
+            ctx.bindBuffer(ctx.ARRAY_BUFFER, buffers.gui_vertex);
+            ctx.bufferSubData(ctx.ARRAY_BUFFER, update_offset * GUI_VERTEX_SIZE, gui_vertex_data);
+        }
     }
 
     private update_view_offset(draw_update: EngineGameDrawUpdate) {
@@ -552,6 +590,9 @@ export class WebGL2Backend {
 
         ctx.useProgram(this.shaders.debug);
         ctx.uniform2f(this.shaders.debug_view_position, x, y);
+
+        ctx.useProgram(this.shaders.world_text);
+        ctx.uniform2f(this.shaders.world_text_view_position, x, y);
     }
 
     private update_debug(updates: EngineGameInstanceUpdates) {
@@ -574,6 +615,47 @@ export class WebGL2Backend {
         ctx.bufferSubData(ctx.ARRAY_BUFFER, 0, debug_vertex_data);
     }
 
+    private update_world_overlay(updates: EngineGameInstanceUpdates, draw_update: EngineGameDrawUpdate) {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+
+        buffers.overlay_vertex_len = updates.get_overlay_vertex_count();
+        buffers.overlay_ground_vertex_len = draw_update.overlay_ground_vertex_count;
+        if (buffers.overlay_vertex_len == 0) {
+            return;
+        }
+
+        if (buffers.overlay_vertex_len > buffers.overlay_vertex_capacity) {
+            const capacity = buffers.overlay_vertex_len + 600;
+            this.setup_overlay_vertex(capacity);
+            this.setup_overlay_vao();
+        }
+
+        const overlay_vertex_data = updates.get_overlay_vertex_data();
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, buffers.overlay_vertex);
+        ctx.bufferSubData(ctx.ARRAY_BUFFER, 0, overlay_vertex_data);
+    }
+
+    private update_world_text(updates: EngineGameInstanceUpdates) {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+
+        buffers.world_text_vertex_len = updates.get_world_text_vertex_count();
+        if (buffers.world_text_vertex_len == 0) {
+            return;
+        }
+
+        if (buffers.world_text_vertex_len > buffers.world_text_vertex_capacity) {
+            const capacity = buffers.world_text_vertex_len + 600;
+            this.setup_world_text_vertex(capacity);
+            this.setup_world_text_vao();
+        }
+
+        const world_text_vertex_data = updates.get_world_text_vertex_data();
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, buffers.world_text_vertex);
+        ctx.bufferSubData(ctx.ARRAY_BUFFER, 0, world_text_vertex_data);
+    }
+
     private clear_drawing() {
         this.sprite_draw_count = 0;
         this.projectile_draw_count = 0;
@@ -581,6 +663,9 @@ export class WebGL2Backend {
         this.buffers.sprites_attributes_len = 0;
         this.buffers.sprite_vao_len = 0;
         this.buffers.debug_vertex_len = 0;
+        this.buffers.overlay_vertex_len = 0;
+        this.buffers.overlay_ground_vertex_len = 0;
+        this.buffers.world_text_vertex_len = 0;
     }
 
     update(game: EngineGameInstance) {
@@ -620,6 +705,14 @@ export class WebGL2Backend {
                     this.update_debug(game_updates);
                     break;
                 }
+                case DrawUpdateType.DrawWorldOverlay: {
+                    this.update_world_overlay(game_updates, draw_update);
+                    break;
+                }
+                case DrawUpdateType.DrawWorldText: {
+                    this.update_world_text(game_updates);
+                    break;
+                }
                 default: {
                     console.log(`Warning: A drawing update with an unknown type ${draw_update.module} was received`);
                 }
@@ -693,6 +786,53 @@ export class WebGL2Backend {
         }
     }
 
+    // The world overlay (ex: health bars) uses the debug shader, but it is always rendered under the gui
+    // The first `overlay_ground_vertex_len` vertex are rendered under the sprites (ex: selection markers)
+    private render_world_overlay_ground() {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+        if (buffers.overlay_ground_vertex_len == 0) {
+            return;
+        }
+
+        ctx.useProgram(this.shaders.debug);
+        ctx.bindVertexArray(buffers.overlay_vao);
+        ctx.drawArrays(ctx.TRIANGLES, 0, buffers.overlay_ground_vertex_len);
+    }
+
+    private render_world_overlay() {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+        const count = buffers.overlay_vertex_len - buffers.overlay_ground_vertex_len;
+        if (count <= 0) {
+            return;
+        }
+
+        ctx.useProgram(this.shaders.debug);
+        ctx.bindVertexArray(buffers.overlay_vao);
+        ctx.drawArrays(ctx.TRIANGLES, buffers.overlay_ground_vertex_len, count);
+    }
+
+    // Floating texts over the world objects
+    private render_world_text() {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+        if (buffers.world_text_vertex_len == 0) {
+            return;
+        }
+
+        ctx.useProgram(this.shaders.world_text);
+
+        ctx.activeTexture(ctx.TEXTURE0);
+        ctx.bindTexture(ctx.TEXTURE_2D, this.font_texture.handle);
+
+        ctx.activeTexture(ctx.TEXTURE1);
+        ctx.bindTexture(ctx.TEXTURE_2D, this.fallback_font_texture.handle);
+
+        ctx.bindVertexArray(buffers.world_text_vao);
+        ctx.drawArrays(ctx.TRIANGLES, 0, buffers.world_text_vertex_len);
+    }
+
     private render_gui() {
         const ctx = this.ctx;
         const buffers = this.buffers;
@@ -708,6 +848,9 @@ export class WebGL2Backend {
         ctx.activeTexture(ctx.TEXTURE1);
         ctx.bindTexture(ctx.TEXTURE_2D, this.gui_texture.handle);
 
+        ctx.activeTexture(ctx.TEXTURE2);
+        ctx.bindTexture(ctx.TEXTURE_2D, this.fallback_font_texture.handle);
+
         ctx.bindVertexArray(buffers.gui_vao);
         ctx.drawElements(ctx.TRIANGLES, buffers.gui_indices_len, ctx.UNSIGNED_SHORT, 0);
     }
@@ -732,8 +875,11 @@ export class WebGL2Backend {
         ctx.clearBufferfv(ctx.COLOR, 0, [0.0, 0.0, 0.0, 1.0]);
 
         this.render_terrain_chunks();
+        this.render_world_overlay_ground();
         this.render_sprites();
         this.render_projectiles();
+        this.render_world_overlay();
+        this.render_world_text();
         this.render_gui();
         this.render_debug();
 
@@ -757,6 +903,7 @@ export class WebGL2Backend {
         switch (name) {
             case "terrain": { this.terrain_texture = new_texture; break; }
             case "roboto": { this.font_texture = new_texture; break; }
+            case "noto_sans": { this.fallback_font_texture = new_texture; break; }
             case "gui": { this.gui_texture = new_texture; break; }
         }
 
@@ -991,6 +1138,7 @@ export class WebGL2Backend {
         shaders.gui_color_attrloc = ctx.getAttribLocation(gui_program, "in_color");
         shaders.gui_view_size = ctx.getUniformLocation(gui_program, "view_size") as any;
         shaders.gui_font_texture = ctx.getUniformLocation(gui_program, "fonts_texture") as any;
+        shaders.gui_fallback_font_texture = ctx.getUniformLocation(gui_program, "fallback_font_texture") as any;
         shaders.gui_image_texture = ctx.getUniformLocation(gui_program, "images_texture") as any;
         shaders.gui = gui_program;
 
@@ -1020,6 +1168,35 @@ export class WebGL2Backend {
         shaders.debug_view_size = ctx.getUniformLocation(debug_program, "view_size") as any;
         shaders.debug = debug_program;
 
+        // World text
+        const world_text_shader_source = assets.shaders.get("world_text");
+        if (!world_text_shader_source) {
+            set_last_error("Failed to find world text shader source in assets");
+            return false;
+        }
+
+        const world_text_vert = create_shader(ctx, ctx.VERTEX_SHADER, world_text_shader_source.vertex);
+        const world_text_frag = create_shader(ctx, ctx.FRAGMENT_SHADER, world_text_shader_source.fragment);
+        if (!world_text_vert || !world_text_frag) {
+            set_last_error("Failed to create world text shaders");
+            return false;
+        }
+
+        const world_text_program = create_program(ctx, world_text_vert, world_text_frag);
+        if (!world_text_program) {
+            set_last_error("Failed to compile world text shaders");
+            return false;
+        }
+
+        shaders.world_text_position_attrloc = ctx.getAttribLocation(world_text_program, "in_position");
+        shaders.world_text_uv_attrloc = ctx.getAttribLocation(world_text_program, "in_uv");
+        shaders.world_text_color_attrloc = ctx.getAttribLocation(world_text_program, "in_color");
+        shaders.world_text_view_position = ctx.getUniformLocation(world_text_program, "view_position") as any;
+        shaders.world_text_view_size = ctx.getUniformLocation(world_text_program, "view_size") as any;
+        shaders.world_text_font_texture = ctx.getUniformLocation(world_text_program, "font_texture") as any;
+        shaders.world_text_fallback_font_texture = ctx.getUniformLocation(world_text_program, "fallback_font_texture") as any;
+        shaders.world_text = world_text_program;
+
         // Cleanup
         ctx.deleteShader(sprites_vert);
         ctx.deleteShader(sprites_frag);
@@ -1031,6 +1208,8 @@ export class WebGL2Backend {
         ctx.deleteShader(gui_frag);
         ctx.deleteShader(debug_vert);
         ctx.deleteShader(debug_frag);
+        ctx.deleteShader(world_text_vert);
+        ctx.deleteShader(world_text_frag);
 
         return true;
     }
@@ -1060,6 +1239,10 @@ export class WebGL2Backend {
         this.font_texture = this.create_renderer_texture(font_texture_id);
         this.gui_texture = this.create_renderer_texture(gui_texture_id);
 
+        // Glyphs missing from the main font are rendered using the fallback font. If there is none, the main font is bound instead.
+        const fallback_font_texture_id = this.assets.fonts.get("noto_sans")?.texture_id;
+        this.fallback_font_texture = fallback_font_texture_id ? this.create_renderer_texture(fallback_font_texture_id) : this.font_texture;
+
         return true;
     }
 
@@ -1263,6 +1446,86 @@ export class WebGL2Backend {
         ctx.bindVertexArray(null);
     }
 
+    private setup_overlay_vertex(capacity?: number) {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+        const OVERLAY_VERTEX_CAPACITY = capacity ? capacity : 600;
+        if (buffers.overlay_vertex) {
+            ctx.deleteBuffer(buffers.overlay_vertex);
+        }
+
+        buffers.overlay_vertex = ctx.createBuffer();
+        buffers.overlay_vertex_capacity = OVERLAY_VERTEX_CAPACITY;
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, this.buffers.overlay_vertex);
+        ctx.bufferData(ctx.ARRAY_BUFFER, buffers.overlay_vertex_capacity * OVERLAY_VERTEX_SIZE, ctx.DYNAMIC_DRAW);
+    }
+
+    private setup_overlay_vao() {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+
+        const position = this.shaders.debug_position_attrloc;
+        const color = this.shaders.debug_color_attrloc;
+
+        if (!buffers.overlay_vao) {
+            buffers.overlay_vao = ctx.createVertexArray();
+        }
+        
+        ctx.bindVertexArray(buffers.overlay_vao);
+
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, this.buffers.overlay_vertex);
+
+        ctx.enableVertexAttribArray(position);
+        ctx.vertexAttribPointer(position, 2, ctx.FLOAT, false, 12, 0);
+
+        ctx.enableVertexAttribArray(color);
+        ctx.vertexAttribPointer(color, 4, ctx.UNSIGNED_BYTE, true, 12, 8);
+
+        ctx.bindVertexArray(null);
+    }
+
+    private setup_world_text_vertex(capacity?: number) {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+        const WORLD_TEXT_VERTEX_CAPACITY = capacity ? capacity : 600;
+        if (buffers.world_text_vertex) {
+            ctx.deleteBuffer(buffers.world_text_vertex);
+        }
+
+        buffers.world_text_vertex = ctx.createBuffer();
+        buffers.world_text_vertex_capacity = WORLD_TEXT_VERTEX_CAPACITY;
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, this.buffers.world_text_vertex);
+        ctx.bufferData(ctx.ARRAY_BUFFER, buffers.world_text_vertex_capacity * WORLD_TEXT_VERTEX_SIZE, ctx.DYNAMIC_DRAW);
+    }
+
+    private setup_world_text_vao() {
+        const ctx = this.ctx;
+        const buffers = this.buffers;
+
+        const position = this.shaders.world_text_position_attrloc;
+        const uv = this.shaders.world_text_uv_attrloc;
+        const color = this.shaders.world_text_color_attrloc;
+
+        if (!buffers.world_text_vao) {
+            buffers.world_text_vao = ctx.createVertexArray();
+        }
+        
+        ctx.bindVertexArray(buffers.world_text_vao);
+
+        ctx.bindBuffer(ctx.ARRAY_BUFFER, this.buffers.world_text_vertex);
+
+        ctx.enableVertexAttribArray(position);
+        ctx.vertexAttribPointer(position, 2, ctx.FLOAT, false, 20, 0);
+
+        ctx.enableVertexAttribArray(uv);
+        ctx.vertexAttribPointer(uv, 2, ctx.FLOAT, false, 20, 8);
+
+        ctx.enableVertexAttribArray(color);
+        ctx.vertexAttribPointer(color, 4, ctx.UNSIGNED_BYTE, true, 20, 16);
+
+        ctx.bindVertexArray(null);
+    }
+
     private setup_buffers() {
         this.setup_sprites_vertex();
         this.setup_sprites_attributes();
@@ -1271,6 +1534,10 @@ export class WebGL2Backend {
         this.setup_gui_vao();
         this.setup_debug_vertex();
         this.setup_debug_vao();
+        this.setup_overlay_vertex();
+        this.setup_overlay_vao();
+        this.setup_world_text_vertex();
+        this.setup_world_text_vao();
     }
 
     private setup_uniforms() {
@@ -1292,10 +1559,17 @@ export class WebGL2Backend {
         ctx.uniform2f(this.shaders.gui_view_size, this.canvas.width, this.canvas.height);
         ctx.uniform1i(this.shaders.gui_font_texture, 0);
         ctx.uniform1i(this.shaders.gui_image_texture, 1);
+        ctx.uniform1i(this.shaders.gui_fallback_font_texture, 2);
 
         ctx.useProgram(this.shaders.debug);
         ctx.uniform2f(this.shaders.debug_view_position, 0.0, 0.0);
         ctx.uniform2f(this.shaders.debug_view_size, this.canvas.width, this.canvas.height);
+
+        ctx.useProgram(this.shaders.world_text);
+        ctx.uniform2f(this.shaders.world_text_view_position, 0.0, 0.0);
+        ctx.uniform2f(this.shaders.world_text_view_size, this.canvas.width, this.canvas.height);
+        ctx.uniform1i(this.shaders.world_text_font_texture, 0);
+        ctx.uniform1i(this.shaders.world_text_fallback_font_texture, 1);
     }
 
 }
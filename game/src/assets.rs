@@ -2,7 +2,7 @@ mod fonts;
 pub use fonts::*;
 
 mod animations;
-pub use animations::AnimationBase;
+pub use animations::{AnimationBase, AnimationEvents};
 use animations::AnimationsBundle;
 
 mod terrain_tilemap;
@@ -20,6 +20,12 @@ pub use resources::*;
 mod gui;
 pub use gui::GuiBundle;
 
+mod gui_layouts;
+pub use gui_layouts::*;
+
+mod strings;
+pub use strings::Strings;
+
 use fnv::FnvHashMap;
 use std::sync::Arc;
 
@@ -34,12 +40,6 @@ pub struct Texture {
     pub id: u32,
 }
 
-#[derive(Default)]
-pub struct Fonts {
-    // Assets are shared with the gui system
-    pub roboto: FontAsset,
-}
-
 #[derive(Default)]
 pub struct Assets {
     pub textures: FnvHashMap<String, Texture>,
@@ -50,6 +50,8 @@ pub struct Assets {
     pub structures: StructuresBundle,
     pub resources: ResourcesBundle,
     pub animations: AnimationsBundle,
+    pub gui_layouts: FnvHashMap<String, GuiLayoutAsset>,
+    pub strings: Strings,
 }
 
 impl Assets {
@@ -88,6 +90,9 @@ impl Assets {
             "gui" => {
                 self.gui.load(csv_string);
             },
+            name if name.starts_with("strings_") => {
+                self.strings.load(&name["strings_".len()..], csv_string)?;
+            },
             name => {
                 warn!("Unknown csv: {:?}", name);
             }
@@ -96,6 +101,19 @@ impl Assets {
         Ok(())
     }
 
+    fn load_gui_layout(&mut self, init: &crate::DemoGameInit, args: &[&str]) -> Result<(), Error> {
+        let &layout_name = args.get(1)
+            .ok_or_else(|| assets_err!("Missing gui layout name") )?;
+
+        let layout_string = init.text_assets.get(layout_name)
+            .ok_or_else(|| assets_err!("Failed to match gui layout name to gui layout data") )?;
+
+        let layout = GuiLayoutAsset::parse(layout_string)?;
+        self.gui_layouts.insert(layout_name.to_string(), layout);
+
+        Ok(())
+    }
+
     fn load_font(&mut self, init: &crate::DemoGameInit, args: &[&str]) -> Result<(), Error> {
         let font_name = args.get(1)
             .map(|value| value.to_string() )
@@ -109,6 +127,7 @@ impl Assets {
 
         match font_name.as_str() {
             "roboto" => { self.fonts.roboto = font; },
+            FALLBACK_FONT => { self.fonts.fallback = font; },
             name => { warn!("Unknown font: {:?}", name); }
         };
 
@@ -156,6 +175,9 @@ fn import_assets_index(assets: &mut Assets, init: &DemoGameInit) -> Result<(), E
             "FONT" => {
                 assets.load_font(init, args)
             },
+            "LAYOUT" => {
+                assets.load_gui_layout(init, args)
+            },
             "SHADER" => Ok(()),
             _ => { Err(assets_err!("Unknown asset type {:?}", args[0])) }
         };
@@ -176,13 +198,61 @@ fn init_world_assets(game: &mut DemoGame) -> Result<(), Error> {
     let world = &mut game.data.world;
     let assets = &game.data.assets;
 
+    // Shared first so that the world never runs without assets, even if a texture is missing
+    world.assets = Some(Arc::clone(assets));
+    game.data.gui.set_assets(Arc::clone(assets));
+
     world.units_texture = assets.textures.get("units").copied()
         .ok_or_else(|| assets_err!("units texture missing") )?;
 
     world.static_resources_texture = assets.textures.get("static_resources").copied()
         .ok_or_else(|| assets_err!("static_resources texture missing") )?;
 
-    world.assets = Some(Arc::clone(assets));
+    Ok(())
+}
+
+/// The assets are shared with the world and the gui. Their references are released while `edit` modifies the assets in place.
+/// The assets are shared again afterward, even if `edit` failed.
+fn edit_assets<F: FnOnce(&mut Assets) -> Result<(), Error>>(game: &mut DemoGame, edit: F) -> Result<(), Error> {
+    game.data.world.assets = None;
+    game.data.gui.release_assets();
+
+    let result = match Arc::get_mut(&mut game.data.assets) {
+        Some(assets) => edit(assets),
+        None => Err(assets_err!("Assets are still shared and cannot be modified"))
+    };
+
+    init_world_assets(game)?;
+
+    result
+}
+
+/// Replaces a text asset that was modified after the game was initialized. Only gui layouts can be reloaded.
+pub fn reload_text_asset(game: &mut DemoGame, name: &str, value: &str) -> Result<(), Error> {
+    if !game.data.assets.gui_layouts.contains_key(name) {
+        return Err(assets_err!("Text asset {:?} cannot be reloaded", name));
+    }
+
+    let layout = GuiLayoutAsset::parse(value)?;
+    edit_assets(game, |assets| {
+        assets.gui_layouts.insert(name.to_string(), layout);
+        Ok(())
+    })?;
+
+    game.data.global.flags.set_reload_gui();
+
+    Ok(())
+}
+
+/// Selects the language of the player facing text. The gui is built again so that every text is rendered in the new language.
+pub fn set_language(game: &mut DemoGame, language: &str) -> Result<(), Error> {
+    if game.data.assets.strings.language() == language {
+        return Ok(());
+    }
+
+    edit_assets(game, |assets| assets.strings.set_language(language) )?;
+
+    game.data.global.flags.set_reload_gui();
 
     Ok(())
 }
@@ -201,6 +271,14 @@ impl crate::store::SaveAndLoad for Assets {
         writer.write(&self.animations);
         writer.write(&self.gui);
         writer.save(&self.fonts);
+
+        writer.write_u32(self.gui_layouts.len() as u32);
+        for (name, layout) in self.gui_layouts.iter() {
+            writer.write_str(name);
+            writer.save(layout);
+        }
+
+        writer.save(&self.strings);
     }
 
     fn load(reader: &mut crate::store::SaveFileReader) -> Self {
@@ -213,19 +291,16 @@ impl crate::store::SaveAndLoad for Assets {
         assets.animations = reader.read();
         assets.gui = reader.read();
         assets.fonts = reader.load();
-        assets
-    }
-}
-
-impl crate::store::SaveAndLoad for Fonts {
-    fn save(&self, writer: &mut crate::store::SaveFileWriter) {
-        writer.save(&self.roboto);
-    }
 
-    fn load(reader: &mut crate::store::SaveFileReader) -> Self {
-        Fonts { 
-            roboto: reader.load(),
+        let layouts_count = reader.read_u32();
+        for _ in 0..layouts_count {
+            let name = reader.read_str().to_string();
+            assets.gui_layouts.insert(name, reader.load());
         }
-    }
 
+        assets.strings = reader.load();
+
+        assets
+    }
 }
+
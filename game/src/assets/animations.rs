@@ -1,13 +1,55 @@
 use crate::error::Error;
-use crate::shared::{AABB, split_csv};
+use crate::shared::{AABB, split_csv, merge_error};
+
+/// Duration of a frame in milliseconds (16fps) if an animation does not set its own
+pub const DEFAULT_FRAME_DURATION: f32 = 1000.0 / 16.0;
+
+/// Events can only be set on the first `MAX_EVENT_FRAMES` frames of an animation
+pub const MAX_EVENT_FRAMES: usize = 8;
+
+/// Events triggered when an animation enters a frame. See `BaseAnimated::update_animation`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnimationEvents(u8);
+
+impl AnimationEvents {
+    pub const NONE: Self = AnimationEvents(0);
+    /// A weapon or a tool connects with its target
+    pub const HIT: Self = AnimationEvents(0b0001);
+    /// A projectile leaves the hand of the unit
+    pub const RELEASE: Self = AnimationEvents(0b0010);
+    /// A foot touches the ground
+    pub const FOOTSTEP: Self = AnimationEvents(0b0100);
+    /// An animation played with `AnimationMode::Once` or `AnimationMode::OnceThenIdle` completed. Not available in `units.csv`
+    pub const FINISHED: Self = AnimationEvents(0b1000);
+
+    pub const fn contains(&self, event: Self) -> bool {
+        self.0 & event.0 > 0
+    }
+
+    pub fn insert(&mut self, events: Self) {
+        self.0 |= events.0;
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "hit" => Some(Self::HIT),
+            "release" => Some(Self::RELEASE),
+            "footstep" => Some(Self::FOOTSTEP),
+            _ => None
+        }
+    }
+}
 
-#[derive(Copy, Clone, Debug, Default)]
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
 pub struct AnimationBase {
     pub x: f32,
     pub y: f32,
     pub sprite_width: f32,
     pub sprite_height: f32,
     pub last_frame: u8,
+    /// Duration of each frame in milliseconds
+    pub frame_duration: f32,
+    pub events: [AnimationEvents; MAX_EVENT_FRAMES],
 }
 
 impl AnimationBase {
@@ -18,8 +60,14 @@ impl AnimationBase {
             sprite_width: aabb.width(),
             sprite_height: aabb.height(),
             last_frame: 0,
+            frame_duration: DEFAULT_FRAME_DURATION,
+            events: [AnimationEvents::NONE; MAX_EVENT_FRAMES],
         }
     }
+
+    pub fn frame_events(&self, frame: u8) -> AnimationEvents {
+        self.events.get(frame as usize).copied().unwrap_or(AnimationEvents::NONE)
+    }
 }
 
 #[derive(Default, Copy, Clone)]
@@ -180,10 +228,14 @@ pub struct AnimationsBundle {
 
 impl AnimationsBundle {
 
+    /// Each line is `name;frame_count;left;top;right;bottom;frame_duration;events;`
+    /// `frame_duration` is in milliseconds and defaults to `DEFAULT_FRAME_DURATION` if empty.
+    /// `events` is a list of `frame:event` separated by spaces (ex: `1:footstep 4:footstep`). Frames start at 0.
     pub fn load_animations(&mut self, source_csv: &str) -> Result<(), Error> {
         fn parse(v: &str) -> f32 { str::parse::<f32>(v).unwrap_or(0.0) }
 
-        split_csv::<6, _>(source_csv, |args| {
+        let mut error = None;
+        split_csv::<8, _>(source_csv, |args| {
             let name = args[0];
             let frame_count = parse(args[1]);
             let left = parse(args[2]);
@@ -192,14 +244,26 @@ impl AnimationsBundle {
             let bottom = parse(args[5]);
             let sprite_width = (right-left) / frame_count;
             let sprite_height = bottom - top;
-            let animation = AnimationBase {
+            let frame_duration = args.get(6)
+                .and_then(|value| str::parse::<f32>(value.trim()).ok() )
+                .unwrap_or(DEFAULT_FRAME_DURATION);
+
+            let mut animation = AnimationBase {
                 x: left,
                 y: top,
                 sprite_width,
                 sprite_height,
                 last_frame: (frame_count as u8) - 1,
+                frame_duration,
+                events: [AnimationEvents::NONE; MAX_EVENT_FRAMES],
             };
 
+            if let Some(events) = args.get(7) {
+                if let Err(e) = parse_events(&mut animation, events) {
+                    merge_error(&mut error, assets_err!("Failed to parse the events of animation {:?}: {}", name, e));
+                }
+            }
+
             let sprite_type = name.split('_').next().unwrap_or("");
             match sprite_type {
                 "gobindynamite" => self.dynamite_goblin.set_animation_by_name(name, animation),
@@ -213,7 +277,28 @@ impl AnimationsBundle {
             };
         });
 
-        Ok(())
+        match error {
+            Some(e) => Err(e),
+            None => Ok(())
+        }
+    }
+
+}
+
+fn parse_events(animation: &mut AnimationBase, events: &str) -> Result<(), String> {
+    for event in events.split_whitespace() {
+        let (frame, name) = event.split_once(':')
+            .ok_or_else(|| format!("{:?} must be written as frame:event", event) )?;
+
+        let frame = str::parse::<u8>(frame).ok()
+            .filter(|&frame| frame <= animation.last_frame && (frame as usize) < MAX_EVENT_FRAMES )
+            .ok_or_else(|| format!("{:?} is not a valid frame", frame) )?;
+
+        let event = AnimationEvents::from_name(name)
+            .ok_or_else(|| format!("Unknown event {:?}", name) )?;
+
+        animation.events[frame as usize].insert(event);
     }
 
+    Ok(())
 }
@@ -24,22 +24,55 @@ pub struct AtlasGlyph {
     pub plane_bound: [f32; 4],
 }
 
+/// Kerning adjustment between two characters, in em
+#[repr(C)]
+#[derive(Copy, Clone, Default)]
+pub struct AtlasKerning {
+    pub unicode1: u32,
+    pub unicode2: u32,
+    pub advance: f32,
+}
+
 #[derive(Copy, Clone, Default, Debug)]
 pub struct ComputedGlyph {
     pub position: AABB,
     pub texcoord: AABB,
+    /// Index of the font of the glyph in its font chain. `0` is the primary font, `1` is the fallback font. See `Fonts`
+    pub font: u32,
+}
+
+/// Horizontal alignment of the lines in a multi-line text
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub enum TextAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Copy, Clone, Default, Debug)]
+pub struct TextLine {
+    /// Bounds of the line relative to the text origin
+    pub bounds: AABB,
+    /// Index of the first glyph of the line in `TextMetrics::glyphs`
+    pub glyph_start: u32,
+    pub glyph_count: u32,
 }
 
 #[derive(Default)]
 pub struct TextMetrics {
     pub size: Size<f32>,
     pub glyphs: Vec<ComputedGlyph>,
+    pub lines: Vec<TextLine>,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct FontAsset {
     pub info: AtlasInfo,
+    /// Glyphs sorted by unicode codepoint
     pub glyphs: Vec<AtlasGlyph>,
+    /// Kerning pairs sorted by (unicode1, unicode2)
+    pub kerning: Vec<AtlasKerning>,
     pub texture_id: u32,
 }
 
@@ -51,34 +84,147 @@ impl FontAsset {
             return Err(assets_err!("Failed to parse font atlas data. Data must be aligned to 4 bytes"));
         }
 
+        if bytes.len() < size_of::<AtlasInfo>() {
+            return Err(assets_err!("Failed to parse font atlas data. Missing atlas info"));
+        }
+
         let info = unsafe { *(bytes.as_ptr() as *const AtlasInfo) };
+        let glyph_count = info.glyph_count as usize;
+        let glyphs_end = size_of::<AtlasInfo>() + (size_of::<AtlasGlyph>() * glyph_count);
+        if bytes.len() < glyphs_end {
+            return Err(assets_err!("Failed to parse font atlas data. Expected {} glyphs", glyph_count));
+        }
 
         let glyph_ptr = unsafe { bytes.as_ptr().add(size_of::<AtlasInfo>()) as *const AtlasGlyph };
-        let mut glyphs = vec![Default::default(); info.glyph_max as usize];
-        for i in 0..(info.glyph_count as usize) {
-            let glyph: AtlasGlyph = unsafe { glyph_ptr.add(i).read() };
-            glyphs[glyph.unicode as usize] = glyph;
+        let mut glyphs = Vec::with_capacity(glyph_count);
+        for i in 0..glyph_count {
+            glyphs.push(unsafe { glyph_ptr.add(i).read() });
+        }
+
+        glyphs.sort_unstable_by_key(|glyph| glyph.unicode);
+
+        // Kerning pairs are optional. They are stored after the glyphs, prefixed by their count
+        let mut kerning = Vec::new();
+        if bytes.len() >= glyphs_end + size_of::<u32>() {
+            let kerning_count = unsafe { (bytes.as_ptr().add(glyphs_end) as *const u32).read() } as usize;
+            let kerning_start = glyphs_end + size_of::<u32>();
+            if bytes.len() < kerning_start + (size_of::<AtlasKerning>() * kerning_count) {
+                return Err(assets_err!("Failed to parse font atlas data. Expected {} kerning pairs", kerning_count));
+            }
+
+            let kerning_ptr = unsafe { bytes.as_ptr().add(kerning_start) as *const AtlasKerning };
+            kerning.reserve_exact(kerning_count);
+            for i in 0..kerning_count {
+                kerning.push(unsafe { kerning_ptr.add(i).read() });
+            }
+
+            kerning.sort_unstable_by_key(|pair| (pair.unicode1, pair.unicode2));
         }
 
         let data = FontAsset {
             info,
             glyphs,
+            kerning,
             texture_id,
         };
 
         Ok(data)
     }
 
-    /// Compute the bounds of character `c` at scale `scale` into `glyph`. Return the advance of the glyph
-    pub fn compute_glyph(&self, c: &str, scale: f32, glyph: &mut ComputedGlyph) -> f32 {
-        // Multi characters glyph not supported
-        let chr = match c.len() == 1 {
-            true => c.chars().next().unwrap_or('?'),
-            false => '?'
-        };
+    /// Returns the atlas glyph of character `chr`, if the font has one
+    pub fn glyph(&self, chr: char) -> Option<&AtlasGlyph> {
+        let unicode = chr as u32;
+        self.glyphs.binary_search_by_key(&unicode, |glyph| glyph.unicode)
+            .ok()
+            .map(|index| &self.glyphs[index])
+    }
 
-        let atlas_height = self.info.height;
-        let atlas_glyph = self.glyphs.get(chr as usize).copied().unwrap_or_default();
+    /// Returns the kerning adjustment (in em) to apply between `left` and `right`
+    pub fn kerning(&self, left: char, right: char) -> f32 {
+        let key = (left as u32, right as u32);
+        match self.kerning.binary_search_by_key(&key, |pair| (pair.unicode1, pair.unicode2)) {
+            Ok(index) => self.kerning[index].advance,
+            Err(_) => 0.0
+        }
+    }
+
+}
+
+/// Name of the font used for the characters missing from the primary font (ex: greek and cyrillic)
+pub const FALLBACK_FONT: &str = "noto_sans";
+
+/// The fonts used by the game. Characters missing from `roboto` are looked up in `fallback`.
+/// The engine binds one texture per font of the chain, so the chain is limited to a single fallback font.
+#[derive(Default, Clone)]
+pub struct Fonts {
+    // Assets are shared with the gui system
+    pub roboto: FontAsset,
+    /// Empty if the fallback font is not in the assets bundle
+    pub fallback: FontAsset,
+}
+
+impl Fonts {
+
+    /// Compute the layout of `text` on a single line using the font chain. Explicit line breaks (`\n`) are still honored.
+    pub fn compute_text_metrics(&self, text: &str, scale: f32) -> TextMetrics {
+        self.compute_multiline_text_metrics(text, scale, f32::INFINITY, TextAlign::Left)
+    }
+
+    /// Compute the layout of `text` using the font chain. Words are wrapped on a new line if a line would be wider than `max_width`.
+    /// Lines are aligned in the bounds of the widest line using `align`.
+    pub fn compute_multiline_text_metrics(&self, text: &str, scale: f32, max_width: f32, align: TextAlign) -> TextMetrics {
+        let chain = FontChain { primary: &self.roboto, fallback: &self.fallback };
+        chain.compute_text_metrics(text, scale, max_width, align)
+    }
+
+    /// Returns the horizontal position of a caret placed before each grapheme of `text`, and after the last one.
+    /// `text` is laid out on a single line, like in `compute_text_metrics`.
+    pub fn compute_caret_offsets(&self, text: &str, scale: f32) -> Vec<f32> {
+        let chain = FontChain { primary: &self.roboto, fallback: &self.fallback };
+        chain.compute_caret_offsets(text, scale)
+    }
+
+}
+
+impl crate::store::SaveAndLoad for Fonts {
+    fn save(&self, writer: &mut crate::store::SaveFileWriter) {
+        writer.save(&self.roboto);
+        writer.save(&self.fallback);
+    }
+
+    fn load(reader: &mut crate::store::SaveFileReader) -> Self {
+        Fonts { 
+            roboto: reader.load(),
+            fallback: reader.load(),
+        }
+    }
+
+}
+
+struct FontChain<'a> {
+    primary: &'a FontAsset,
+    fallback: &'a FontAsset,
+}
+
+impl<'a> FontChain<'a> {
+
+    /// Returns the first font of the chain that has a glyph for `chr` and its index in the chain.
+    /// Missing characters are rendered using the `?` of the primary font.
+    fn find_glyph(&self, chr: char) -> (u32, &'a FontAsset, AtlasGlyph) {
+        if let Some(glyph) = self.primary.glyph(chr) {
+            return (0, self.primary, *glyph);
+        }
+
+        if let Some(glyph) = self.fallback.glyph(chr) {
+            return (1, self.fallback, *glyph);
+        }
+
+        (0, self.primary, self.primary.glyph('?').copied().unwrap_or_default())
+    }
+
+    fn compute_glyph(&self, chr: char, scale: f32, glyph: &mut ComputedGlyph) -> f32 {
+        let (font_index, font, atlas_glyph) = self.find_glyph(chr);
+        let atlas_height = font.info.height;
 
         glyph.position.left = scale * atlas_glyph.plane_bound[0];
         glyph.position.top = scale * atlas_glyph.plane_bound[3];
@@ -90,52 +236,181 @@ impl FontAsset {
         glyph.texcoord.right = atlas_glyph.atlas_bound[2];
         glyph.texcoord.bottom = atlas_height - atlas_glyph.atlas_bound[1];
 
+        glyph.font = font_index;
+
         atlas_glyph.advance * scale
     }
 
-    pub fn compute_text_metrics(&self, text: &str, scale: f32) -> TextMetrics {
+    /// Kerning between two characters. Only applied if both characters are rendered with the same font.
+    fn kerning(&self, left: char, right: char, scale: f32) -> f32 {
+        let (left_font, font, _) = self.find_glyph(left);
+        let (right_font, _, _) = self.find_glyph(right);
+        match left_font == right_font {
+            true => font.kerning(left, right) * scale,
+            false => 0.0
+        }
+    }
+
+    fn compute_caret_offsets(&self, text: &str, scale: f32) -> Vec<f32> {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let mut offsets = Vec::with_capacity(text.len() + 1);
+        let mut glyph = ComputedGlyph::default();
+        let mut previous_char: Option<char> = None;
+        let mut advance = 0.0;
+
+        offsets.push(0.0);
+
+        for g in text.graphemes(true) {
+            let chr = first_char(g);
+            if let Some(previous_char) = previous_char {
+                advance += self.kerning(previous_char, chr, scale);
+            }
+
+            advance += self.compute_glyph(chr, scale, &mut glyph);
+            previous_char = Some(chr);
+            offsets.push(advance);
+        }
+
+        offsets
+    }
+
+    fn compute_text_metrics(&self, text: &str, scale: f32, max_width: f32, align: TextAlign) -> TextMetrics {
         use unicode_segmentation::UnicodeSegmentation;
-        
+
         let mut glyphs = Vec::with_capacity(text.len());
+        let mut lines = Vec::with_capacity(1);
+        let mut line = TextLine::default();
+        let mut content_right = 0.0;
+        let mut wrapped = false;
         let mut advance = 0.0;
         let mut max_height = 0.0;
+        let mut previous_char: Option<char> = None;
         let mut glyph = ComputedGlyph::default();
-        for g in text.graphemes(true) {
-            let a = self.compute_glyph(g, scale, &mut glyph);
-            glyph.position.left += advance;
-            glyph.position.right += advance;
-    
-            advance += a;
-            max_height = f32::max(max_height, glyph.position.bottom);
 
-            glyphs.push(glyph);
+        // Closes the current line and starts a new one
+        let mut new_line = |glyphs: &Vec<ComputedGlyph>, line: &mut TextLine, advance: &mut f32, previous_char: &mut Option<char>| {
+            line.glyph_count = glyphs.len() as u32 - line.glyph_start;
+            lines.push(*line);
+            *line = TextLine { glyph_start: glyphs.len() as u32, ..Default::default() };
+            *advance = 0.0;
+            *previous_char = None;
+        };
+
+        for (paragraph_index, paragraph) in text.split('\n').enumerate() {
+            if paragraph_index > 0 {
+                new_line(&glyphs, &mut line, &mut advance, &mut previous_char);
+                content_right = 0.0;
+                wrapped = false;
+            }
+
+            for word in paragraph.split_word_bounds() {
+                let is_space = word.chars().all(char::is_whitespace);
+                if is_space && wrapped && advance == 0.0 {
+                    // Spaces are not rendered at the start of a wrapped line
+                    continue;
+                }
+
+                if !is_space && advance > 0.0 {
+                    let word_width: f32 = word.graphemes(true).map(|g| self.compute_glyph(first_char(g), scale, &mut glyph)).sum();
+                    if advance + word_width > max_width {
+                        line.bounds.right = content_right;
+                        new_line(&glyphs, &mut line, &mut advance, &mut previous_char);
+                        content_right = 0.0;
+                        wrapped = true;
+                    }
+                }
+
+                for g in word.graphemes(true) {
+                    let chr = first_char(g);
+                    let a = self.compute_glyph(chr, scale, &mut glyph);
+
+                    // Words longer than a whole line are split
+                    if !is_space && advance > 0.0 && advance + a > max_width {
+                        line.bounds.right = content_right;
+                        new_line(&glyphs, &mut line, &mut advance, &mut previous_char);
+                        content_right = 0.0;
+                        wrapped = true;
+                    }
+
+                    if let Some(previous_char) = previous_char {
+                        advance += self.kerning(previous_char, chr, scale);
+                    }
+
+                    glyph.position.left += advance;
+                    glyph.position.right += advance;
+
+                    advance += a;
+                    max_height = f32::max(max_height, glyph.position.bottom);
+                    previous_char = Some(chr);
+
+                    // Trailing spaces count in the width of a line, except on the lines ended by a wrap so that aligned lines stay flush
+                    line.bounds.right = glyph.position.right;
+                    if !is_space {
+                        content_right = glyph.position.right;
+                    }
+
+                    glyphs.push(glyph);
+                }
+            }
         }
 
-        // Second pass to align the glyph on the bottom
+        new_line(&glyphs, &mut line, &mut advance, &mut previous_char);
+
+        let width = lines.iter().fold(0.0, |width, line| f32::max(width, line.bounds.right));
+        let line_step = match self.primary.info.line_height > 0.0 {
+            true => self.primary.info.line_height * scale,
+            false => max_height
+        };
+
+        // Second pass to align the glyph on the bottom of their line and apply the horizontal alignment
         // This also flips the y axis
-        for glyph in glyphs.iter_mut() {
-            glyph.position.top = max_height - glyph.position.top;
-            glyph.position.bottom = max_height - glyph.position.bottom;
+        for (index, line) in lines.iter_mut().enumerate() {
+            let line_width = line.bounds.right;
+            let x = match align {
+                TextAlign::Left => 0.0,
+                TextAlign::Center => (width - line_width) / 2.0,
+                TextAlign::Right => width - line_width,
+            };
+            let y = (index as f32) * line_step;
+
+            line.bounds = AABB { left: x, top: y, right: x + line_width, bottom: y + max_height };
+
+            let glyph_start = line.glyph_start as usize;
+            let glyph_end = glyph_start + (line.glyph_count as usize);
+            for glyph in glyphs[glyph_start..glyph_end].iter_mut() {
+                glyph.position.left += x;
+                glyph.position.right += x;
+                glyph.position.top = y + max_height - glyph.position.top;
+                glyph.position.bottom = y + max_height - glyph.position.bottom;
+            }
         }
 
         let size = match text.len() {
             0 => size(0.0, 0.0),
-            _ => size(glyph.position.right, max_height)
+            _ => size(width, ((lines.len() - 1) as f32 * line_step) + max_height)
         };
 
         TextMetrics { 
             size,
-            glyphs
+            glyphs,
+            lines,
         }
     }
 
 }
 
+/// Graphemes made of multiple characters (ex: a letter followed by a combining accent) are rendered using their first character
+fn first_char(grapheme: &str) -> char {
+    grapheme.chars().next().unwrap_or('?')
+}
+
 
 impl crate::store::SaveAndLoad for FontAsset {
     fn save(&self, writer: &mut crate::store::SaveFileWriter) {
         writer.write(&self.info);
         writer.write_slice(&self.glyphs);
+        writer.write_slice(&self.kerning);
         writer.write_u32(self.texture_id);
     }
 
@@ -143,6 +418,7 @@ impl crate::store::SaveAndLoad for FontAsset {
         FontAsset { 
             info: reader.read(),
             glyphs: reader.read_vec(),
+            kerning: reader.read_vec(),
             texture_id: reader.read_u32(),
         }
     }
@@ -153,14 +429,17 @@ impl crate::store::SaveAndLoad for TextMetrics {
     fn save(&self, writer: &mut crate::store::SaveFileWriter) {
         writer.write(&self.size);
         writer.write_slice(&self.glyphs);
+        writer.write_slice(&self.lines);
     }
 
     fn load(reader: &mut crate::store::SaveFileReader) -> Self {
         let size: Size<f32> = reader.read();
         let glyphs: Vec<ComputedGlyph> = reader.read_vec();
+        let lines: Vec<TextLine> = reader.read_vec();
         TextMetrics {
             size,
-            glyphs
+            glyphs,
+            lines,
         }
     }
 }
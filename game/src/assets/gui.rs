@@ -1,8 +1,54 @@
+use crate::gui::GuiPadding;
 use crate::shared::AABB;
 
+/// Maps the sprite names of gui.csv to the fields of a bundle. `$bundle` is a reference to the bundle, mutable or not.
+macro_rules! sprite_field {
+    ($name:expr, $($bundle:tt)+) => {
+        match $name {
+            "info_panel" => Some($($bundle)+.info_panel),
+            "pawn_portrait" => Some($($bundle)+.pawn_portrait),
+            "warrior_portrait" => Some($($bundle)+.warrior_portrait),
+            "archer_portrait" => Some($($bundle)+.archer_portrait),
+            "gobindynamite_portrait" => Some($($bundle)+.goblin_dynamite_portrait),
+            "gobintorch_portrait" => Some($($bundle)+.goblin_torch_portrait),
+            "sheep_portrait" => Some($($bundle)+.sheep_portrait),
+            "gold_icon" => Some($($bundle)+.gold_icon),
+            "meat_icon" => Some($($bundle)+.meat_icon),
+            "wood_icon" => Some($($bundle)+.wood_icon),
+            "life_icon" => Some($($bundle)+.life_icon),
+            "button" => Some($($bundle)+.button),
+            "button_hover" => Some($($bundle)+.button_hover),
+            "button_pressed" => Some($($bundle)+.button_pressed),
+            "move_icon" => Some($($bundle)+.move_icon),
+            "stop_icon" => Some($($bundle)+.stop_icon),
+            "attack_icon" => Some($($bundle)+.attack_icon),
+            "harvest_icon" => Some($($bundle)+.harvest_icon),
+            "build_icon" => Some($($bundle)+.build_icon),
+            "cancel_icon" => Some($($bundle)+.cancel_icon),
+            "progress_bar" => Some($($bundle)+.progress_bar),
+            "progress_bar_fill" => Some($($bundle)+.progress_bar_fill),
+            "scrollbar" => Some($($bundle)+.scrollbar),
+            "scrollbar_thumb" => Some($($bundle)+.scrollbar_thumb),
+            _ => None
+        }
+    };
+}
+
+/// Maps the sprite names of gui.csv to the nine-slice borders of a bundle
+macro_rules! nine_slice_field {
+    ($name:expr, $($bundle:tt)+) => {
+        match $name {
+            "info_panel" => Some($($bundle)+.info_panel_insets),
+            _ => None
+        }
+    };
+}
+
 #[derive(Copy, Clone, Default)]
 pub struct GuiBundle {
     pub info_panel: AABB,
+    /// Nine-slice borders of the info panel, in pixels from each side of the image
+    pub info_panel_insets: GuiPadding,
     pub pawn_portrait: AABB,
     pub warrior_portrait: AABB,
     pub archer_portrait: AABB,
@@ -13,6 +59,19 @@ pub struct GuiBundle {
     pub meat_icon: AABB,
     pub wood_icon: AABB,
     pub life_icon: AABB,
+    pub button: AABB,
+    pub button_hover: AABB,
+    pub button_pressed: AABB,
+    pub move_icon: AABB,
+    pub stop_icon: AABB,
+    pub attack_icon: AABB,
+    pub harvest_icon: AABB,
+    pub build_icon: AABB,
+    pub cancel_icon: AABB,
+    pub progress_bar: AABB,
+    pub progress_bar_fill: AABB,
+    pub scrollbar: AABB,
+    pub scrollbar_thumb: AABB,
 }
 
 impl GuiBundle {
@@ -22,32 +81,51 @@ impl GuiBundle {
             str::parse::<f32>(v).unwrap_or(0.0)
         }
 
-        crate::shared::split_csv::<6, _>(csv, |args| {
+        // Sprites with nine-slice borders have 4 extra values: the left, top, right and bottom insets
+        crate::shared::split_csv::<10, _>(csv, |args| {
             let name = args[0];
             let left = parse(args[2]);
             let top = parse(args[3]);
             let right = parse(args[4]);
             let bottom = parse(args[5]);
 
-            let aabb = match name {
-                "info_panel" => &mut self.info_panel,
-                "pawn_portrait" => &mut self.pawn_portrait,
-                "warrior_portrait" => &mut self.warrior_portrait,
-                "archer_portrait" => &mut self.archer_portrait,
-                "gobindynamite_portrait" => &mut self.goblin_dynamite_portrait,
-                "gobintorch_portrait" => &mut self.goblin_torch_portrait,
-                "sheep_portrait" => &mut self.sheep_portrait,
-                "gold_icon" => &mut self.gold_icon,
-                "meat_icon" => &mut self.meat_icon,
-                "wood_icon" => &mut self.wood_icon,
-                "life_icon" => &mut self.life_icon,
-                _ => {
+            let aabb = match self.sprite_mut(name) {
+                Some(aabb) => aabb,
+                None => {
                     return;
                 }
             };
 
             *aabb = AABB { left, top, right, bottom };
+
+            let insets = match self.nine_slice_mut(name) {
+                Some(insets) => insets,
+                None => {
+                    return;
+                }
+            };
+
+            let inset = |index: usize| args.get(index).map(|v| parse(v) ).unwrap_or(0.0);
+            *insets = GuiPadding { left: inset(6), top: inset(7), right: inset(8), bottom: inset(9) };
         });
     }
 
+    /// Returns the texture coordinates of the sprite named `name` in gui.csv
+    pub fn sprite(&self, name: &str) -> Option<AABB> {
+        sprite_field!(name, &self).copied()
+    }
+
+    /// Returns the nine-slice borders of the sprite named `name` in gui.csv, if it has any
+    pub fn nine_slice(&self, name: &str) -> Option<GuiPadding> {
+        nine_slice_field!(name, &self).copied()
+    }
+
+    fn sprite_mut(&mut self, name: &str) -> Option<&mut AABB> {
+        sprite_field!(name, &mut self)
+    }
+
+    fn nine_slice_mut(&mut self, name: &str) -> Option<&mut GuiPadding> {
+        nine_slice_field!(name, &mut self)
+    }
+
 }
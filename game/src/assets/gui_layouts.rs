@@ -0,0 +1,158 @@
+//! Gui layouts described in text assets. Layouts are listed in the assets bundle as `LAYOUT;name;path;`
+//!
+//! Each line of a layout is a node: the node type followed by `key=value` attributes.
+//! A line ending with `{` opens the children of the node. The children are closed by a line with a single `}`.
+//! Everything after a `#` is a comment. See `GuiBuilder::layout` for the attributes of each node type.
+use crate::error::Error;
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GuiLayoutNodeType {
+    Frame,
+    Group,
+    ScrollFrame,
+    Spacer,
+    Label,
+    Image,
+    Button,
+    ProgressBar,
+    TextInput,
+    /// Builds its children `count` times
+    Repeat,
+}
+
+impl GuiLayoutNodeType {
+
+    fn from_name(name: &str) -> Option<Self> {
+        let ty = match name {
+            "frame" => Self::Frame,
+            "group" => Self::Group,
+            "scroll" => Self::ScrollFrame,
+            "spacer" => Self::Spacer,
+            "label" => Self::Label,
+            "image" => Self::Image,
+            "button" => Self::Button,
+            "progress" => Self::ProgressBar,
+            "input" => Self::TextInput,
+            "repeat" => Self::Repeat,
+            _ => { return None; }
+        };
+
+        Some(ty)
+    }
+
+    fn has_children(&self) -> bool {
+        matches!(self, Self::Frame | Self::Group | Self::ScrollFrame | Self::Repeat)
+    }
+
+}
+
+#[derive(Clone)]
+pub struct GuiLayoutNode {
+    pub ty: GuiLayoutNodeType,
+    /// Line of the node in the layout source. Used in error messages.
+    pub line: u32,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<GuiLayoutNode>,
+}
+
+impl GuiLayoutNode {
+
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(key, _)| key == name )
+            .map(|(_, value)| value.as_str() )
+    }
+
+}
+
+#[derive(Clone, Default)]
+pub struct GuiLayoutAsset {
+    /// Text of the layout. Saved instead of the nodes, which are parsed again on load.
+    pub source: String,
+    pub roots: Vec<GuiLayoutNode>,
+}
+
+impl GuiLayoutAsset {
+
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut roots = Vec::new();
+        let mut open_nodes: Vec<GuiLayoutNode> = Vec::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = (index + 1) as u32;
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line
+            };
+
+            let mut tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.is_empty() {
+                continue;
+            }
+
+            if tokens == ["}"] {
+                let node = open_nodes.pop()
+                    .ok_or_else(|| assets_err!("Line {}: unexpected '}}' in gui layout", line_number) )?;
+
+                match open_nodes.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => roots.push(node),
+                }
+
+                continue;
+            }
+
+            let opens_children = tokens.last() == Some(&"{");
+            if opens_children {
+                tokens.pop();
+            }
+
+            let ty = GuiLayoutNodeType::from_name(tokens[0])
+                .ok_or_else(|| assets_err!("Line {}: unknown gui layout node {:?}", line_number, tokens[0]) )?;
+
+            if opens_children && !ty.has_children() {
+                return Err(assets_err!("Line {}: gui layout node {:?} cannot have children", line_number, tokens[0]));
+            }
+
+            let mut attributes = Vec::with_capacity(tokens.len() - 1);
+            for token in &tokens[1..] {
+                let (key, value) = token.split_once('=')
+                    .ok_or_else(|| assets_err!("Line {}: expected a key=value attribute, got {:?}", line_number, token) )?;
+                attributes.push((key.to_string(), value.to_string()));
+            }
+
+            let node = GuiLayoutNode { ty, line: line_number, attributes, children: Vec::new() };
+            if opens_children {
+                open_nodes.push(node);
+            } else {
+                match open_nodes.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => roots.push(node),
+                }
+            }
+        }
+
+        if let Some(node) = open_nodes.last() {
+            return Err(assets_err!("Line {}: gui layout node is never closed", node.line));
+        }
+
+        Ok(GuiLayoutAsset {
+            source: source.to_string(),
+            roots,
+        })
+    }
+
+}
+
+impl crate::store::SaveAndLoad for GuiLayoutAsset {
+
+    fn save(&self, writer: &mut crate::store::SaveFileWriter) {
+        writer.write_str(&self.source);
+    }
+
+    fn load(reader: &mut crate::store::SaveFileReader) -> Self {
+        // The source was valid when it was saved
+        GuiLayoutAsset::parse(reader.read_str()).unwrap_or_default()
+    }
+
+}
@@ -1,4 +1,4 @@
-use crate::assets::animations::AnimationBase;
+use crate::assets::animations::{AnimationBase, DEFAULT_FRAME_DURATION};
 use crate::shared::AABB;
 
 #[allow(dead_code)]
@@ -22,6 +22,11 @@ pub struct ResourcesBundle {
     pub gold_spawn: AnimationBase,
     pub meat_spawn: AnimationBase,
     pub wood_spawn: AnimationBase,
+
+    pub particle_dust: AABB,
+    pub particle_wood_chip: AABB,
+    pub particle_spark: AABB,
+    pub particle_smoke: AABB,
 }
 
 impl ResourcesBundle {
@@ -53,6 +58,8 @@ impl ResourcesBundle {
                         sprite_width,
                         sprite_height,
                         last_frame: (frame_count as u8) - 1,
+                        frame_duration: DEFAULT_FRAME_DURATION,
+                        events: Default::default(),
                     };
                 }
             }
@@ -71,6 +78,10 @@ impl ResourcesBundle {
             "tree_stump" => Some(&mut self.tree_stump),
             "arrow" => Some(&mut self.arrow),
             "arrow_stuck" => Some(&mut self.arrow_stuck),
+            "particle_dust" => Some(&mut self.particle_dust),
+            "particle_wood_chip" => Some(&mut self.particle_wood_chip),
+            "particle_spark" => Some(&mut self.particle_spark),
+            "particle_smoke" => Some(&mut self.particle_smoke),
             _ => None,
         }
     }
@@ -0,0 +1,156 @@
+//! Player facing text. Each language has its own csv listed in the assets bundle as `CSV;strings_[language];path;`
+//!
+//! Each line of a string table is `key;text;`. Texts can have format arguments written as `{0}`, `{1}`, etc.
+use std::fmt::{Display, Write};
+use fnv::FnvHashMap;
+use crate::error::Error;
+
+/// Language used when no language is selected, and when a key is missing from the selected language
+pub const DEFAULT_LANGUAGE: &str = "en";
+
+#[derive(Clone, Default)]
+pub struct StringTable {
+    pub language: String,
+    pub strings: FnvHashMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+pub struct Strings {
+    pub tables: Vec<StringTable>,
+    /// Index of the selected language in `tables`
+    pub language: usize,
+}
+
+impl Strings {
+
+    pub fn load(&mut self, language: &str, csv: &str) -> Result<(), Error> {
+        let mut table = StringTable {
+            language: language.to_string(),
+            strings: FnvHashMap::default(),
+        };
+
+        crate::shared::split_csv::<3, _>(csv, |args| {
+            table.strings.insert(args[0].to_string(), args[1].to_string());
+        });
+
+        if table.strings.is_empty() {
+            return Err(assets_err!("String table {:?} is empty", language));
+        }
+
+        let index = match self.tables.iter().position(|t| t.language == language) {
+            Some(index) => {
+                self.tables[index] = table;
+                index
+            },
+            None => {
+                self.tables.push(table);
+                self.tables.len() - 1
+            }
+        };
+
+        if language == DEFAULT_LANGUAGE {
+            self.language = index;
+        }
+
+        Ok(())
+    }
+
+    pub fn language(&self) -> &str {
+        self.tables.get(self.language)
+            .map(|table| table.language.as_str() )
+            .unwrap_or(DEFAULT_LANGUAGE)
+    }
+
+    pub fn set_language(&mut self, language: &str) -> Result<(), Error> {
+        self.language = self.tables.iter().position(|table| table.language == language)
+            .ok_or_else(|| assets_err!("No string table for language {:?}", language) )?;
+
+        Ok(())
+    }
+
+    /// Returns the text of `key` in the selected language. Falls back to the default language, then to the key itself.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        let selected = self.tables.get(self.language).and_then(|table| table.strings.get(key) );
+        let text = selected.or_else(|| {
+            self.tables.iter()
+                .find(|table| table.language == DEFAULT_LANGUAGE )
+                .and_then(|table| table.strings.get(key) )
+        });
+
+        match text {
+            Some(text) => text.as_str(),
+            None => key
+        }
+    }
+
+    /// Returns the text of `key` with the `{index}` arguments replaced by `args`
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        let mut text = self.text(key);
+        let mut out = String::with_capacity(text.len() + 8);
+
+        while let Some(start) = text.find('{') {
+            out.push_str(&text[..start]);
+            text = &text[start..];
+
+            let argument = text.find('}')
+                .and_then(|end| text[1..end].parse::<usize>().ok().map(|index| (index, end)) );
+
+            match argument {
+                Some((index, end)) => {
+                    if let Some(arg) = args.get(index) {
+                        let _ = write!(out, "{}", arg);
+                    }
+                    text = &text[end+1..];
+                },
+                None => {
+                    out.push('{');
+                    text = &text[1..];
+                }
+            }
+        }
+
+        out.push_str(text);
+        out
+    }
+
+}
+
+impl crate::store::SaveAndLoad for Strings {
+
+    fn save(&self, writer: &mut crate::store::SaveFileWriter) {
+        writer.write_u32(self.language as u32);
+        writer.write_u32(self.tables.len() as u32);
+        for table in self.tables.iter() {
+            writer.write_str(&table.language);
+            writer.write_u32(table.strings.len() as u32);
+            for (key, text) in table.strings.iter() {
+                writer.write_str(key);
+                writer.write_str(text);
+            }
+        }
+    }
+
+    fn load(reader: &mut crate::store::SaveFileReader) -> Self {
+        let language = reader.read_u32() as usize;
+        let tables_count = reader.read_u32();
+        let mut tables = Vec::with_capacity(tables_count as usize);
+        for _ in 0..tables_count {
+            let mut table = StringTable {
+                language: reader.read_str().to_string(),
+                strings: FnvHashMap::default(),
+            };
+
+            let strings_count = reader.read_u32();
+            for _ in 0..strings_count {
+                let key = reader.read_str().to_string();
+                let text = reader.read_str().to_string();
+                table.strings.insert(key, text);
+            }
+
+            tables.push(table);
+        }
+
+        Strings { tables, language }
+    }
+
+}
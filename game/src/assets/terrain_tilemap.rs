@@ -10,6 +10,7 @@ pub enum TerrainCell {
 }
 
 /// Maps [TerrainCell] to their texture coordinate in the terrain texture 
+#[derive(Clone)]
 pub struct TerrainTilemap {
     pub cells_texture_coordinates: Vec<[f32; 2]>
 }
@@ -7,6 +7,8 @@ pub mod sheep;
 pub mod spawn_resources;
 pub mod arrow;
 
+use crate::assets::AnimationBase;
+use crate::world::BaseAnimated;
 use crate::DemoGame;
 
 #[derive(Copy, Clone)]
@@ -16,6 +18,8 @@ pub enum BehaviourState {
 }
 
 pub fn update(game: &mut DemoGame) {
+    update_animations(game);
+
     run_pawn_behaviour(game);
     run_warrior_behaviour(game);
     run_archers_behaviour(game);
@@ -26,6 +30,65 @@ pub fn update(game: &mut DemoGame) {
     if game.data.world.resources_spawn.len() > 0 {
         run_resource_spawn_behaviour(game);
     }
+
+    update_effects(game);
+
+    // Index the new unit positions for the next frame queries
+    game.data.world.update_static_grid();
+    game.data.world.update_dynamic_grid();
+}
+
+/// Advances the animations of every actor. Behaviours react to the animation events of the frames entered during this update.
+fn update_animations(game: &mut DemoGame) {
+    let delta = game.data.global.frame_delta;
+    let world = &mut game.data.world;
+    let animations = &game.data.assets.animations;
+    let resources = &game.data.assets.resources;
+
+    // Second value is the animation played after an `AnimationMode::OnceThenIdle` animation
+    let groups: [(&mut [BaseAnimated], Option<AnimationBase>); 8] = [
+        (&mut world.pawns, Some(animations.pawn.idle)),
+        (&mut world.warriors, Some(animations.warrior.idle)),
+        (&mut world.archers, Some(animations.archer.idle)),
+        (&mut world.torch_goblins, Some(animations.torch_goblin.idle)),
+        (&mut world.tnt_goblins, Some(animations.dynamite_goblin.idle)),
+        (&mut world.sheeps, Some(animations.sheep.idle)),
+        (&mut world.resources_spawn, None),
+        (&mut world.trees, Some(resources.tree_idle)),
+    ];
+
+    for (actors, idle) in groups {
+        for actor in actors.iter_mut() {
+            actor.update_animation(delta, idle);
+        }
+    }
+}
+
+/// Moves the particles and the floating texts, expires the overlay markers, and spawns dust under the units on their footstep frames
+fn update_effects(game: &mut DemoGame) {
+    use crate::assets::AnimationEvents;
+    use crate::world::ParticleType;
+
+    let data = &mut game.data;
+    let world = &mut data.world;
+
+    let groups: [&[BaseAnimated]; 5] = [
+        &world.pawns,
+        &world.warriors,
+        &world.archers,
+        &world.torch_goblins,
+        &world.tnt_goblins,
+    ];
+
+    for units in groups {
+        for unit in units.iter().filter(|unit| !unit.deleted && unit.events.contains(AnimationEvents::FOOTSTEP) ) {
+            world.particles.burst(ParticleType::Dust, unit.position, 1);
+        }
+    }
+
+    world.particles.update(data.global.frame_delta);
+    world.floating_texts.update(data.global.frame_delta);
+    world.expire_overlay_markers(data.global.time);
 }
 
 fn run_pawn_behaviour(game: &mut DemoGame) {
@@ -3,7 +3,8 @@ pub mod shoot;
 
 use crate::behaviour::BehaviourState;
 use crate::shared::Position;
-use crate::world::WorldObject;
+use crate::world::{WorldObject, WorldObjectType};
+use crate::world::queries::{QueryFilter, WorldObjectOwner};
 use crate::DemoGameData;
 
 #[derive(Copy, Clone)]
@@ -31,12 +32,21 @@ impl ArcherBehaviour {
 
 }
 
+/// Idle archers shoot the closest enemy structure in range on their own
 pub fn idle(game: &mut DemoGameData, archer_index: usize) {
+    const TARGETS: QueryFilter = QueryFilter::types(&[WorldObjectType::Structure]).with_owner(WorldObjectOwner::Enemy);
+
     let world = &mut game.world;
     let behaviour = &mut world.archers_behaviour[archer_index];
     if let BehaviourState::Initial = behaviour.state {
         let archer = &mut world.archers[archer_index];
-        archer.animation = game.assets.animations.archer.idle;
+        archer.play(game.assets.animations.archer.idle);
         behaviour.state = BehaviourState::Running(0);
     }
+
+    let position = world.archers[archer_index].position;
+    if let Some(target) = world.closest_object(position, shoot::MAX_SHOOTING_DISTANCE, TARGETS) {
+        let archer = WorldObject { id: archer_index as u32, ty: WorldObjectType::Archer };
+        shoot::new(game, archer, target);
+    }
 }
@@ -38,7 +38,7 @@ pub fn process(game: &mut DemoGameData, archer_index: usize) {
 }
 
 fn init(game: &DemoGameData, params: &mut ArcherMoveParams) {
-    params.archer.animation = game.assets.animations.archer .walk;
+    params.archer.play(game.assets.animations.archer.walk);
     params.state = BehaviourState::Running(MOVING);
     moving(game, params);
 }
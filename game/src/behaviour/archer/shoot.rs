@@ -1,7 +1,8 @@
+use crate::assets::AnimationEvents;
 use crate::behaviour::BehaviourState;
 use crate::behaviour::behaviour_shared::{is_enemy_structure, move_to, elapsed};
 use crate::shared::{Position, AABB, pos};
-use crate::world::{BaseAnimated, WorldObject, WorldObjectType};
+use crate::world::{AnimationMode, BaseAnimated, WorldObject, WorldObjectType};
 use crate::DemoGameData;
 use super::{ArcherBehaviour, ArcherBehaviourType};
 
@@ -9,7 +10,7 @@ const MOVING: u8 = 0;
 const SHOOTING: u8 = 2;
 const PAUSE: u8 = 3;
 
-const MAX_SHOOTING_DISTANCE: f32 = 64.0 * 7.0;
+pub const MAX_SHOOTING_DISTANCE: f32 = 64.0 * 7.0;
 
 pub struct ArcherShootParams {
     archer: BaseAnimated,
@@ -73,15 +74,12 @@ pub fn process(game: &mut DemoGameData, archer_index: usize) {
 }
 
 fn init(game: &DemoGameData, params: &mut ArcherShootParams) {
-    params.archer.current_frame = 0;
-    
     if params.archer.position.distance(params.target_position) > MAX_SHOOTING_DISTANCE {
-        params.archer.animation = game.assets.animations.archer.walk;
-        params.archer.current_frame = 0;
+        params.archer.play(game.assets.animations.archer.walk);
         params.state = BehaviourState::Running(MOVING);
         moving(game, params);
     } else {
-        params.state = BehaviourState::Running(SHOOTING);
+        start_shot(game, params);
         shooting(game, params);
     }
 }
@@ -91,11 +89,16 @@ fn moving(game: &DemoGameData, params: &mut ArcherShootParams) {
     params.archer.flipped = params.archer.position.x > params.target_position.x;
 
     if params.archer.position.distance(params.target_position) < MAX_SHOOTING_DISTANCE {
-        params.archer.current_frame = 0;
-        params.state = BehaviourState::Running(SHOOTING);
+        start_shot(game, params);
     }
 }
 
+/// The shot animation is played once, then the archer goes back to idle. The aim direction is selected in `shooting`
+fn start_shot(game: &DemoGameData, params: &mut ArcherShootParams) {
+    params.archer.play_with_mode(game.assets.animations.archer.fire_h, AnimationMode::OnceThenIdle);
+    params.state = BehaviourState::Running(SHOOTING);
+}
+
 fn shooting(game: &DemoGameData, params: &mut ArcherShootParams) {
     fn select_animation(params: &ArcherShootParams) -> ShootingAnimation {
         let position = params.archer.position;
@@ -147,23 +150,25 @@ fn shooting(game: &DemoGameData, params: &mut ArcherShootParams) {
     }
 
     if params.archer.position.distance(params.target_position) > MAX_SHOOTING_DISTANCE {
-        params.archer.animation = game.assets.animations.archer.idle;
-        params.archer.current_frame = 0;
+        params.archer.play(game.assets.animations.archer.idle);
         params.last_timestamp = game.global.time as f32;
         params.state = BehaviourState::Running(PAUSE);
         return;
     }
 
-    set_aim_animation(game, params);
+    // The arrow leaves the bow on the frame tagged with the `release` event in `units.csv`
+    if params.archer.events.contains(AnimationEvents::RELEASE) {
+        params.spawn_arrow = true;
+    }
 
-    let animation_time = crate::ANIMATION_INTERVAL * 7.0;
-    if params.archer.current_frame == 7 && elapsed(game.global.time, params.last_timestamp as f64, animation_time) {
-        params.archer.animation = game.assets.animations.archer.idle;
-        params.archer.current_frame = 0;
+    // The archer is already back to idle when the shot completes
+    if params.archer.events.contains(AnimationEvents::FINISHED) {
         params.last_timestamp = game.global.time as f32;
         params.state = BehaviourState::Running(PAUSE);
-        params.spawn_arrow = true;
+        return;
     }
+
+    set_aim_animation(game, params);
 }
 
 fn pause(game: &DemoGameData, params: &mut ArcherShootParams) {
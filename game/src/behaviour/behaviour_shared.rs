@@ -1,6 +1,6 @@
 //! Shared logic between actions
 use crate::shared::{Position, pos};
-use crate::world::{WorldObject, WorldObjectType, StructureData};
+use crate::world::{WorldObject, WorldObjectType, StructureData, ParticleType, DAMAGE_TEXT_COLOR};
 use crate::DemoGameData;
 
 pub fn move_to(current: Position<f32>, target: Position<f32>, frame_delta: f32) -> Position<f32> {
@@ -85,40 +85,64 @@ pub fn is_enemy_structure(game: &DemoGameData, structure_index: usize) -> bool {
     is_enemy
 }
 
+/// Shows the damage taken by `obj` in a floating text over its sprite
+pub fn show_damage(game: &mut DemoGameData, obj: WorldObject, damage: u8) {
+    if damage == 0 {
+        return;
+    }
+
+    if let Some((position, sprite)) = game.world.object_bounds(obj) {
+        let text_position = pos(position.x, sprite.top);
+        game.world.floating_texts.add(&game.assets.fonts, &format!("-{}", damage), text_position, DAMAGE_TEXT_COLOR);
+    }
+}
+
 pub fn damage_structure(game: &mut DemoGameData, structure_index: usize, damage: u8) {
     if structure_index >= game.world.structures.len() {
         return;
     }
 
+    let structure = WorldObject { id: structure_index as u32, ty: WorldObjectType::Structure };
+    game.world.set_damaged(structure, game.global.time);
+
+    let mut applied = 0;
     let destroyed = match &mut game.world.structures_data[structure_index] {
         StructureData::Castle(data) => { 
-            data.hp -= u8::min(data.hp, damage);
+            applied = u8::min(data.hp, damage);
+            data.hp -= applied;
             data.destroyed = data.hp == 0;
             data.destroyed
         }
         StructureData::Tower(data) => { 
-            data.hp -= u8::min(data.hp, damage);
+            applied = u8::min(data.hp, damage);
+            data.hp -= applied;
             data.destroyed = data.hp == 0;
             data.destroyed
         }
         StructureData::House(data) => { 
-            data.hp -= u8::min(data.hp, damage);
+            applied = u8::min(data.hp, damage);
+            data.hp -= applied;
             data.destroyed = data.hp == 0;
             data.destroyed 
         }
         StructureData::GoblinHut(data) => { 
-            data.hp -= u8::min(data.hp, damage);
+            applied = u8::min(data.hp, damage);
+            data.hp -= applied;
             data.destroyed = data.hp == 0;
             data.destroyed
         }
         StructureData::GoldMine(_) => { false },
     };
 
+    show_damage(game, structure, applied);
+
     if !destroyed {
         return;
     }
 
     let structure = &mut game.world.structures[structure_index];
+    game.world.particles.emit(ParticleType::Smoke, structure.position, 12000.0, 150.0);
+
     match game.world.structures_data[structure_index] {
         StructureData::Castle(_) => { 
             structure.sprite = game.assets.structures.knights_castle_destroyed; 
@@ -15,11 +15,11 @@ use crate::DemoGameData;
 pub enum PawnBehaviourType {
     Idle,
     MoveTo { pathfinding_state: PathFindingData },
-    HarvestWood { tree_id: u32, last_timestamp: f32 },
+    HarvestWood { tree_id: u32 },
     HarvestGold { structure_id: u32, last_timestamp: f32 },
     GrabResource { resource_id: u32 },
     HuntSheep { sheep_id: u32, last_timestamp: f32 },
-    BuildStructure { structure_id: u32, last_timestamp: f32 },
+    BuildStructure { structure_id: u32, strikes: u32 },
 }
 
 #[derive(Copy, Clone)]
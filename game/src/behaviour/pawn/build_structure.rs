@@ -1,3 +1,4 @@
+use crate::assets::AnimationEvents;
 use crate::behaviour::BehaviourState;
 use crate::shared::pos;
 use crate::world::{BaseAnimated, BaseStatic, StructureData, WorldObject, WorldObjectType};
@@ -13,7 +14,8 @@ struct PawnBuildStructureParams {
     pawn: BaseAnimated,
     structure: BaseStatic,
     structure_data: StructureData,
-    last_timestamp: f32,
+    /// Number of hammer strikes since the structure hp was last increased
+    strikes: u32,
     structure_id: u32,
     new_behaviour: Option<PawnBehaviour>,
     state: BehaviourState,
@@ -45,7 +47,7 @@ pub fn new(game: &mut DemoGameData, pawn: WorldObject, structure: WorldObject) {
     PawnBehaviour::cancel(game, pawn.id, true);
 
     game.world.pawns_behaviour[pawn_index] = PawnBehaviour {
-        ty: PawnBehaviourType::BuildStructure { structure_id: structure.id, last_timestamp: 0.0 },
+        ty: PawnBehaviourType::BuildStructure { structure_id: structure.id, strikes: 0 },
         state: BehaviourState::Initial,
     };
 }
@@ -55,7 +57,7 @@ pub fn process(game: &mut DemoGameData, pawn_index: usize) {
     match params.state {
         BehaviourState::Initial => init(game, &mut params),
         BehaviourState::Running(MOVE_TO_STRUCTURE) => move_to_structure(game, &mut params),
-        BehaviourState::Running(BUILD_STRUCTURE) => build_structure(game, &mut params),
+        BehaviourState::Running(BUILD_STRUCTURE) => build_structure(&mut params),
         BehaviourState::Running(FINALIZE_STRUCTURE) => finalize_structure(game, &mut params),
         _ => {}
     }
@@ -82,8 +84,7 @@ fn move_to_structure(game: &DemoGameData, params: &mut PawnBuildStructureParams)
     let target_position = pos(f32::max(f32::min(params.pawn.position.x, aabb.right), aabb.left), aabb.bottom + 5.0);
     let updated_position = move_to(params.pawn.position, target_position, game.global.frame_delta);
     if updated_position == target_position {
-        params.pawn.animation = game.assets.animations.pawn.hammer;
-        params.pawn.current_frame = 0;
+        params.pawn.play(game.assets.animations.pawn.hammer);
         params.state = BehaviourState::Running(BUILD_STRUCTURE);
     }
 
@@ -91,17 +92,18 @@ fn move_to_structure(game: &DemoGameData, params: &mut PawnBuildStructureParams)
     params.pawn.flipped = params.pawn.position.x > params.structure.position.x;
 }
 
-fn build_structure(game: &DemoGameData, params: &mut PawnBuildStructureParams) {
-    use crate::behaviour::behaviour_shared::elapsed;
-
+fn build_structure(params: &mut PawnBuildStructureParams) {
     if early_exit(params.structure_data) {
         params.new_behaviour = Some(PawnBehaviour::idle());
         return;
     }
 
     // Every 2 hammer strike adds 5 hp to the structure
-    let total_animation_time = crate::ANIMATION_INTERVAL * 6.0 * 2.0;
-    if !elapsed(game.global.time, params.last_timestamp as f64, total_animation_time) {
+    if params.pawn.events.contains(AnimationEvents::HIT) {
+        params.strikes += 1;
+    }
+
+    if params.strikes < 2 {
         return;
     }
 
@@ -125,7 +127,7 @@ fn build_structure(game: &DemoGameData, params: &mut PawnBuildStructureParams) {
         params.state = BehaviourState::Running(FINALIZE_STRUCTURE);
     }
 
-    params.last_timestamp = game.global.time as f32;
+    params.strikes = 0;
 }
 
 fn finalize_structure(game: &DemoGameData, params: &mut PawnBuildStructureParams) {
@@ -161,8 +163,8 @@ fn read_params(game: &DemoGameData, pawn_index: usize) -> PawnBuildStructurePara
     let pawn = unsafe { game.world.pawns.get_unchecked(pawn_index) };
     let behaviour = unsafe { game.world.pawns_behaviour.get_unchecked(pawn_index) };
 
-    let (structure_index, last_timestamp) = match behaviour.ty {
-        PawnBehaviourType::BuildStructure { structure_id, last_timestamp } => (structure_id as usize, last_timestamp),
+    let (structure_index, strikes) = match behaviour.ty {
+        PawnBehaviourType::BuildStructure { structure_id, strikes } => (structure_id as usize, strikes),
         _ => unsafe { ::std::hint::unreachable_unchecked()}
     };
 
@@ -173,7 +175,7 @@ fn read_params(game: &DemoGameData, pawn_index: usize) -> PawnBuildStructurePara
         pawn: *pawn,
         structure: *structure,
         structure_data: *structure_data,
-        last_timestamp,
+        strikes,
         structure_id: structure_index as u32,
         new_behaviour: None,
         state: behaviour.state
@@ -199,7 +201,7 @@ fn write_params(game: &mut DemoGameData, pawn_index: usize, params: &PawnBuildSt
         None => {
             behaviour.ty = PawnBehaviourType::BuildStructure { 
                 structure_id: params.structure_id,
-                last_timestamp: params.last_timestamp
+                strikes: params.strikes
             };
 
             behaviour.state = params.state;
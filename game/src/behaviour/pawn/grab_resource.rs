@@ -1,5 +1,5 @@
 use crate::behaviour::BehaviourState;
-use crate::world::{BaseAnimated, BaseStatic, PawnData, ResourceData, ResourceType, WorldObject, WorldObjectType};
+use crate::world::{BaseAnimated, BaseStatic, PawnData, ResourceData, ResourceType, WorldObject, WorldObjectType, RESOURCE_TEXT_COLOR};
 use crate::DemoGameData;
 use super::{PawnBehaviour, PawnBehaviourType};
 
@@ -12,6 +12,7 @@ struct PawnGrabResourceParams {
     resource: BaseStatic,
     resource_data: ResourceData,
     resource_index: u32,
+    collected: bool,
     new_behaviour: Option<PawnBehaviour>,
     state: BehaviourState,
 }
@@ -89,6 +90,12 @@ fn grab_resource(game: &DemoGameData, params: &mut PawnGrabResourceParams) {
 
     params.resource_data.grabbed = true;
 
+    // Resources are added to the stockpile the first time they are picked up
+    if !params.resource_data.collected {
+        params.resource_data.collected = true;
+        params.collected = true;
+    }
+
     params.resource.position = params.pawn.position;
     params.resource.position.y -= 60.0;
 
@@ -120,6 +127,7 @@ fn read_params(game: &DemoGameData, pawn_index: usize) -> PawnGrabResourceParams
         resource: *resource,
         resource_data: *resource_data,
         resource_index: resource_index as u32,
+        collected: false,
         new_behaviour: None,
         state: behaviour.state
     }
@@ -137,6 +145,17 @@ fn write_params(game: &mut DemoGameData, pawn_index: usize, params: &PawnGrabRes
     *resource = params.resource;
     *resource_data = params.resource_data;
 
+    if params.collected {
+        let resource_type = params.resource_data.resource_type;
+        game.world.stockpile.add(resource_type, 1);
+
+        let strings = &game.assets.strings;
+        let text = strings.format("floating.resource_gain", &[&1, &strings.text(resource_type.name_key())]);
+        let mut text_position = params.resource.position;
+        text_position.y -= params.resource.aabb().height();
+        game.world.floating_texts.add(&game.assets.fonts, &text, text_position, RESOURCE_TEXT_COLOR);
+    }
+
     match params.new_behaviour {
         Some(new_behaviour) => {
             *behaviour = new_behaviour;
@@ -1,6 +1,6 @@
-use crate::assets::AnimationBase;
+use crate::assets::{AnimationBase, AnimationEvents};
 use crate::behaviour::BehaviourState;
-use crate::world::{BaseAnimated, WorldObject, WorldObjectType, TreeData};
+use crate::world::{BaseAnimated, WorldObject, WorldObjectType, TreeData, ParticleType};
 use crate::DemoGameData;
 use super::{PawnBehaviour, PawnBehaviourType};
 
@@ -13,8 +13,9 @@ struct PawnHarvestWoodParams {
     tree: BaseAnimated,
     tree_data: TreeData,
     tree_id: u32,
-    last_timestamp: f32,
     spawn_wood: bool,
+    /// The pawn axe hit the tree this frame
+    strike: bool,
     new_behaviour: Option<PawnBehaviour>,
     state: BehaviourState,
 }
@@ -39,7 +40,7 @@ pub fn new(game: &mut DemoGameData, pawn: WorldObject, tree: WorldObject) {
     PawnBehaviour::cancel(game, pawn.id, true);
 
     game.world.pawns_behaviour[pawn_index] = PawnBehaviour {
-        ty: PawnBehaviourType::HarvestWood { tree_id: tree.id, last_timestamp: 0.0 },
+        ty: PawnBehaviourType::HarvestWood { tree_id: tree.id },
         state: BehaviourState::Initial,
     };
 }
@@ -105,27 +106,21 @@ fn move_to_tree(game: &DemoGameData, params: &mut PawnHarvestWoodParams) {
 }
 
 fn begin_cut_tree(game: &DemoGameData, params: &mut PawnHarvestWoodParams) {
-    params.pawn.animation = game.assets.animations.pawn.axe;
+    params.pawn.play(game.assets.animations.pawn.axe);
     params.pawn.flipped = params.pawn.position.x > params.tree.position.x;
 
-    params.tree.animation = game.assets.resources.tree_cut;
-    params.tree.current_frame = 0;
+    params.tree.play(game.assets.resources.tree_cut);
     params.tree_data.being_harvested = true;
     
-    params.last_timestamp = game.global.time as f32;
     params.state = BehaviourState::Running(CUT_TREE);
 
     cut_tree(game, params);
 }
 
 fn cut_tree(game: &DemoGameData, params: &mut PawnHarvestWoodParams) {
-    use crate::behaviour::behaviour_shared::elapsed;
-
-    let total_animation_time = crate::ANIMATION_INTERVAL * 6.0;
-
-    if elapsed(game.global.time, params.last_timestamp as f64, total_animation_time) {
-        params.last_timestamp = game.global.time as f32;
+    if params.pawn.events.contains(AnimationEvents::HIT) {
         params.tree_data.life -= u8::min(params.tree_data.life, 1);
+        params.strike = true;
     }
 
     if params.tree_data.life == 0 {
@@ -139,8 +134,8 @@ fn cut_tree(game: &DemoGameData, params: &mut PawnHarvestWoodParams) {
 fn read_params(game: &DemoGameData, pawn_index: usize) -> PawnHarvestWoodParams {
     let pawn = unsafe { game.world.pawns.get_unchecked(pawn_index) };
     let behaviour = unsafe { game.world.pawns_behaviour.get_unchecked(pawn_index) };
-    let (tree_index, last_timestamp) = match behaviour.ty {
-        PawnBehaviourType::HarvestWood { tree_id, last_timestamp } => (tree_id as usize, last_timestamp),
+    let tree_index = match behaviour.ty {
+        PawnBehaviourType::HarvestWood { tree_id } => tree_id as usize,
         _ => unsafe { ::std::hint::unreachable_unchecked()}
     };
 
@@ -152,8 +147,8 @@ fn read_params(game: &DemoGameData, pawn_index: usize) -> PawnHarvestWoodParams
         tree: *tree,
         tree_data: *tree_data,
         tree_id: tree_index as u32,
-        last_timestamp,
         spawn_wood: false,
+        strike: false,
         new_behaviour: None,
         state: behaviour.state
     }
@@ -177,6 +172,14 @@ fn write_params(game: &mut DemoGameData, pawn_index: usize, params: &PawnHarvest
     if params.spawn_wood {
         spawn_wood(game, params);
     }
+
+    if params.strike {
+        // Chips fly from the trunk, on the side of the pawn
+        let mut position = params.tree.position;
+        position.x += (params.pawn.position.x - position.x).clamp(-16.0, 16.0);
+        position.y += 1.0;
+        game.world.particles.burst(ParticleType::WoodChip, position, 4);
+    }
     
     let tree_index = params.tree_id as usize;
     let pawn = unsafe { game.world.pawns.get_unchecked_mut(pawn_index) };
@@ -193,10 +196,7 @@ fn write_params(game: &mut DemoGameData, pawn_index: usize, params: &PawnHarvest
             *behaviour = new_behaviour;
         },
         None => {
-            behaviour.ty = PawnBehaviourType::HarvestWood { 
-                tree_id: params.tree_id,
-                last_timestamp: params.last_timestamp
-            };
+            behaviour.ty = PawnBehaviourType::HarvestWood { tree_id: params.tree_id };
 
             behaviour.state = params.state;
         }
@@ -1,3 +1,4 @@
+use crate::assets::AnimationEvents;
 use crate::behaviour::BehaviourState;
 use crate::shared::Position;
 use crate::world::{BaseAnimated, WorldObject, WorldObjectType};
@@ -76,8 +77,7 @@ fn move_to_sheep(game: &DemoGameData, params: &mut PawnHuntSheepParams) {
 
     let updated_position = move_to(params.pawn.position, target_position, game.global.frame_delta);
     if updated_position == target_position {
-        params.pawn.animation = game.assets.animations.pawn.axe;
-        params.pawn.current_frame = 0;
+        params.pawn.play(game.assets.animations.pawn.axe);
         params.state = BehaviourState::Running(ATTACK_SHEEP);
         params.last_timestamp = game.global.time as f32;
     }
@@ -87,8 +87,6 @@ fn move_to_sheep(game: &DemoGameData, params: &mut PawnHuntSheepParams) {
 }
 
 fn attack_sheep(game: &DemoGameData, params: &mut PawnHuntSheepParams) {
-    use crate::behaviour::behaviour_shared::elapsed;
-
     if params.sheep_life == 0 {
         params.new_behaviour = Some(PawnBehaviour::idle());
         return;
@@ -100,8 +98,7 @@ fn attack_sheep(game: &DemoGameData, params: &mut PawnHuntSheepParams) {
         params.state = BehaviourState::Running(PAUSE);
         return;
     }
-    else if params.pawn.current_frame == 5 && elapsed(game.global.time, params.last_timestamp as f64, 300.0) {
-        params.last_timestamp = game.global.time as f32;
+    else if params.pawn.events.contains(AnimationEvents::HIT) {
         params.sheep_strike = true;
     }
 }
@@ -4,6 +4,7 @@ pub mod escaping;
 
 use crate::behaviour::BehaviourState;
 use crate::shared::Position;
+use crate::world::{WorldObject, WorldObjectType};
 use crate::DemoGameData;
 
 
@@ -47,10 +48,17 @@ impl SheepBehaviour {
 }
 
 pub fn strike(data: &mut DemoGameData, sheep_index: usize, damage: u8) {
+    let sheep = WorldObject { id: sheep_index as u32, ty: WorldObjectType::Sheep };
+    data.world.set_damaged(sheep, data.global.time);
+
     let sheep_data = &mut data.world.sheeps_data[sheep_index];
-    sheep_data.life -= u8::min(sheep_data.life, damage);
+    let applied = u8::min(sheep_data.life, damage);
+    sheep_data.life -= applied;
+    let dead = sheep_data.life == 0;
+
+    crate::behaviour::behaviour_shared::show_damage(data, sheep, applied);
 
-    if sheep_data.life == 0 {
+    if dead {
         data.world.sheep_behaviour[sheep_index] = SheepBehaviour::dead();
         spawn_meat(data, sheep_index);
     } else {
@@ -34,24 +34,25 @@ pub fn process(game: &mut DemoGameData, spawn_index: usize) {
     let behaviour = &mut game.world.resources_spawn_behaviour[spawn_index];
     if behaviour.init {
         let resource_spawn = &mut game.world.resources_spawn[spawn_index];
-        resource_spawn.animation = match behaviour.resource_type {
+        resource_spawn.play_once(match behaviour.resource_type {
             ResourceType::Food => game.assets.resources.meat_spawn,
             ResourceType::Gold => game.assets.resources.gold_spawn,
             ResourceType::Wood => game.assets.resources.wood_spawn,
-        };
-        resource_spawn.current_frame = 0;
+        });
         behaviour.init = false;
         return;
     }
 
+    // The resource replaces the spawn once the spawn animation completed
     let resource_spawn = game.world.resources_spawn[spawn_index];
-    if resource_spawn.current_frame != resource_spawn.animation.last_frame {
+    if !resource_spawn.finished {
         return;
     }
 
     let data = ResourceData {
         resource_type: behaviour.resource_type,
         grabbed: false,
+        collected: false,
     };
 
     match behaviour.resource_type {
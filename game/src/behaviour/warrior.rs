@@ -10,7 +10,7 @@ use crate::DemoGameData;
 pub enum WarriorBehaviourType {
     Idle,
     MoveTo { target_position: Position<f32> },
-    Attack { target: WorldObject, timestamp1: f64, timestamp2: f64 }
+    Attack { target: WorldObject, timestamp1: f64 }
 }
 
 #[derive(Copy, Clone)]
@@ -33,7 +33,7 @@ pub fn idle(game: &mut DemoGameData, warrior_index: usize) {
     let behaviour = &mut world.warriors_behaviour[warrior_index];
     if let BehaviourState::Initial = behaviour.state {
         let warrior = &mut world.warriors[warrior_index];
-        warrior.animation = game.assets.animations.warrior.idle;
+        warrior.play(game.assets.animations.warrior.idle);
         behaviour.state = BehaviourState::Running(0);
     }
 }
@@ -64,11 +64,10 @@ impl crate::store::SaveAndLoad for WarriorBehaviourType {
                 writer.write_u32(2);
                 writer.write(target_position);
             },
-            Self::Attack { target, timestamp1, timestamp2 } => {
+            Self::Attack { target, timestamp1 } => {
                 writer.write_u32(3);
                 writer.write(target);
                 writer.write_f64(*timestamp1);
-                writer.write_f64(*timestamp2);
             }
         }
     }
@@ -77,7 +76,7 @@ impl crate::store::SaveAndLoad for WarriorBehaviourType {
         let id = reader.read_u32();
         match id {
             2 => Self::MoveTo { target_position: reader.read() },
-            3 => Self::Attack { target: reader.read(), timestamp1: reader.read_f64(), timestamp2: reader.read_f64() },
+            3 => Self::Attack { target: reader.read(), timestamp1: reader.read_f64() },
             _ => Self::Idle,
         }
 
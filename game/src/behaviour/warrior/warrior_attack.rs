@@ -1,7 +1,8 @@
+use crate::assets::AnimationEvents;
 use crate::behaviour::BehaviourState;
 use crate::behaviour::behaviour_shared::{elapsed, is_enemy_structure};
 use crate::shared::Position;
-use crate::world::{BaseAnimated, WorldObject, WorldObjectType};
+use crate::world::{BaseAnimated, WorldObject, WorldObjectType, ParticleType};
 use crate::DemoGameData;
 use super::{WarriorBehaviour, WarriorBehaviourType};
 
@@ -10,26 +11,17 @@ const STRIKE: u8 = 1;
 const PAUSE: u8 = 2;
 
 const MAX_ATTACK_DISTANCE: f32 = 80.0;
-const STRIKE_TIME: f64 = crate::ANIMATION_INTERVAL * 5.0;
 
 pub struct WarriorAttackParams {
     warrior: BaseAnimated,
     target_position: Position<f32>,
     timestamp1: f64,
-    timestamp2: f64,
     target_life: u8,
     compute_damage: bool,
     new_behaviour: Option<WarriorBehaviour>,
     state: BehaviourState,
 }
 
-enum AttackAnimation {
-    Left1,
-    Left2,
-    Right1,
-    Right2,
-}
-
 pub fn new(game: &mut DemoGameData, warrior: WorldObject, target: WorldObject) {
     let warrior_index = warrior.id as usize;
     let target_index = target.id as usize;
@@ -45,7 +37,7 @@ pub fn new(game: &mut DemoGameData, warrior: WorldObject, target: WorldObject) {
     }
 
     game.world.warriors_behaviour[warrior_index] = WarriorBehaviour {
-        ty: WarriorBehaviourType::Attack { target, timestamp1: 0.0, timestamp2: 0.0 },
+        ty: WarriorBehaviourType::Attack { target, timestamp1: 0.0 },
         state: BehaviourState::Initial,
     };
 }
@@ -72,11 +64,11 @@ pub fn process(game: &mut DemoGameData, warrior_index: usize) {
 fn init(game: &DemoGameData, params: &mut WarriorAttackParams) {
     let distance = params.warrior.position.distance(params.target_position);
     if distance > MAX_ATTACK_DISTANCE {
-        params.warrior.animation = game.assets.animations.warrior.walk;
-        params.warrior.current_frame = 0;
+        params.warrior.play(game.assets.animations.warrior.walk);
         params.state = BehaviourState::Running(MOVING);
         moving(game, params);
     } else {
+        params.warrior.play_once(game.assets.animations.warrior.strike_h1);
         params.timestamp1 = game.global.time;
         params.state = BehaviourState::Running(STRIKE);
         strike(game, params);
@@ -98,62 +90,39 @@ fn moving(game: &DemoGameData, params: &mut WarriorAttackParams) {
     params.warrior.flipped = params.warrior.position.x > params.target_position.x;
 
     if params.warrior.position == target {
-        params.warrior.current_frame = 0;
+        params.warrior.play_once(game.assets.animations.warrior.strike_h1);
         params.timestamp1 = game.global.time;
         params.state = BehaviourState::Running(STRIKE);
     }
 }
 
 fn strike(game: &DemoGameData, params: &mut WarriorAttackParams) {
-
-    fn select_animation(game: &DemoGameData, params: &WarriorAttackParams) -> AttackAnimation {
-        let x1 = params.warrior.position.x;
-        let x2 = params.target_position.x;
-
-        match (x1 < x2, elapsed(game.global.time, params.timestamp1, STRIKE_TIME)) {
-            (false, false) => AttackAnimation::Left1,
-            (false, true) => AttackAnimation::Left2,
-            (true, false) => AttackAnimation::Right1,
-            (true, true) => AttackAnimation::Right2,
-        }
-    }
-
-    fn set_attack_animation(game: &DemoGameData, params: &mut WarriorAttackParams) {
-        let animation = select_animation(game, params);
-        let animations = &game.assets.animations.warrior;
-        params.warrior.animation = match animation {
-            AttackAnimation::Right1 | AttackAnimation::Left1 => animations.strike_h1,
-            AttackAnimation::Right2 | AttackAnimation::Left2 => animations.strike_h2
-        };
-
-        params.warrior.flipped = match animation {
-            AttackAnimation::Left1 | AttackAnimation::Left2 => true,
-            AttackAnimation::Right1 | AttackAnimation::Right2 => false,
-        };
-    }
-
     if params.warrior.position.distance(params.target_position) > MAX_ATTACK_DISTANCE {
-        params.warrior.animation = game.assets.animations.warrior.idle;
-        params.warrior.current_frame = 0;
+        params.warrior.play(game.assets.animations.warrior.idle);
         params.timestamp1 = game.global.time;
         params.state = BehaviourState::Running(PAUSE);
         return;
     }
 
-    set_attack_animation(game, params);
+    // Damage is dealt on the frames tagged with the `hit` event in `units.csv`
+    if params.warrior.events.contains(AnimationEvents::HIT) {
+        params.compute_damage = true;
+    }
 
-    if params.warrior.current_frame == 5 {
-        if elapsed(game.global.time, params.timestamp1,  STRIKE_TIME * 2.0) {
-            params.warrior.animation = game.assets.animations.warrior.idle;
+    // Both strikes are played once, one after the other, then the warrior pauses
+    let animations = &game.assets.animations.warrior;
+    if params.warrior.finished {
+        if params.warrior.animation == animations.strike_h1 {
+            params.warrior.play_once(animations.strike_h2);
+        } else {
+            params.warrior.play(animations.idle);
             params.timestamp1 = game.global.time;
             params.state = BehaviourState::Running(PAUSE);
-        }
-
-        if elapsed(game.global.time, params.timestamp2, 200.0) {
-            params.compute_damage = true;
-            params.timestamp2 = game.global.time;
+            return;
         }
     }
+
+    params.warrior.flipped = params.warrior.position.x >= params.target_position.x;
 }
 
 fn pause(game: &DemoGameData, params: &mut WarriorAttackParams) {
@@ -165,8 +134,8 @@ fn pause(game: &DemoGameData, params: &mut WarriorAttackParams) {
 fn read_params(game: &DemoGameData, warrior_index: usize) -> WarriorAttackParams {
     let warrior = unsafe { game.world.warriors.get_unchecked(warrior_index) };
     let warrior_behaviour = unsafe { game.world.warriors_behaviour.get_unchecked(warrior_index) };
-    let (target, timestamp1, timestamp2) = match warrior_behaviour.ty {
-        WarriorBehaviourType::Attack { target, timestamp1, timestamp2 } => (target, timestamp1, timestamp2),
+    let (target, timestamp1) = match warrior_behaviour.ty {
+        WarriorBehaviourType::Attack { target, timestamp1 } => (target, timestamp1),
         _ => unsafe { ::std::hint::unreachable_unchecked(); }
     };
 
@@ -177,7 +146,6 @@ fn read_params(game: &DemoGameData, warrior_index: usize) -> WarriorAttackParams
         warrior: *warrior,
         target_position,
         timestamp1,
-        timestamp2,
         target_life,
         compute_damage: false,
         new_behaviour: None,
@@ -193,6 +161,10 @@ fn compute_damage(game: &mut DemoGameData, warrior_index: usize) {
     };
 
     let target_index = target.id as usize;
+    if let Some((position, _)) = game.world.object_bounds(target) {
+        game.world.particles.burst(ParticleType::Spark, position, 6);
+    }
+
     match target.ty {
         WorldObjectType::Sheep => crate::behaviour::sheep::strike(game, target_index, 5),
         WorldObjectType::Structure => crate::behaviour::behaviour_shared::damage_structure(game, target_index, 5),
@@ -217,7 +189,6 @@ fn write_params(game: &mut DemoGameData, warrior_index: usize, params: &WarriorA
                 WarriorBehaviourType::Attack { target, .. } => WarriorBehaviourType::Attack { 
                     target,
                     timestamp1: params.timestamp1,
-                    timestamp2: params.timestamp2,
                 },
                 _ => unsafe { ::std::hint::unreachable_unchecked(); }
             };
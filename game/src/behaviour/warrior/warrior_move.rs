@@ -38,7 +38,7 @@ pub fn process(game: &mut DemoGameData, warrior_index: usize) {
 }
 
 fn init(game: &DemoGameData, params: &mut WarriorMoveParams) {
-    params.warrior.animation = game.assets.animations.warrior.walk;
+    params.warrior.play(game.assets.animations.warrior.walk);
     params.state = BehaviourState::Running(MOVING);
 }
 
@@ -17,19 +17,18 @@ macro_rules! flags {
 }
 
 impl DemoGameFlags {
-    const UPDATE_ANIMATIONS: u32 = 0b0001;   // Animations must be updated
     const SYNC_VIEW: u32         = 0b0010;   // World view offset must be synchronized with engine
     const SYNC_TERRAIN: u32      = 0b0100;   // Terrain data was changed and must be synchronized
+    const RELOAD_GUI: u32        = 0b1000;   // A gui layout was reloaded or the language changed and the gui must be built again
 
-    flags!(get_update_animations, set_update_animations, clear_update_animations, Self::UPDATE_ANIMATIONS);
     flags!(get_sync_view, set_sync_view, clear_sync_view, Self::SYNC_VIEW);
     flags!(get_sync_terrain, set_sync_terrain, clear_sync_terrain, Self::SYNC_TERRAIN);
+    flags!(get_reload_gui, set_reload_gui, clear_reload_gui, Self::RELOAD_GUI);
 }
 
 #[derive(Default, Copy, Clone)]
 pub struct DemoGameGlobalData {
     pub time: f64,
-    pub last_animation_tick: f64,
     pub seed: u64,
     pub view_offset: Position<f32>,
     pub frame_delta: f32,
@@ -83,7 +82,6 @@ impl Default for DemoGameData {
 impl store::SaveAndLoad for DemoGameGlobalData {
     fn save(&self, writer: &mut store::SaveFileWriter) {
         writer.write_f64(self.time);
-        writer.write_f64(self.last_animation_tick);
         writer.write_u64(self.seed);
         writer.write(&self.view_offset);
         writer.write_f32(self.frame_delta);
@@ -93,7 +91,6 @@ impl store::SaveAndLoad for DemoGameGlobalData {
     fn load(reader: &mut store::SaveFileReader) -> Self {
         DemoGameGlobalData {
             time: reader.read_f64(),
-            last_animation_tick: reader.read_f64(),
             seed: reader.read_u64(),
             view_offset: reader.read(),
             frame_delta: reader.read_f32(),
@@ -114,12 +111,13 @@ impl store::SaveAndLoad for DemoGameData {
     fn load(reader: &mut store::SaveFileReader) -> Self {
         let assets = Arc::new(reader.load());
         let mut world = reader.load::<crate::world::World>();
-        let gui = reader.load();
+        let mut gui = reader.load::<crate::gui::Gui>();
         let global = reader.load();
         let inputs = reader.read();
         let debug = crate::debug::DebugState::default();
 
         world.assets = Some(Arc::clone(&assets));
+        gui.set_assets(Arc::clone(&assets));
 
         DemoGameData {
             global,
@@ -13,31 +13,68 @@ pub use gui_components::*;
 mod gui_builder;
 pub use gui_builder::{GuiBuilder, GuiBuilderData};
 
+mod gui_inputs;
+pub use gui_inputs::{GuiEvent, GuiInputState};
+
+mod gui_text_input;
+pub use gui_text_input::GuiTextInputState;
+
+mod gui_scroll;
+
+mod layout_asset;
+pub use layout_asset::GuiLayoutBindings;
+
 mod layout_compute;
 mod generate_sprites;
 
 use std::cell::UnsafeCell;
-use crate::assets::TextMetrics;
+use std::sync::Arc;
+use crate::assets::{Assets, TextAlign, TextMetrics};
 use crate::error::Error;
-use crate::shared::{Size, AABB};
+use crate::inputs::InputState;
+use crate::shared::{Position, Size, AABB};
+
+/// Limits of the gui scale chosen by the user
+pub const MIN_USER_SCALE: f32 = 0.5;
+pub const MAX_USER_SCALE: f32 = 3.0;
 
 struct GuiUpdateFlags(u8);
 impl GuiUpdateFlags {
-    const ALL: u8 = 0b011;
-    const COMPUTE_LAYOUT_POSITIONS: u8  = 0b01;
-    const COMPUTE_LAYOUT_SIZES: u8      = 0b10;
+    const ALL: u8 = 0b111;
+    const COMPUTE_LAYOUT_POSITIONS: u8  = 0b001;
+    const COMPUTE_LAYOUT_SIZES: u8      = 0b010;
+    const GENERATE_SPRITES: u8          = 0b100;
     pub fn set(&mut self, flags: u8) { self.0 = flags; }
+    pub fn add(&mut self, flags: u8) { self.0 |= flags; }
     pub fn clear(&mut self) { self.0 = 0; }
     pub fn generate_sprites(&self) -> bool { self.0 != 0 }
     pub fn compute_layout_sizes(&self) -> bool { self.0 & Self::COMPUTE_LAYOUT_SIZES > 0 }
     pub fn compute_layout_positions(&self) -> bool { self.0 & (Self::COMPUTE_LAYOUT_POSITIONS | Self::COMPUTE_LAYOUT_SIZES) > 0 }
 }
 
+/// Range of `Gui::output_sprites` reserved for a root component. Sprites after `count` are empty.
+#[derive(Copy, Clone)]
+struct GuiRootSprites {
+    root: u32,
+    first: u32,
+    count: u32,
+    capacity: u32,
+}
+
+/// State of the floating roots. See `GuiLayoutOrigin::Floating`
+#[derive(Copy, Clone, Default)]
+struct GuiFloatingLayer {
+    position: Position<f32>,
+    visible: bool,
+}
+
 pub struct Gui {
     builder_data: UnsafeCell<GuiBuilderData>,
 
     images: Vec<GuiImage>,
     text: Vec<TextMetrics>,
+    progress: Vec<GuiProgress>,
+    text_inputs: Vec<GuiTextInputState>,
     dynamic_resources: Vec<DynamicResource>,
 
     components_nodes: Vec<GuiNode>,
@@ -46,9 +83,28 @@ pub struct Gui {
     components: Vec<GuiComponent>,
 
     output_sprites: Vec<GuiOutputSprite>,
+    /// Sprites of each root, in rendering order. Empty when all the sprites must be generated again.
+    roots_sprites: Vec<GuiRootSprites>,
+    /// Range of `output_sprites` modified since the last call to `take_sprites_update`
+    sprites_update: (u32, u32),
+    /// Storage reused when the sprites of a single root are generated
+    sprites_scratch: Vec<GuiOutputSprite>,
+    /// Scroll frames being generated. See `generate_sprites::generate_root`
+    clips_scratch: Vec<(usize, AABB, usize)>,
+
+    events: Vec<GuiEvent>,
+    input_state: GuiInputState,
+    floating: GuiFloatingLayer,
 
     view_size: Size<f32>,
+    /// Ratio between the view pixels and the css pixels of the page. Reported by the engine.
+    pixel_ratio: f32,
+    /// Scale of the gui chosen by the user, on top of the pixel ratio
+    user_scale: f32,
     update_flags: GuiUpdateFlags,
+
+    /// Used to compute the layout of the text typed in the text inputs
+    assets: Option<Arc<Assets>>,
 }
 
 impl Gui {
@@ -66,8 +122,9 @@ impl Gui {
             return Err(error);
         }
 
-        // Sizing is already done by the building so we only need to compute the positions
-        self.update_flags.set(GuiUpdateFlags::COMPUTE_LAYOUT_POSITIONS);
+        // The builder only computes the static and auto sizes. Percent and fill sizing need the size of the parents,
+        // so the whole layout is computed in `layout_compute`.
+        self.update_flags.set(GuiUpdateFlags::ALL);
         
         Ok(())
     }
@@ -86,15 +143,90 @@ impl Gui {
         &self.output_sprites
     }
 
+    /// Returns the range of `sprites` modified since the last call to this function
+    pub fn take_sprites_update(&mut self) -> ::std::ops::Range<usize> {
+        let (start, end) = ::std::mem::take(&mut self.sprites_update);
+        (start as usize)..(end as usize)
+    }
+
+    /// Updates the buttons state from the user inputs and generates the gui events for this frame.
+    /// Events from the last call are discarded.
+    pub fn process_inputs(&mut self, inputs: &InputState) {
+        gui_inputs::process_inputs(self, inputs);
+    }
+
+    /// Returns the next event generated in `process_inputs`
+    pub fn pop_event(&mut self) -> Option<GuiEvent> {
+        self.events.pop()
+    }
+
+    /// Returns true if the mouse is over a visible gui component. In this case, mouse inputs should not be processed by the game.
+    pub fn captures_mouse(&self) -> bool {
+        self.input_state.mouse_over
+    }
+
+    /// Returns the button under the mouse, if any
+    pub fn hovered_button(&self) -> Option<GuiButtonId> {
+        match self.input_state.hovered_button {
+            u32::MAX => None,
+            index => Some(GuiButtonId(index))
+        }
+    }
+
+    /// Shows the floating roots at `position`. The position is clamped so that the roots stay in the view.
+    pub fn show_floating(&mut self, position: Position<f32>) {
+        self.floating = GuiFloatingLayer { position, visible: true };
+        self.tag_floating_roots();
+        self.update_flags.add(GuiUpdateFlags::COMPUTE_LAYOUT_POSITIONS | GuiUpdateFlags::GENERATE_SPRITES);
+    }
+
+    pub fn hide_floating(&mut self) {
+        if self.floating.visible {
+            self.floating.visible = false;
+            self.tag_floating_roots();
+            self.update_flags.add(GuiUpdateFlags::GENERATE_SPRITES);
+        }
+    }
+
+    pub fn floating_visible(&self) -> bool {
+        self.floating.visible
+    }
+
+    pub fn set_assets(&mut self, assets: Arc<Assets>) {
+        self.assets = Some(assets);
+    }
+
+    pub fn release_assets(&mut self) {
+        self.assets = None;
+    }
+
+    /// Returns true if a text input has the keyboard focus. In this case, the game should ignore the keyboard hotkeys.
+    pub fn captures_keyboard(&self) -> bool {
+        self.input_state.focused_input != u32::MAX
+    }
+
+    pub fn text_input_value(&self, input_id: GuiTextInputId) -> &str {
+        match self.components.get(input_id.0 as usize) {
+            Some(GuiComponent::TextInput(input)) => &self.text_inputs[input.state as usize].value,
+            _ => ""
+        }
+    }
+
     pub fn clear(&mut self) {
         self.images.clear();
         self.text.clear();
+        self.progress.clear();
+        self.text_inputs.clear();
         self.dynamic_resources.clear();
         self.components.clear();
         self.components_views.clear();
         self.components_nodes.clear();
         self.components_layout.clear();
         self.output_sprites.clear();
+        self.roots_sprites.clear();
+        self.events.clear();
+        self.input_state = GuiInputState::default();
+        self.floating = GuiFloatingLayer::default();
         self.update_flags.set(GuiUpdateFlags::ALL);
     }
 
@@ -112,6 +244,36 @@ impl Gui {
         }
     }
 
+    /// Scale applied to every pixel size of the gui. Sizes in the builder, the layouts and the text metrics
+    /// are in css pixels, and the layout is computed in view pixels.
+    pub fn scale(&self) -> f32 {
+        self.pixel_ratio * self.user_scale
+    }
+
+    pub fn set_pixel_ratio(&mut self, pixel_ratio: f32) {
+        let old_scale = self.scale();
+        self.pixel_ratio = pixel_ratio;
+        self.scale_changed(old_scale);
+    }
+
+    pub fn set_user_scale(&mut self, user_scale: f32) {
+        let old_scale = self.scale();
+        self.user_scale = user_scale.clamp(MIN_USER_SCALE, MAX_USER_SCALE);
+        self.scale_changed(old_scale);
+    }
+
+    fn scale_changed(&mut self, old_scale: f32) {
+        if self.scale() == old_scale {
+            return;
+        }
+
+        for node in self.components_nodes.iter_mut() {
+            node.dirty = true;
+        }
+
+        self.update_flags.set(GuiUpdateFlags::ALL);
+    }
+
     pub fn set_image(&mut self, image_id: GuiImageId, image: AABB) {
         let image_index = image_id.index();
         let dyn_index = image_id.dyn_index();
@@ -119,10 +281,9 @@ impl Gui {
             return;
         }
 
+        let resized = self.images[image_index].texcoord.size() != image.size();
         self.images[image_index] = GuiImage::from_aabb(image);
-        self.tag_dynamic_resource(dyn_index);
-
-        self.update_flags.set(GuiUpdateFlags::ALL);
+        self.tag_dynamic_resource(dyn_index, resized);
     }
 
     pub fn clear_image(&mut self, image_id: GuiImageId) {
@@ -132,8 +293,9 @@ impl Gui {
             return;
         }
 
+        let resized = self.images[image_index].texcoord.size() != AABB::default().size();
         self.images[image_index].texcoord = AABB::default();
-        self.tag_dynamic_resource(dyn_index);
+        self.tag_dynamic_resource(dyn_index, resized);
     }
 
     pub fn set_text(&mut self, text_id: GuiStaticTextId, text: TextMetrics) {
@@ -143,8 +305,35 @@ impl Gui {
             return;
         }
 
+        let resized = self.text[text_index].size != text.size;
         self.text[text_index] = text;
-        self.tag_dynamic_resource(dyn_index);
+        self.tag_dynamic_resource(dyn_index, resized);
+    }
+
+    /// Lays out `text` with the alignment and the wrap width of the first label displaying `text_id`, then displays it. See `GuiLabel`
+    pub fn set_label_text(&mut self, text_id: GuiStaticTextId, text: &str, font_size: f32) {
+        let dyn_index = text_id.dyn_index();
+        let users = match self.dynamic_resources.get(dyn_index) {
+            Some(resource) => &resource.users,
+            None => { return; }
+        };
+
+        let label = users.iter().find_map(|&user| match self.components.get(user as usize) {
+            Some(GuiComponent::Label(label)) => Some(*label),
+            _ => None
+        });
+
+        let (align, wrap_width) = match label {
+            Some(label) => (label.align, label.wrap_width),
+            None => (TextAlign::Left, f32::INFINITY)
+        };
+
+        let metrics = match self.assets.as_ref() {
+            Some(assets) => assets.fonts.compute_multiline_text_metrics(text, font_size, wrap_width, align),
+            None => { return; }
+        };
+
+        self.set_text(text_id, metrics);
     }
 
     pub fn clear_text(&mut self, text_id: GuiStaticTextId) {
@@ -154,15 +343,101 @@ impl Gui {
             return;
         }
 
+        let resized = self.text[text_index].size != Size::default();
         self.text[text_index].glyphs.clear();
+        self.text[text_index].lines.clear();
         self.text[text_index].size = Default::default();
-        self.tag_dynamic_resource(dyn_index);
+        self.tag_dynamic_resource(dyn_index, resized);
+    }
+
+    /// Sets the filled part of a progress bar. `ratio` is clamped between 0.0 and 1.0
+    pub fn set_progress(&mut self, progress_id: GuiProgressId, ratio: f32) {
+        let ratio = ratio.clamp(0.0, 1.0);
+        if let Some(progress) = self.progress.get_mut(progress_id.index()) {
+            if progress.ratio != ratio {
+                progress.ratio = ratio;
+                self.tag_progress(progress_id);
+            }
+        }
+    }
+
+    pub fn set_progress_color(&mut self, progress_id: GuiProgressId, color: GuiColor) {
+        if let Some(progress) = self.progress.get_mut(progress_id.index()) {
+            if progress.color.splat() != color.splat() {
+                progress.color = color;
+                self.tag_progress(progress_id);
+            }
+        }
+    }
+
+    pub fn set_progress_bar_visible(&mut self, bar_id: GuiProgressBarId, visible: bool) {
+        let index = bar_id.0 as usize;
+        if let Some(GuiComponent::ProgressBar(bar)) = self.components.get_mut(index) {
+            if bar.visible != visible {
+                bar.visible = visible;
+                self.tag_sprites(bar_id.0);
+            }
+        }
     }
 
-    fn tag_dynamic_resource(&mut self, resource_index: usize) {
+    pub fn set_button_visible(&mut self, button_id: GuiButtonId, visible: bool) {
+        let index = button_id.0 as usize;
+        if let Some(GuiComponent::Button(button)) = self.components.get_mut(index) {
+            if button.visible != visible {
+                button.visible = visible;
+                self.tag_sprites(button_id.0);
+            }
+        }
+    }
+
+    /// Returns true if the component at `index` is part of a floating root
+    fn is_floating(&self, index: usize) -> bool {
+        let root_index = self.components_nodes[index].root_index as usize;
+        matches!(self.components_layout[root_index].align_self.origin, GuiLayoutOrigin::Floating)
+    }
+
+    fn tag_floating_roots(&mut self) {
+        for index in 0..self.components_nodes.len() {
+            if self.components_nodes[index].root_index as usize == index && self.is_floating(index) {
+                self.components_nodes[index].dirty = true;
+                self.components_nodes[index].sprites_dirty = true;
+            }
+        }
+    }
+
+    /// Regenerates the sprites of the root of the component at `index` without computing the layout again
+    fn tag_sprites(&mut self, index: u32) {
+        if let Some(node) = self.components_nodes.get(index as usize) {
+            let root_index = node.root_index as usize;
+            self.components_nodes[root_index].sprites_dirty = true;
+            self.update_flags.add(GuiUpdateFlags::GENERATE_SPRITES);
+        }
+    }
+
+    /// Progress values are not dynamic resources, so the bars using them are looked up
+    fn tag_progress(&mut self, progress_id: GuiProgressId) {
+        for index in 0..self.components.len() {
+            if let GuiComponent::ProgressBar(bar) = self.components[index] {
+                if bar.progress.index() == progress_id.index() {
+                    self.tag_sprites(index as u32);
+                }
+            }
+        }
+    }
+
+    /// Regenerates the sprites of the roots using a dynamic resource. The layout of the roots is only computed again
+    /// if the measured size of the resource changed.
+    fn tag_dynamic_resource(&mut self, resource_index: usize, resized: bool) {
         for &index in self.dynamic_resources[resource_index].users.iter() {
             let root_index = self.components_nodes[index as usize].root_index as usize;
-            self.components_nodes[root_index].dirty = true;
+            let root = &mut self.components_nodes[root_index];
+            root.sprites_dirty = true;
+            root.dirty |= resized;
+        }
+
+        match resized {
+            true => self.update_flags.add(GuiUpdateFlags::ALL),
+            false => self.update_flags.add(GuiUpdateFlags::GENERATE_SPRITES),
         }
     }
 
@@ -176,6 +451,8 @@ impl Default for Gui {
 
             images: Vec::with_capacity(16),
             text: Vec::with_capacity(16),
+            progress: Vec::with_capacity(8),
+            text_inputs: Vec::new(),
             dynamic_resources: Vec::with_capacity(8),
 
             components_nodes: Vec::with_capacity(16),
@@ -184,9 +461,21 @@ impl Default for Gui {
             components: Vec::with_capacity(16),
 
             output_sprites: Vec::with_capacity(64),
+            roots_sprites: Vec::with_capacity(8),
+            sprites_update: (0, 0),
+            sprites_scratch: Vec::with_capacity(64),
+            clips_scratch: Vec::new(),
+
+            events: Vec::with_capacity(4),
+            input_state: GuiInputState::default(),
+            floating: GuiFloatingLayer::default(),
     
             view_size: Size::default(),
+            pixel_ratio: 1.0,
+            user_scale: 1.0,
             update_flags: GuiUpdateFlags(0),
+
+            assets: None,
         }
     }
 
@@ -196,13 +485,19 @@ impl crate::store::SaveAndLoad for Gui {
     fn save(&self, writer: &mut crate::store::SaveFileWriter) {
         writer.write_slice(&self.images);
         writer.save_slice(&self.text);
+        writer.write_slice(&self.progress);
+        writer.save_slice(&self.text_inputs);
         writer.save_slice(&self.dynamic_resources);
         writer.write_slice(&self.components_nodes);
         writer.write_slice(&self.components_views);
         writer.write_slice(&self.components_layout);
         writer.write_slice(&self.components);
         writer.write_slice(&self.output_sprites);
+        writer.write(&self.input_state);
+        writer.write(&self.floating);
         writer.write(&self.view_size);
+        writer.write_f32(self.pixel_ratio);
+        writer.write_f32(self.user_scale);
         writer.write_u32(self.update_flags.0 as u32);
     }
 
@@ -212,14 +507,27 @@ impl crate::store::SaveAndLoad for Gui {
             builder_data,
             images: reader.read_vec(),
             text: reader.load_vec(),
+            progress: reader.read_vec(),
+            text_inputs: reader.load_vec(),
             dynamic_resources: reader.load_vec(),
             components_nodes: reader.read_vec(),
             components_views: reader.read_vec(),
             components_layout: reader.read_vec(),
             components: reader.read_vec(),
             output_sprites: reader.read_vec(),
+            // Sprites ranges are not saved, so all the sprites are generated again on the next update
+            roots_sprites: Vec::new(),
+            sprites_update: (0, 0),
+            sprites_scratch: Vec::new(),
+            clips_scratch: Vec::new(),
+            events: Vec::with_capacity(4),
+            input_state: reader.read(),
+            floating: reader.read(),
             view_size: reader.read(),
+            pixel_ratio: reader.read_f32(),
+            user_scale: reader.read_f32(),
             update_flags: GuiUpdateFlags(reader.read_u32() as u8),
+            assets: None,
         }
     }
 }
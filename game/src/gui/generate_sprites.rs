@@ -1,53 +1,297 @@
-use crate::shared::AABB;
-use super::{Gui, GuiComponent, GuiComponentView, GuiContainer, GuiImageDisplay, GuiLabel, GuiOutputSprite};
+use crate::shared::{Position, AABB};
+use crate::shared::{pos, size};
+use super::{Gui, GuiRootSprites, GuiButton, GuiColor, GuiComponent, GuiComponentView, GuiContainer, GuiImageDisplay, GuiLabel, GuiOutputSprite, GuiProgressBar, GuiTextInput};
+use super::gui_text_input::TEXT_INPUT_PADDING;
+use super::gui_scroll;
+
+/// Empty sprites reserved after the sprites of each root. A root can grow by this many sprites (ex: a counter gaining a digit)
+/// without moving the sprites of the following roots.
+const ROOT_SPRITES_SLACK: u32 = 16;
 
 pub(super) fn generate_sprites(gui: &mut Gui) {
     if !gui.update_flags.generate_sprites() {
         return;
     }
 
+    match gui.roots_sprites.is_empty() {
+        true => generate_all_roots(gui),
+        false => generate_dirty_roots(gui),
+    }
+}
+
+fn generate_all_roots(gui: &mut Gui) {
     gui.output_sprites.clear();
 
+    // Floating roots are generated last so that they are rendered above the other roots
     let component_count = gui.components.len();
-    for i in 0..component_count {
-        let view = gui.components_views[i];
-        let component = gui.components[i];
-        match component {
-            GuiComponent::Group | GuiComponent::Spacer(_) => {},
-            GuiComponent::Container(background) => { generate_container(gui, view, background); }
-            GuiComponent::Label(label) => { generate_label(gui, view, label); }
-            GuiComponent::ImageDisplay(image) => { generate_image_display(gui, view, image); }
+    for floating_pass in [false, true] {
+        let mut root = 0;
+        while root < component_count {
+            let end = root + (gui.components_nodes[root].descendants_count + 1) as usize;
+            if gui.is_floating(root) == floating_pass {
+                let first = gui.output_sprites.len();
+                generate_root_sprites(gui, root, end);
+
+                let count = (gui.output_sprites.len() - first) as u32;
+                let capacity = count + ROOT_SPRITES_SLACK;
+                gui.output_sprites.resize(first + capacity as usize, empty_sprite());
+                gui.roots_sprites.push(GuiRootSprites {
+                    root: root as u32,
+                    first: first as u32,
+                    count,
+                    capacity,
+                });
+            }
+
+            root = end;
         }
     }
+
+    add_sprites_update(gui, 0, gui.output_sprites.len() as u32);
+}
+
+/// Generates the sprites of the roots tagged with `GuiNode::sprites_dirty` in the range reserved for each root.
+/// If a root outgrows its range, the range is extended and the sprites of the following roots are moved and are part of the updated range.
+fn generate_dirty_roots(gui: &mut Gui) {
+    let mut update_start = u32::MAX;
+    let mut update_end = 0;
+    let mut offset = 0;
+
+    for root_sprites_index in 0..gui.roots_sprites.len() {
+        let mut root_sprites = gui.roots_sprites[root_sprites_index];
+        root_sprites.first += offset;
+
+        let root = root_sprites.root as usize;
+        if gui.components_nodes[root].sprites_dirty {
+            let end = root + (gui.components_nodes[root].descendants_count + 1) as usize;
+
+            // Sprites are generated at the end of the output, then moved to the root range
+            let generated_start = gui.output_sprites.len();
+            generate_root_sprites(gui, root, end);
+
+            let mut scratch = ::std::mem::take(&mut gui.sprites_scratch);
+            scratch.clear();
+            scratch.extend(gui.output_sprites.drain(generated_start..));
+
+            let first = root_sprites.first as usize;
+            let new_count = scratch.len() as u32;
+            let updated_count = match new_count > root_sprites.capacity {
+                true => {
+                    let capacity = new_count + ROOT_SPRITES_SLACK;
+                    let old_range = first..(first + root_sprites.capacity as usize);
+                    scratch.resize(capacity as usize, empty_sprite());
+                    gui.output_sprites.splice(old_range, scratch.drain(..));
+                    offset += capacity - root_sprites.capacity;
+                    root_sprites.capacity = capacity;
+                    capacity
+                },
+                false => {
+                    // Sprites left over from the last generation are cleared
+                    let cleared_count = u32::max(new_count, root_sprites.count);
+                    scratch.resize(cleared_count as usize, empty_sprite());
+                    gui.output_sprites[first..(first + cleared_count as usize)].copy_from_slice(&scratch);
+                    cleared_count
+                }
+            };
+
+            gui.sprites_scratch = scratch;
+
+            update_start = u32::min(update_start, root_sprites.first);
+            update_end = u32::max(update_end, root_sprites.first + updated_count);
+            root_sprites.count = new_count;
+        }
+
+        gui.roots_sprites[root_sprites_index] = root_sprites;
+    }
+
+    // The sprites after a root that outgrew its range were moved
+    if offset > 0 {
+        update_end = gui.output_sprites.len() as u32;
+    }
+
+    if update_start != u32::MAX {
+        add_sprites_update(gui, update_start, update_end);
+    }
+}
+
+/// Sprite with an empty area. Used to fill the unused part of the roots sprites ranges.
+fn empty_sprite() -> GuiOutputSprite {
+    GuiOutputSprite {
+        positions: AABB::default(),
+        texcoord: AABB::default(),
+        color: GuiColor::rgb(0, 0, 0),
+        flags: 0,
+    }
+}
+
+/// Merges `start..end` with the sprites updated since the last call to `Gui::take_sprites_update`
+fn add_sprites_update(gui: &mut Gui, start: u32, end: u32) {
+    let (old_start, old_end) = gui.sprites_update;
+    gui.sprites_update = match old_start == old_end {
+        true => (start, end),
+        false => (u32::min(old_start, start), u32::max(old_end, end)),
+    };
+}
+
+/// Generates the sprites of a root component. Hidden floating roots do not have any sprites.
+fn generate_root_sprites(gui: &mut Gui, root: usize, end: usize) {
+    gui.components_nodes[root].sprites_dirty = false;
+    if !gui.is_floating(root) || gui.floating.visible {
+        generate_root(gui, root, end);
+    }
+}
+
+/// Generates the sprites of the components in `start..end`. Sprites of the scroll frames children are clipped to the frames views.
+fn generate_root(gui: &mut Gui, start: usize, end: usize) {
+    // Scroll frames being generated: end of the frame children, clip rect of the children and frame index
+    let mut clips = ::std::mem::take(&mut gui.clips_scratch);
+    clips.clear();
+
+    for i in start..end {
+        while clips.last().map(|&(clip_end, _, _)| clip_end <= i ).unwrap_or(false) {
+            close_scroll_frame(gui, &mut clips);
+        }
+
+        let first_sprite = gui.output_sprites.len();
+        generate_component(gui, i);
+
+        let parent_clip = clips.last().map(|&(_, clip, _)| clip );
+        if let Some(clip) = parent_clip {
+            gui_scroll::clip_sprites(&mut gui.output_sprites, first_sprite, clip);
+        }
+
+        if let GuiComponent::ScrollFrame(_) = gui.components[i] {
+            let view = gui.components_views[i];
+            let mut clip = AABB::from_position_and_size(view.position, view.size);
+            if let Some(parent_clip) = parent_clip {
+                clip.left = f32::max(clip.left, parent_clip.left);
+                clip.top = f32::max(clip.top, parent_clip.top);
+                clip.right = f32::min(clip.right, parent_clip.right);
+                clip.bottom = f32::min(clip.bottom, parent_clip.bottom);
+            }
+
+            let children_end = i + (gui.components_nodes[i].descendants_count as usize) + 1;
+            clips.push((children_end, clip, i));
+        }
+    }
+
+    while !clips.is_empty() {
+        close_scroll_frame(gui, &mut clips);
+    }
+
+    gui.clips_scratch = clips;
+}
+
+/// The scrollbar of a scroll frame is generated after its children so that it is rendered above them
+fn close_scroll_frame(gui: &mut Gui, clips: &mut Vec<(usize, AABB, usize)>) {
+    let frame_index = match clips.pop() {
+        Some((_, _, frame_index)) => frame_index,
+        None => { return; }
+    };
+
+    let first_sprite = gui.output_sprites.len();
+    generate_scrollbar(gui, frame_index);
+
+    if let Some(&(_, clip, _)) = clips.last() {
+        gui_scroll::clip_sprites(&mut gui.output_sprites, first_sprite, clip);
+    }
+}
+
+fn generate_component(gui: &mut Gui, index: usize) {
+    let view = gui.components_views[index];
+    let component = gui.components[index];
+    match component {
+        // Scrollbars are generated in `close_scroll_frame`
+        GuiComponent::Group | GuiComponent::ScrollFrame(_) | GuiComponent::Spacer(_) => {},
+        GuiComponent::Container(background) => { generate_container(gui, view, background); }
+        GuiComponent::Label(label) => { generate_label(gui, view, label); }
+        GuiComponent::ImageDisplay(image) => { generate_image_display(gui, view, image); }
+        GuiComponent::Button(button) => { generate_button(gui, view, button); }
+        GuiComponent::ProgressBar(bar) => { generate_progress_bar(gui, view, bar); }
+        GuiComponent::TextInput(input) => { generate_text_input(gui, view, input, index); }
+    }
 }
 
 fn generate_container(gui: &mut Gui, view: GuiComponentView, container: GuiContainer) {
-    let positions = AABB::from_position_and_size(view.position, view.size);
     let image_index = container.background.index();
     let texcoord = gui.images[image_index].texcoord;
     let color = container.color;
+    let insets = container.insets;
+    let borders = insets.scaled(gui.scale());
 
-    gui.output_sprites.push(GuiOutputSprite {
-        positions,
-        texcoord,
-        color,
-        flags: 0,
-    });
+    if insets.left + insets.top + insets.right + insets.bottom == 0.0 {
+        gui.output_sprites.push(GuiOutputSprite {
+            positions: AABB::from_position_and_size(view.position, view.size),
+            texcoord,
+            color,
+            flags: 0,
+        });
+        return;
+    }
+
+    // If the container is smaller than the borders, the borders are scaled down so that they don't overlap
+    let scale_x = f32::min(1.0, view.size.width / f32::max(borders.left + borders.right, 1.0));
+    let scale_y = f32::min(1.0, view.size.height / f32::max(borders.top + borders.bottom, 1.0));
+
+    let p = view.position;
+    let s = view.size;
+    let xs = [p.x, p.x + borders.left * scale_x, p.x + s.width - borders.right * scale_x, p.x + s.width];
+    let ys = [p.y, p.y + borders.top * scale_y, p.y + s.height - borders.bottom * scale_y, p.y + s.height];
+
+    let t = texcoord;
+    let txs = [t.left, t.left + insets.left, t.right - insets.right, t.right];
+    let tys = [t.top, t.top + insets.top, t.bottom - insets.bottom, t.bottom];
+
+    for row in 0..3 {
+        for col in 0..3 {
+            let positions = AABB { left: xs[col], top: ys[row], right: xs[col+1], bottom: ys[row+1] };
+            if positions.right <= positions.left || positions.bottom <= positions.top {
+                continue;
+            }
+
+            gui.output_sprites.push(GuiOutputSprite {
+                positions,
+                texcoord: AABB { left: txs[col], top: tys[row], right: txs[col+1], bottom: tys[row+1] },
+                color,
+                flags: 0,
+            });
+        }
+    }
+}
+
+fn generate_scrollbar(gui: &mut Gui, index: usize) {
+    let frame = match gui.components[index] {
+        GuiComponent::ScrollFrame(frame) => frame,
+        _ => { return; }
+    };
+
+    let (track, thumb) = match gui_scroll::scrollbar(gui, index) {
+        Some(scrollbar) => scrollbar,
+        None => { return; }
+    };
+
+    for (positions, image) in [(track, frame.track), (thumb, frame.thumb)] {
+        gui.output_sprites.push(GuiOutputSprite {
+            positions,
+            texcoord: gui.images[image.index()].texcoord,
+            color: GuiColor::white(),
+            flags: 0,
+        });
+    }
 }
 
 fn generate_label(gui: &mut Gui, view: GuiComponentView, label: GuiLabel) {
+    let scale = gui.scale();
     let text_index = label.text.index();
     let text = &gui.text[text_index];
 
     for glyph in text.glyphs.iter() {
-        let mut positions = glyph.position;
-        positions.offset(view.position);
-
         gui.output_sprites.push(GuiOutputSprite {
-            positions,
+            positions: glyph_positions(glyph.position, scale, view.position),
             texcoord: glyph.texcoord,
             color: label.text_color,
-            flags: 1,
+            // Bit 0 tells the shader to sample a font, bit 1 selects the fallback font
+            flags: 1 | ((glyph.font.min(1) as u8) << 1),
         });
     }
 }
@@ -63,3 +307,175 @@ fn generate_image_display(gui: &mut Gui, view: GuiComponentView, display: GuiIma
         flags: 0,
     });
 }
+
+fn generate_progress_bar(gui: &mut Gui, view: GuiComponentView, bar: GuiProgressBar) {
+    if !bar.visible {
+        return;
+    }
+
+    let progress = gui.progress[bar.progress.index()];
+
+    gui.output_sprites.push(GuiOutputSprite {
+        positions: AABB::from_position_and_size(view.position, view.size),
+        texcoord: gui.images[bar.background.index()].texcoord,
+        color: super::GuiColor::white(),
+        flags: 0,
+    });
+
+    if progress.ratio <= 0.0 {
+        return;
+    }
+
+    let mut texcoord = gui.images[bar.fill.index()].texcoord;
+    texcoord.right = texcoord.left + (texcoord.width() * progress.ratio);
+
+    gui.output_sprites.push(GuiOutputSprite {
+        positions: AABB::from_position_and_size(view.position, size(view.size.width * progress.ratio, view.size.height)),
+        texcoord,
+        color: progress.color,
+        flags: 0,
+    });
+}
+
+fn generate_text_input(gui: &mut Gui, view: GuiComponentView, input: GuiTextInput, index: usize) {
+    const SELECTION_COLOR: GuiColor = GuiColor::rgb(120, 160, 230);
+    const CARET_WIDTH: f32 = 2.0;
+
+    let background = gui.images[input.background.index()].texcoord;
+    gui.output_sprites.push(GuiOutputSprite {
+        positions: AABB::from_position_and_size(view.position, view.size),
+        texcoord: background,
+        color: GuiColor::white(),
+        flags: 0,
+    });
+
+    // The text input state is in css pixels
+    let scale = gui.scale();
+    let state = &gui.text_inputs[input.state as usize];
+    let focused = gui.input_state.focused_input == index as u32;
+    let text_height = f32::max(state.metrics.size.height, input.font_size) * scale;
+    let inner_left = view.position.x + (TEXT_INPUT_PADDING * scale);
+    let inner_right = view.position.x + view.size.width - (TEXT_INPUT_PADDING * scale);
+    let origin = pos(inner_left - (state.scroll * scale), view.position.y + ((view.size.height - text_height) / 2.0));
+
+    // The selection and the caret are drawn using a single texel from the center of the background tinted with a solid color
+    let center = background.center();
+    let solid_texcoord = AABB { left: center.x, top: center.y, right: center.x, bottom: center.y };
+    let offset = |caret: u32| state.caret_offsets.get(caret as usize).copied().unwrap_or(0.0) * scale;
+
+    let (selection_start, selection_end) = state.selection();
+    if focused && selection_start != selection_end {
+        let left = f32::max(origin.x + offset(selection_start), inner_left);
+        let right = f32::min(origin.x + offset(selection_end), inner_right);
+        gui.output_sprites.push(GuiOutputSprite {
+            positions: AABB { left, top: origin.y, right, bottom: origin.y + text_height },
+            texcoord: solid_texcoord,
+            color: SELECTION_COLOR,
+            flags: 0,
+        });
+    }
+
+    for glyph in state.metrics.glyphs.iter() {
+        let positions = glyph_positions(glyph.position, scale, origin);
+
+        // Glyphs outside of the field are not rendered
+        if positions.left < inner_left || positions.right > inner_right {
+            continue;
+        }
+
+        gui.output_sprites.push(GuiOutputSprite {
+            positions,
+            texcoord: glyph.texcoord,
+            color: input.text_color,
+            flags: 1 | ((glyph.font.min(1) as u8) << 1),
+        });
+    }
+
+    if focused {
+        let x = origin.x + offset(state.caret);
+        gui.output_sprites.push(GuiOutputSprite {
+            positions: AABB::from_position_and_size(pos(x, origin.y), size(CARET_WIDTH * scale, text_height)),
+            texcoord: solid_texcoord,
+            color: input.text_color,
+            flags: 0,
+        });
+    }
+}
+
+fn generate_button(gui: &mut Gui, view: GuiComponentView, button: GuiButton) {
+    if !button.visible {
+        return;
+    }
+
+    let positions = AABB::from_position_and_size(view.position, view.size);
+    let image_index = button.image().index();
+    let texcoord = gui.images[image_index].texcoord;
+    gui.output_sprites.push(GuiOutputSprite {
+        positions,
+        texcoord,
+        color: super::GuiColor::white(),
+        flags: 0,
+    });
+
+    // Icons are centered in the button and scaled down to fit the button inner area
+    if let Some(icon) = button.icon {
+        const ICON_SCALE: f32 = 0.7;
+        let texcoord = gui.images[icon.index()].texcoord;
+        let icon_size = texcoord.size();
+        if icon_size.width > 0.0 && icon_size.height > 0.0 {
+            let max_width = view.size.width * ICON_SCALE;
+            let max_height = view.size.height * ICON_SCALE;
+            let scale = f32::min(max_width / icon_size.width, max_height / icon_size.height);
+            let width = icon_size.width * scale;
+            let height = icon_size.height * scale;
+            let position = pos(
+                view.position.x + ((view.size.width - width) / 2.0),
+                view.position.y + ((view.size.height - height) / 2.0),
+            );
+
+            gui.output_sprites.push(GuiOutputSprite {
+                positions: AABB::from_position_and_size(position, size(width, height)),
+                texcoord,
+                color: super::GuiColor::white(),
+                flags: 0,
+            });
+        }
+    }
+
+    // Labels are centered in the button. If the button has an icon, the label goes in the bottom right corner.
+    if let Some(label) = button.label {
+        const LABEL_INSET: f32 = 6.0;
+        let scale = gui.scale();
+        let label_inset = LABEL_INSET * scale;
+        let text_size = gui.text[label.text.index()].size;
+        let text_size = size(text_size.width * scale, text_size.height * scale);
+        let position = match button.icon.is_some() {
+            true => pos(
+                view.position.x + view.size.width - text_size.width - label_inset,
+                view.position.y + view.size.height - text_size.height - label_inset,
+            ),
+            false => pos(
+                view.position.x + ((view.size.width - text_size.width) / 2.0),
+                view.position.y + ((view.size.height - text_size.height) / 2.0),
+            )
+        };
+
+        let label_view = GuiComponentView {
+            position,
+            size: text_size,
+            items_size: text_size,
+        };
+
+        generate_label(gui, label_view, label);
+    }
+}
+
+/// Glyph positions are computed by the fonts in css pixels. MSDF glyphs stay sharp when they are scaled.
+fn glyph_positions(glyph: AABB, scale: f32, origin: Position<f32>) -> AABB {
+    AABB {
+        left: origin.x + (glyph.left * scale),
+        top: origin.y + (glyph.top * scale),
+        right: origin.x + (glyph.right * scale),
+        bottom: origin.y + (glyph.bottom * scale),
+    }
+}
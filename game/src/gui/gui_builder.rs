@@ -5,6 +5,8 @@ use super::*;
 pub struct GuiBuilderStack {
     pub layout: GuiLayout,
     pub items_size: Size<f32>,
+    /// Size of the last row of items in a `RowWrap` container
+    pub line_size: Size<f32>,
     pub children_count: u32,
     pub descendants_count: u32,
 }
@@ -45,6 +47,25 @@ impl<'a> GuiBuilder<'a> {
         &mut self,
         background: GuiResourceId<GuiImage>,
         callback: CB
+    ) {
+        self.frame(background, GuiPadding::default(), callback);
+    }
+
+    /// A container using an image with nine-slice borders as background. See `GuiContainer::insets`
+    pub fn nine_slice_frame<CB: FnOnce(&mut GuiBuilder)>(
+        &mut self,
+        background: GuiResourceId<GuiImage>,
+        insets: GuiPadding,
+        callback: CB
+    ) {
+        self.frame(background, insets, callback);
+    }
+
+    fn frame<CB: FnOnce(&mut GuiBuilder)>(
+        &mut self,
+        background: GuiResourceId<GuiImage>,
+        insets: GuiPadding,
+        callback: CB
     ) {
         let node = self.new_gui_node();
         let layout = self.next_layout();
@@ -52,7 +73,8 @@ impl<'a> GuiBuilder<'a> {
         
         let container = GuiContainer {
             background,
-            color: GuiColor::white()
+            color: GuiColor::white(),
+            insets,
         };
 
         self.gui.components.push(GuiComponent::Container(container));
@@ -75,6 +97,39 @@ impl<'a> GuiBuilder<'a> {
         self.update_root_node();
     }
 
+    /// A container clipping and scrolling its children. See `GuiScrollFrame`
+    pub fn scroll_frame<CB: FnOnce(&mut GuiBuilder)>(&mut self, frame: GuiScrollFrame, callback: CB) {
+        for image in [frame.track, frame.thumb] {
+            if self.gui.images.get(image.index()).is_none() {
+                self.set_error(gui_err!("Unknown image with ID {:?} in gui", image.index()));
+                return;
+            }
+        }
+
+        let node = self.new_gui_node();
+        let layout = self.next_layout();
+        let index = self.gui.components.len();
+
+        self.gui.components.push(GuiComponent::ScrollFrame(frame));
+        self.gui.components_nodes.push(node);
+        self.gui.components_views.push(GuiComponentView::default());
+        self.gui.components_layout.push(layout);
+
+        self.push_stack(layout);
+
+        callback(self);
+
+        let items_params = self.pop_stack();
+        let view = Self::container_view_from_layout(&layout, items_params.items_size);
+        self.gui.components_nodes[index].children_count = items_params.children_count;
+        self.gui.components_nodes[index].descendants_count = items_params.descendants_count;
+        self.gui.components_views[index] = view;
+        
+        self.update_parent_items_size(view.size);
+        self.update_parent_children_count(items_params.descendants_count);
+        self.update_root_node();
+    }
+
     /// An invisible container
     pub fn group<CB: FnOnce(&mut GuiBuilder)>(&mut self, callback: CB) {
         let node = self.new_gui_node();
@@ -195,6 +250,137 @@ impl<'a> GuiBuilder<'a> {
         self.update_root_node();
     }
 
+    /// A bar displaying a progress. The bar size is the size of the `background` image unless a static sizing is used.
+    pub fn progress_bar(&mut self, bar: GuiProgressBar) -> GuiProgressBarId {
+        let image_id = bar.background.index();
+        if self.gui.images.get(image_id).is_none() || self.gui.images.get(bar.fill.index()).is_none() {
+            self.set_error(gui_err!("Unknown image with ID {:?} in gui", image_id));
+            return GuiProgressBarId::default();
+        }
+
+        if self.gui.progress.get(bar.progress.index()).is_none() {
+            self.set_error(gui_err!("Unknown progress with ID {:?} in gui", bar.progress.index()));
+            return GuiProgressBarId::default();
+        }
+
+        let node = self.new_gui_node();
+        let layout = self.next_layout();
+        let index = self.gui.components.len();
+
+        for image in [bar.background, bar.fill] {
+            if image.is_dyn() {
+                self.gui.dynamic_resources[image.dyn_index()].users.push(index as u32);
+            }
+        }
+
+        let component_size = match layout.align_self.sizing {
+            GuiSizing::Static { width, height } => size(width, height),
+            _ => self.gui.images[image_id].texcoord.size(),
+        };
+
+        self.gui.components.push(GuiComponent::ProgressBar(bar));
+        self.gui.components_nodes.push(node);
+        self.gui.components_layout.push(layout);
+        self.gui.components_views.push(GuiComponentView {
+            position: pos(0.0, 0.0),
+            size: component_size,
+            items_size: size(0.0, 0.0),
+        });
+
+        self.update_parent_items_size(component_size);
+        self.update_parent_children_count(0);
+        self.update_root_node();
+
+        GuiProgressBarId(index as u32)
+    }
+
+    /// A single line text field. See `GuiTextInput`
+    pub fn text_input(&mut self, mut input: GuiTextInput) -> GuiTextInputId {
+        let image_id = input.background.index();
+        if self.gui.images.get(image_id).is_none() {
+            self.set_error(gui_err!("Unknown image with ID {:?} in gui", image_id));
+            return GuiTextInputId::default();
+        }
+
+        let node = self.new_gui_node();
+        let layout = self.next_layout();
+        let index = self.gui.components.len();
+
+        if input.background.is_dyn() {
+            self.gui.dynamic_resources[input.background.dyn_index()].users.push(index as u32);
+        }
+
+        input.state = self.gui.text_inputs.len() as u32;
+        self.gui.text_inputs.push(GuiTextInputState::default());
+
+        let component_size = match layout.align_self.sizing {
+            GuiSizing::Static { width, height } => size(width, height),
+            _ => self.gui.images[image_id].texcoord.size(),
+        };
+
+        self.gui.components.push(GuiComponent::TextInput(input));
+        self.gui.components_nodes.push(node);
+        self.gui.components_layout.push(layout);
+        self.gui.components_views.push(GuiComponentView {
+            position: pos(0.0, 0.0),
+            size: component_size,
+            items_size: size(0.0, 0.0),
+        });
+
+        self.update_parent_items_size(component_size);
+        self.update_parent_children_count(0);
+        self.update_root_node();
+
+        GuiTextInputId(index as u32)
+    }
+
+    /// A clickable image. The button size is the size of the `normal` image unless a static sizing is used.
+    pub fn button(&mut self, button: GuiButton) -> GuiButtonId {
+        let image_id = button.normal.index();
+        if self.gui.images.get(image_id).is_none() {
+            self.set_error(gui_err!("Unknown image with ID {:?} in gui", image_id));
+            return GuiButtonId::default();
+        }
+
+        let node = self.new_gui_node();
+        let layout = self.next_layout();
+        let index = self.gui.components.len();
+
+        let icon = button.icon.unwrap_or_default();
+        for image in [button.normal, button.hover, button.pressed, icon] {
+            if image.is_dyn() {
+                self.gui.dynamic_resources[image.dyn_index()].users.push(index as u32);
+            }
+        }
+
+        if let Some(label) = button.label {
+            if label.text.is_dyn() {
+                self.gui.dynamic_resources[label.text.dyn_index()].users.push(index as u32);
+            }
+        }
+
+        self.gui.components.push(GuiComponent::Button(button));
+        self.gui.components_nodes.push(node);
+        self.gui.components_layout.push(layout);
+
+        let component_size = match layout.align_self.sizing {
+            GuiSizing::Static { width, height } => size(width, height),
+            _ => self.gui.images[image_id].texcoord.size(),
+        };
+
+        self.gui.components_views.push(GuiComponentView {
+            position: pos(0.0, 0.0),
+            size: component_size,
+            items_size: size(0.0, 0.0),
+        });
+
+        self.update_parent_items_size(component_size);
+        self.update_parent_children_count(0);
+        self.update_root_node();
+
+        GuiButtonId(index as u32)
+    }
+
     //
     // Layout
     //
@@ -211,12 +397,19 @@ impl<'a> GuiBuilder<'a> {
         self.data.next_layout.align_self.padding = padding;
     }
 
+    pub fn margin(&mut self, margin: GuiMargin) {
+        self.data.next_layout.align_self.margin = margin;
+    }
+
     pub fn items_align(&mut self, direction: ItemsDirection, position: ItemsPosition, alignment: ItemsAlign) {
-        self.data.next_layout.align_items = GuiAlignItems {
-            direction,
-            alignment,
-            position,
-        };
+        let align_items = &mut self.data.next_layout.align_items;
+        align_items.direction = direction;
+        align_items.alignment = alignment;
+        align_items.position = position;
+    }
+
+    pub fn items_spacing(&mut self, spacing: f32) {
+        self.data.next_layout.align_items.spacing = spacing;
     }
 
     //
@@ -237,6 +430,12 @@ impl<'a> GuiBuilder<'a> {
         GuiResourceId::new_dyn(image_index, image_dyn_index)
     }
 
+    /// Add a progress to the gui. Progress values are always dynamic. See `Gui::set_progress`
+    pub fn dyn_progress(&mut self, color: GuiColor) -> GuiProgressId {
+        self.gui.progress.push(GuiProgress { ratio: 0.0, color });
+        GuiResourceId::new(self.gui.progress.len() - 1)
+    }
+
     pub fn static_text(&mut self, text: TextMetrics) -> GuiResourceId<TextMetrics> {
         self.gui.text.push(text);
         GuiResourceId::new(self.gui.text.len() - 1)
@@ -255,7 +454,7 @@ impl<'a> GuiBuilder<'a> {
     // Helpers
     //
 
-    fn set_error(&mut self, err: Error) {
+    pub(super) fn set_error(&mut self, err: Error) {
         match &mut self.data.error {
             Some(error) => { error.merge(err); }
             None => { self.data.error = Some(err); }
@@ -266,6 +465,7 @@ impl<'a> GuiBuilder<'a> {
         self.data.build_stack.push(GuiBuilderStack { 
             layout,
             items_size: size(0.0, 0.0),
+            line_size: size(0.0, 0.0),
             children_count: 0,
             descendants_count: 0
         })
@@ -292,6 +492,19 @@ impl<'a> GuiBuilder<'a> {
             ItemsDirection::Row => {
                 build.items_size.width += child_size.width;
                 build.items_size.height = f32::max(build.items_size.height, child_size.height);
+            },
+            ItemsDirection::RowWrap => {
+                let mut lines_height = build.items_size.height - build.line_size.height;
+                if build.line_size.width > 0.0 && build.line_size.width + child_size.width > build.layout.wrap_width() {
+                    lines_height += build.line_size.height;
+                    build.line_size = child_size;
+                } else {
+                    build.line_size.width += child_size.width;
+                    build.line_size.height = f32::max(build.line_size.height, child_size.height);
+                }
+
+                build.items_size.width = f32::max(build.items_size.width, build.line_size.width);
+                build.items_size.height = lines_height + build.line_size.height;
             }
         }
     }
@@ -312,7 +525,7 @@ impl<'a> GuiBuilder<'a> {
     fn container_view_from_layout(layout: &GuiLayout, items_size: Size<f32>) -> GuiComponentView {
         let component_size = match layout.align_self.sizing {
             GuiSizing::Static { width, height } => size(width, height),
-            GuiSizing::Auto => items_size
+            _ => items_size
         };
 
         GuiComponentView {
@@ -332,6 +545,7 @@ impl<'a> GuiBuilder<'a> {
             children_count: 0,
             descendants_count: 0,
             dirty: true,
+            sprites_dirty: true,
         }
     }
 
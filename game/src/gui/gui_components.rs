@@ -1,10 +1,15 @@
+use crate::assets::TextAlign;
 use crate::shared::{Position, Size};
-use super::{GuiColor, GuiImageId, GuiStaticTextId};
+use super::{GuiColor, GuiImageId, GuiPadding, GuiProgressId, GuiStaticTextId};
 
 #[derive(Copy, Clone)]
 pub struct GuiLabel {
     pub text: GuiStaticTextId,
     pub text_color: GuiColor,
+    /// Alignment of the lines of the text. Used by `Gui::set_label_text`
+    pub align: TextAlign,
+    /// Text is wrapped on multiple lines past this width. Used by `Gui::set_label_text`
+    pub wrap_width: f32,
 }
 
 impl GuiLabel {
@@ -13,9 +18,21 @@ impl GuiLabel {
         GuiLabel {
             text,
             text_color,
+            align: TextAlign::Left,
+            wrap_width: f32::INFINITY,
         }
     }
 
+    pub fn with_align(mut self, align: TextAlign) -> Self {
+        self.align = align;
+        self
+    }
+
+    pub fn with_wrap_width(mut self, wrap_width: f32) -> Self {
+        self.wrap_width = wrap_width;
+        self
+    }
+
 }
 
 #[derive(Copy, Clone)]
@@ -47,15 +64,175 @@ impl GuiImageDisplay {
 pub struct GuiContainer {
     pub background: GuiImageId,
     pub color: GuiColor,
+    /// Nine-slice borders of the background, in pixels. The corners keep their size, the edges and the center are stretched.
+    /// If all the insets are zero, the background is stretched over the whole container.
+    pub insets: GuiPadding,
+}
+
+/// A bar filled from left to right. The fill image is cropped to the progress ratio and tinted with the progress color.
+/// The bar size is the size of the `background` image unless a static sizing is used.
+#[derive(Copy, Clone)]
+pub struct GuiProgressBar {
+    pub background: GuiImageId,
+    pub fill: GuiImageId,
+    pub progress: GuiProgressId,
+    pub visible: bool,
+}
+
+impl GuiProgressBar {
+
+    pub fn from_images(background: GuiImageId, fill: GuiImageId, progress: GuiProgressId) -> Self {
+        GuiProgressBar { background, fill, progress, visible: true }
+    }
+
+}
+
+/// Identifies a progress bar in the gui. This is the index of the progress bar component.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GuiProgressBarId(pub(super) u32);
+
+impl Default for GuiProgressBarId {
+    fn default() -> Self {
+        GuiProgressBarId(u32::MAX)
+    }
+}
+
+/// A container clipping its children to its view. The children are scrolled vertically using the mouse wheel or by dragging the scrollbar.
+/// Scroll frames should not use the `Auto` sizing, otherwise they grow to fit their children and never scroll.
+/// The scrollbar is drawn over the right side of the frame, use a right padding of `SCROLLBAR_WIDTH` to keep it clear.
+#[derive(Copy, Clone)]
+pub struct GuiScrollFrame {
+    pub track: GuiImageId,
+    pub thumb: GuiImageId,
+    /// Vertical scrolling of the children, in pixels
+    pub scroll: f32,
+}
+
+impl GuiScrollFrame {
+
+    pub fn from_images(track: GuiImageId, thumb: GuiImageId) -> Self {
+        GuiScrollFrame { track, thumb, scroll: 0.0 }
+    }
+
+}
+
+/// A single line text field. Clicking the field gives it the keyboard focus.
+/// The field size is the size of the `background` image unless a static sizing is used.
+#[derive(Copy, Clone)]
+pub struct GuiTextInput {
+    pub background: GuiImageId,
+    pub text_color: GuiColor,
+    pub font_size: f32,
+    /// Maximum number of characters in the field
+    pub max_length: u32,
+    /// Index of the edition state of the field in the gui. Set by the builder.
+    pub(super) state: u32,
+}
+
+impl GuiTextInput {
+
+    pub fn from_image_and_color(background: GuiImageId, text_color: GuiColor) -> Self {
+        GuiTextInput {
+            background,
+            text_color,
+            font_size: 20.0,
+            max_length: 32,
+            state: u32::MAX,
+        }
+    }
+
+    pub fn with_font_size(mut self, font_size: f32) -> Self {
+        self.font_size = font_size;
+        self
+    }
+
+}
+
+/// Identifies a text input in the gui. This is the index of the text input component.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GuiTextInputId(pub(super) u32);
+
+impl Default for GuiTextInputId {
+    fn default() -> Self {
+        GuiTextInputId(u32::MAX)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GuiButtonState {
+    Normal,
+    Hovered,
+    Pressed,
+}
+
+#[derive(Copy, Clone)]
+pub struct GuiButton {
+    pub normal: GuiImageId,
+    pub hover: GuiImageId,
+    pub pressed: GuiImageId,
+    pub icon: Option<GuiImageId>,
+    pub label: Option<GuiLabel>,
+    pub state: GuiButtonState,
+    pub visible: bool,
+}
+
+impl GuiButton {
+
+    pub fn from_images(normal: GuiImageId, hover: GuiImageId, pressed: GuiImageId) -> Self {
+        GuiButton {
+            normal,
+            hover,
+            pressed,
+            icon: None,
+            label: None,
+            state: GuiButtonState::Normal,
+            visible: true,
+        }
+    }
+
+    /// Adds an icon drawn over the button background. The icon is scaled to fit the button.
+    pub fn with_icon(mut self, icon: GuiImageId) -> Self {
+        self.icon = Some(icon);
+        self
+    }
+
+    pub fn with_label(mut self, label: GuiLabel) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    /// Returns the image matching the current state of the button
+    pub fn image(&self) -> GuiImageId {
+        match self.state {
+            GuiButtonState::Normal => self.normal,
+            GuiButtonState::Hovered => self.hover,
+            GuiButtonState::Pressed => self.pressed,
+        }
+    }
+
+}
+
+/// Identifies a button in the gui. This is the index of the button component.
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct GuiButtonId(pub(super) u32);
+
+impl Default for GuiButtonId {
+    fn default() -> Self {
+        GuiButtonId(u32::MAX)
+    }
 }
 
 #[derive(Copy, Clone)]
 pub enum GuiComponent {
     Group,
     Container(GuiContainer),
+    ScrollFrame(GuiScrollFrame),
     Spacer(Size<f32>),
     Label(GuiLabel),
     ImageDisplay(GuiImageDisplay),
+    Button(GuiButton),
+    ProgressBar(GuiProgressBar),
+    TextInput(GuiTextInput),
 }
 
 #[derive(Copy, Clone)]
@@ -69,6 +246,9 @@ pub struct GuiNode {
     /// If the component layout needs to be recomputed
     /// Right now this is only checked for root nodes
     pub dirty: bool,
+    /// If the sprites of the component need to be generated again. Only checked for root nodes.
+    /// Positioning a component also sets this flag.
+    pub sprites_dirty: bool,
 }
 
 #[derive(Copy, Clone, Default, Debug)]
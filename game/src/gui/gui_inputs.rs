@@ -0,0 +1,202 @@
+use crate::inputs::{ButtonState, InputState, MouseButton};
+use crate::shared::{Position, AABB};
+use super::{gui_scroll, gui_text_input, Gui, GuiButtonId, GuiButtonState, GuiComponent, GuiTextInputId};
+
+/// Events generated by the gui while processing the user inputs
+#[derive(Copy, Clone, Debug)]
+pub enum GuiEvent {
+    ButtonClicked(GuiButtonId),
+    /// The user pressed `Enter` in a text input
+    TextInputSubmitted(GuiTextInputId),
+}
+
+/// Mouse interaction state of the gui. Indices are components index, `u32::MAX` means none.
+#[derive(Copy, Clone)]
+pub struct GuiInputState {
+    pub hovered_button: u32,
+    pub pressed_button: u32,
+    /// Text input receiving the keyboard inputs
+    pub focused_input: u32,
+    /// Scroll frame with its scrollbar thumb being dragged
+    pub dragged_scroll_frame: u32,
+    /// Distance between the mouse and the top of the dragged thumb
+    pub drag_offset: f32,
+    pub mouse_over: bool,
+}
+
+impl Default for GuiInputState {
+    fn default() -> Self {
+        GuiInputState {
+            hovered_button: u32::MAX,
+            pressed_button: u32::MAX,
+            focused_input: u32::MAX,
+            dragged_scroll_frame: u32::MAX,
+            drag_offset: 0.0,
+            mouse_over: false,
+        }
+    }
+}
+
+pub(super) fn process_inputs(gui: &mut Gui, inputs: &InputState) {
+    gui.events.clear();
+
+    if gui.components.len() == 0 {
+        gui.input_state = GuiInputState::default();
+        return;
+    }
+
+    let mouse_position = inputs.mouse_position;
+    let old_state = gui.input_state;
+    let mut new_state = old_state;
+
+    new_state.hovered_button = button_at(gui, mouse_position);
+    new_state.mouse_over = new_state.hovered_button != u32::MAX || solid_component_at(gui, mouse_position);
+
+    match inputs.mouse_button_state(MouseButton::Left) {
+        ButtonState::JustPressed => {
+            new_state.pressed_button = new_state.hovered_button;
+            if new_state.hovered_button == u32::MAX {
+                start_scroll_drag(gui, mouse_position, &mut new_state);
+            }
+
+            new_state.focused_input = text_input_at(gui, mouse_position);
+            if new_state.focused_input != old_state.focused_input {
+                gui.tag_sprites(old_state.focused_input);
+                gui.tag_sprites(new_state.focused_input);
+            }
+        },
+        ButtonState::JustReleased => {
+            if new_state.pressed_button != u32::MAX && new_state.pressed_button == new_state.hovered_button {
+                gui.events.push(GuiEvent::ButtonClicked(GuiButtonId(new_state.pressed_button)));
+            }
+
+            new_state.pressed_button = u32::MAX;
+            new_state.dragged_scroll_frame = u32::MAX;
+        },
+        _ => {}
+    }
+
+    // A button being pressed or a scrollbar being dragged keeps capturing the mouse until it is released
+    new_state.mouse_over |= new_state.pressed_button != u32::MAX || new_state.dragged_scroll_frame != u32::MAX;
+
+    gui.input_state = new_state;
+
+    if new_state.dragged_scroll_frame != u32::MAX {
+        gui_scroll::drag_thumb(gui, new_state.dragged_scroll_frame as usize, mouse_position.y - new_state.drag_offset);
+    }
+
+    if inputs.mouse_wheel != 0.0 {
+        let frame_index = gui_scroll::scroll_frame_at(gui, mouse_position);
+        if let Some(GuiComponent::ScrollFrame(frame)) = gui.components.get(frame_index as usize) {
+            // The wheel delta is in css pixels
+            let scroll = frame.scroll + (inputs.mouse_wheel * gui.scale());
+            gui_scroll::scroll_to(gui, frame_index as usize, scroll);
+        }
+    }
+
+    for index in [old_state.hovered_button, old_state.pressed_button, new_state.hovered_button, new_state.pressed_button] {
+        update_button_state(gui, index);
+    }
+
+    if inputs.left_mouse_clicked() && new_state.focused_input != u32::MAX {
+        gui_text_input::focus_at(gui, new_state.focused_input, mouse_position.x);
+    }
+
+    gui_text_input::process_keyboard(gui, inputs);
+}
+
+fn update_button_state(gui: &mut Gui, index: u32) {
+    let input_state = gui.input_state;
+    let button = match gui.components.get_mut(index as usize) {
+        Some(GuiComponent::Button(button)) => button,
+        _ => { return; }
+    };
+
+    let new_state = if input_state.pressed_button == index {
+        match input_state.hovered_button == index {
+            true => GuiButtonState::Pressed,
+            false => GuiButtonState::Hovered,
+        }
+    } else if input_state.hovered_button == index && input_state.pressed_button == u32::MAX {
+        GuiButtonState::Hovered
+    } else {
+        GuiButtonState::Normal
+    };
+
+    if button.state != new_state {
+        button.state = new_state;
+        gui.tag_sprites(index);
+    }
+}
+
+/// Returns the index of the button under `position` or `u32::MAX` if there is none.
+/// Floating roots never receive inputs.
+fn button_at(gui: &Gui, position: Position<f32>) -> u32 {
+    for (index, component) in gui.components.iter().enumerate().rev() {
+        if let GuiComponent::Button(button) = component {
+            if !button.visible || gui.is_floating(index) {
+                continue;
+            }
+
+            let view = gui.components_views[index];
+            if AABB::from_position_and_size(view.position, view.size).point_inside(position) && gui_scroll::visible_at(gui, index, position) {
+                return index as u32;
+            }
+        }
+    }
+
+    u32::MAX
+}
+
+/// Starts dragging the scrollbar thumb under `position`. Clicking the track outside of the thumb moves the thumb under the mouse.
+fn start_scroll_drag(gui: &Gui, position: Position<f32>, state: &mut GuiInputState) {
+    let frame_index = gui_scroll::scroll_frame_at(gui, position);
+    if frame_index == u32::MAX {
+        return;
+    }
+
+    if let Some((track, thumb)) = gui_scroll::scrollbar(gui, frame_index as usize) {
+        if thumb.point_inside(position) {
+            state.dragged_scroll_frame = frame_index;
+            state.drag_offset = position.y - thumb.top;
+        } else if track.point_inside(position) {
+            state.dragged_scroll_frame = frame_index;
+            state.drag_offset = thumb.height() / 2.0;
+        }
+    }
+}
+
+/// Returns the index of the text input under `position` or `u32::MAX` if there is none
+fn text_input_at(gui: &Gui, position: Position<f32>) -> u32 {
+    for (index, component) in gui.components.iter().enumerate().rev() {
+        if let GuiComponent::TextInput(_) = component {
+            if gui.is_floating(index) {
+                continue;
+            }
+
+            let view = gui.components_views[index];
+            if AABB::from_position_and_size(view.position, view.size).point_inside(position) && gui_scroll::visible_at(gui, index, position) {
+                return index as u32;
+            }
+        }
+    }
+
+    u32::MAX
+}
+
+/// Returns true if a visible component is under `position`. Invisible components (groups, spacers) are ignored.
+fn solid_component_at(gui: &Gui, position: Position<f32>) -> bool {
+    gui.components.iter().enumerate().any(|(index, component)| {
+        match component {
+            _ if gui.is_floating(index) => false,
+            GuiComponent::Button(button) if !button.visible => false,
+            GuiComponent::ProgressBar(bar) if !bar.visible => false,
+            GuiComponent::Container(_) | GuiComponent::ScrollFrame(_) | GuiComponent::ImageDisplay(_) | GuiComponent::Button(_) |
+            GuiComponent::ProgressBar(_) | GuiComponent::TextInput(_) => {
+                let view = gui.components_views[index];
+                AABB::from_position_and_size(view.position, view.size).point_inside(position) && gui_scroll::visible_at(gui, index, position)
+            },
+            _ => false
+        }
+    })
+}
@@ -1,9 +1,11 @@
 use std::marker::PhantomData;
 use crate::assets::TextMetrics;
 use crate::shared::AABB;
+use super::GuiColor;
 
 pub type GuiImageId = GuiResourceId<GuiImage>;
 pub type GuiStaticTextId = GuiResourceId<TextMetrics>;
+pub type GuiProgressId = GuiResourceId<GuiProgress>;
 
 /// Id representing a resource type in the gui
 pub struct GuiResourceId<T> {
@@ -53,6 +55,14 @@ impl GuiImage {
     }
 }
 
+/// Value displayed by a progress bar. Updating a progress does not recompute the gui layout.
+#[derive(Copy, Clone)]
+pub struct GuiProgress {
+    /// Filled part of the bar, from 0.0 to 1.0
+    pub ratio: f32,
+    pub color: GuiColor,
+}
+
 pub struct DynamicResource {
     /// List of component index using this resource
     pub users: Vec<u32>
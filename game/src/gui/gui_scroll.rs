@@ -0,0 +1,139 @@
+use crate::shared::{Position, AABB};
+use super::{Gui, GuiComponent, GuiOutputSprite, GuiUpdateFlags};
+
+/// Width of the scrollbar drawn on the right side of the scroll frames
+pub const SCROLLBAR_WIDTH: f32 = 10.0;
+
+const MIN_THUMB_HEIGHT: f32 = 20.0;
+
+/// Maximum scrolling of the scroll frame at `index`. Zero if the children fit in the frame.
+pub(super) fn max_scroll(gui: &Gui, index: usize) -> f32 {
+    let view = gui.components_views[index];
+    let padding = gui.components_layout[index].align_self.padding.scaled(gui.scale());
+    let content_height = view.items_size.height + padding.top + padding.bottom;
+    f32::max(content_height - view.size.height, 0.0)
+}
+
+/// Returns the track and the thumb of the scrollbar of the scroll frame at `index`.
+/// Returns `None` if the children fit in the frame.
+pub(super) fn scrollbar(gui: &Gui, index: usize) -> Option<(AABB, AABB)> {
+    let frame = match gui.components[index] {
+        GuiComponent::ScrollFrame(frame) => frame,
+        _ => { return None; }
+    };
+
+    let max = max_scroll(gui, index);
+    if max <= 0.0 {
+        return None;
+    }
+
+    let scale = gui.scale();
+    let view = gui.components_views[index];
+    let track = AABB {
+        left: view.position.x + view.size.width - (SCROLLBAR_WIDTH * scale),
+        top: view.position.y,
+        right: view.position.x + view.size.width,
+        bottom: view.position.y + view.size.height,
+    };
+
+    let track_height = track.height();
+    let thumb_height = f32::min(f32::max(track_height * (track_height / (track_height + max)), MIN_THUMB_HEIGHT * scale), track_height);
+    let thumb_top = track.top + ((track_height - thumb_height) * (frame.scroll / max));
+    let thumb = AABB { top: thumb_top, bottom: thumb_top + thumb_height, ..track };
+
+    Some((track, thumb))
+}
+
+/// Sets the scrolling of the scroll frame at `index`. The value is clamped to the frame content.
+pub(super) fn scroll_to(gui: &mut Gui, index: usize, scroll: f32) {
+    let scroll = scroll.clamp(0.0, max_scroll(gui, index));
+    if let GuiComponent::ScrollFrame(frame) = &mut gui.components[index] {
+        if frame.scroll != scroll {
+            frame.scroll = scroll;
+
+            let root_index = gui.components_nodes[index].root_index as usize;
+            gui.components_nodes[root_index].dirty = true;
+            gui.update_flags.add(GuiUpdateFlags::COMPUTE_LAYOUT_POSITIONS | GuiUpdateFlags::GENERATE_SPRITES);
+        }
+    }
+}
+
+/// Moves the thumb of the scroll frame at `index` so that its top is at `thumb_top`
+pub(super) fn drag_thumb(gui: &mut Gui, index: usize, thumb_top: f32) {
+    if let Some((track, thumb)) = scrollbar(gui, index) {
+        let range = track.height() - thumb.height();
+        if range > 0.0 {
+            let scroll = ((thumb_top - track.top) / range) * max_scroll(gui, index);
+            scroll_to(gui, index, scroll);
+        }
+    }
+}
+
+/// Returns false if the component at `index` is scrolled out of one of its parent scroll frames at `position`
+pub(super) fn visible_at(gui: &Gui, index: usize, position: Position<f32>) -> bool {
+    for parent in 0..index {
+        if let GuiComponent::ScrollFrame(_) = gui.components[parent] {
+            let end = parent + (gui.components_nodes[parent].descendants_count as usize);
+            let view = gui.components_views[parent];
+            if index <= end && !AABB::from_position_and_size(view.position, view.size).point_inside(position) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Returns the index of the innermost scroll frame under `position` or `u32::MAX` if there is none
+pub(super) fn scroll_frame_at(gui: &Gui, position: Position<f32>) -> u32 {
+    for (index, component) in gui.components.iter().enumerate().rev() {
+        if let GuiComponent::ScrollFrame(_) = component {
+            let view = gui.components_views[index];
+            if AABB::from_position_and_size(view.position, view.size).point_inside(position) && visible_at(gui, index, position) {
+                return index as u32;
+            }
+        }
+    }
+
+    u32::MAX
+}
+
+/// Clips the sprites starting at `first` to `clip`. Sprites fully outside of `clip` are removed.
+pub(super) fn clip_sprites(sprites: &mut Vec<GuiOutputSprite>, first: usize, clip: AABB) {
+    let mut index = first;
+    while index < sprites.len() {
+        let sprite = &mut sprites[index];
+        let p = sprite.positions;
+        if p.right <= clip.left || p.left >= clip.right || p.bottom <= clip.top || p.top >= clip.bottom {
+            sprites.remove(index);
+            continue;
+        }
+
+        // Texture coordinates are cropped by the same ratio as the positions
+        let t = sprite.texcoord;
+        let tx = t.width() / f32::max(p.width(), f32::EPSILON);
+        let ty = t.height() / f32::max(p.height(), f32::EPSILON);
+
+        if p.left < clip.left {
+            sprite.texcoord.left += (clip.left - p.left) * tx;
+            sprite.positions.left = clip.left;
+        }
+
+        if p.right > clip.right {
+            sprite.texcoord.right -= (p.right - clip.right) * tx;
+            sprite.positions.right = clip.right;
+        }
+
+        if p.top < clip.top {
+            sprite.texcoord.top += (clip.top - p.top) * ty;
+            sprite.positions.top = clip.top;
+        }
+
+        if p.bottom > clip.bottom {
+            sprite.texcoord.bottom -= (p.bottom - clip.bottom) * ty;
+            sprite.positions.bottom = clip.bottom;
+        }
+
+        index += 1;
+    }
+}
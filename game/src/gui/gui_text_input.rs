@@ -0,0 +1,243 @@
+use unicode_segmentation::UnicodeSegmentation;
+use crate::assets::TextMetrics;
+use crate::inputs::{InputState, Key};
+use super::{Gui, GuiComponent, GuiEvent, GuiTextInput, GuiTextInputId};
+
+/// Space between the border of a text input and its text
+pub(super) const TEXT_INPUT_PADDING: f32 = 8.0;
+
+/// Edition state of a text input
+#[derive(Default)]
+pub struct GuiTextInputState {
+    pub value: String,
+    /// Position of the caret, in graphemes
+    pub caret: u32,
+    /// Other end of the selection, in graphemes. Equal to `caret` if nothing is selected.
+    pub anchor: u32,
+    /// Horizontal scrolling of the text so that the caret stays inside the field
+    pub scroll: f32,
+    pub metrics: TextMetrics,
+    /// Position of the caret before each grapheme of `value`, and after the last one
+    pub caret_offsets: Vec<f32>,
+}
+
+impl GuiTextInputState {
+
+    pub fn selection(&self) -> (u32, u32) {
+        (u32::min(self.caret, self.anchor), u32::max(self.caret, self.anchor))
+    }
+
+    /// Number of graphemes in `value`
+    fn length(&self) -> u32 {
+        self.value.graphemes(true).count() as u32
+    }
+
+    /// Byte offset of the grapheme at `index` in `value`
+    fn byte_offset(&self, index: u32) -> usize {
+        self.value.grapheme_indices(true)
+            .nth(index as usize)
+            .map(|(offset, _)| offset )
+            .unwrap_or(self.value.len())
+    }
+
+    /// Removes the selected text. Returns `false` if nothing was selected.
+    fn delete_selection(&mut self) -> bool {
+        let (start, end) = self.selection();
+        if start == end {
+            return false;
+        }
+
+        let range = self.byte_offset(start)..self.byte_offset(end);
+        self.value.replace_range(range, "");
+        self.caret = start;
+        self.anchor = start;
+        true
+    }
+
+    /// Moves the caret to `index`. The selection is extended instead of cleared if `select` is true.
+    fn move_caret(&mut self, index: u32, select: bool) {
+        self.caret = index;
+        if !select {
+            self.anchor = index;
+        }
+    }
+
+}
+
+/// Focus the text input at `index` and places the caret at the grapheme the closest to `x`
+pub(super) fn focus_at(gui: &mut Gui, index: u32, x: f32) {
+    let input = match gui.components.get(index as usize) {
+        Some(GuiComponent::TextInput(input)) => *input,
+        _ => { return; }
+    };
+
+    // The text metrics of the inputs are not scaled
+    let scale = gui.scale();
+    let view = gui.components_views[index as usize];
+    let state = &mut gui.text_inputs[input.state as usize];
+    let local_x = ((x - view.position.x) / scale) - TEXT_INPUT_PADDING + state.scroll;
+    let caret = state.caret_offsets.iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| (*a - local_x).abs().total_cmp(&(*b - local_x).abs()) )
+        .map(|(index, _)| index as u32 )
+        .unwrap_or(0);
+
+    state.move_caret(caret, false);
+    gui.tag_sprites(index);
+}
+
+/// Edits the focused text input using the keys and the characters typed this frame
+pub(super) fn process_keyboard(gui: &mut Gui, inputs: &InputState) {
+    let index = gui.input_state.focused_input;
+    let input = match gui.components.get(index as usize) {
+        Some(GuiComponent::TextInput(input)) => *input,
+        _ => { return; }
+    };
+
+    let select = inputs.key_state(Key::ShiftLeft).pressed();
+    let state = &mut gui.text_inputs[input.state as usize];
+    let old_caret = (state.caret, state.anchor);
+    let mut changed = false;
+
+    for &chr in inputs.typed_chars() {
+        changed |= state.delete_selection();
+        if state.length() >= input.max_length {
+            break;
+        }
+
+        let offset = state.byte_offset(state.caret);
+        state.value.insert(offset, chr);
+
+        // A character can merge with the previous grapheme (ex: a combining accent)
+        let caret = u32::min(state.caret + 1, state.length());
+        state.move_caret(caret, false);
+        changed = true;
+    }
+
+    if inputs.key_state(Key::Backspace).just_pressed() {
+        if state.delete_selection() {
+            changed = true;
+        } else if state.caret > 0 {
+            let range = state.byte_offset(state.caret - 1)..state.byte_offset(state.caret);
+            state.value.replace_range(range, "");
+            state.move_caret(state.caret - 1, false);
+            changed = true;
+        }
+    }
+
+    if inputs.key_state(Key::Delete).just_pressed() {
+        if state.delete_selection() {
+            changed = true;
+        } else if state.caret < state.length() {
+            let range = state.byte_offset(state.caret)..state.byte_offset(state.caret + 1);
+            state.value.replace_range(range, "");
+            changed = true;
+        }
+    }
+
+    if inputs.key_state(Key::ArrowLeft).just_pressed() {
+        let (start, end) = state.selection();
+        match select || start == end {
+            true => state.move_caret(state.caret.saturating_sub(1), select),
+            false => state.move_caret(start, false),
+        }
+    }
+
+    if inputs.key_state(Key::ArrowRight).just_pressed() {
+        let (start, end) = state.selection();
+        match select || start == end {
+            true => state.move_caret(u32::min(state.caret + 1, state.length()), select),
+            false => state.move_caret(end, false),
+        }
+    }
+
+    if inputs.key_state(Key::Home).just_pressed() {
+        state.move_caret(0, select);
+    }
+
+    if inputs.key_state(Key::End).just_pressed() {
+        let length = state.length();
+        state.move_caret(length, select);
+    }
+
+    let caret_moved = old_caret != (state.caret, state.anchor);
+
+    if changed {
+        update_text(gui, index);
+    } else if caret_moved {
+        update_scroll(gui, index);
+    }
+
+    if inputs.key_state(Key::Enter).just_pressed() {
+        gui.events.push(GuiEvent::TextInputSubmitted(GuiTextInputId(index)));
+    }
+
+    if inputs.key_state(Key::Escape).just_pressed() {
+        gui.input_state.focused_input = u32::MAX;
+        gui.tag_sprites(index);
+    }
+}
+
+/// Computes the layout of the text input value. The caret is clamped to the new value length.
+pub(super) fn update_text(gui: &mut Gui, index: u32) {
+    let input = match gui.components.get(index as usize) {
+        Some(GuiComponent::TextInput(input)) => *input,
+        _ => { return; }
+    };
+
+    let state = &mut gui.text_inputs[input.state as usize];
+    if let Some(assets) = gui.assets.as_ref() {
+        state.metrics = assets.fonts.compute_text_metrics(&state.value, input.font_size);
+        state.caret_offsets = assets.fonts.compute_caret_offsets(&state.value, input.font_size);
+    }
+
+    let length = state.length();
+    state.caret = u32::min(state.caret, length);
+    state.anchor = u32::min(state.anchor, length);
+
+    update_scroll(gui, index);
+}
+
+/// Scrolls the text so that the caret is inside the text input
+fn update_scroll(gui: &mut Gui, index: u32) {
+    let input: GuiTextInput = match gui.components.get(index as usize) {
+        Some(GuiComponent::TextInput(input)) => *input,
+        _ => { return; }
+    };
+
+    let scale = gui.scale();
+    let view = gui.components_views[index as usize];
+    let state = &mut gui.text_inputs[input.state as usize];
+    let inner_width = f32::max((view.size.width / scale) - (TEXT_INPUT_PADDING * 2.0), 0.0);
+    let caret_x = state.caret_offsets.get(state.caret as usize).copied().unwrap_or(0.0);
+
+    if caret_x - state.scroll > inner_width {
+        state.scroll = caret_x - inner_width;
+    } else if caret_x < state.scroll {
+        state.scroll = caret_x;
+    }
+
+    gui.tag_sprites(index);
+}
+
+impl crate::store::SaveAndLoad for GuiTextInputState {
+    fn save(&self, writer: &mut crate::store::SaveFileWriter) {
+        writer.write_str(&self.value);
+        writer.write_u32(self.caret);
+        writer.write_u32(self.anchor);
+        writer.write_f32(self.scroll);
+        writer.save(&self.metrics);
+        writer.write_slice(&self.caret_offsets);
+    }
+
+    fn load(reader: &mut crate::store::SaveFileReader) -> Self {
+        GuiTextInputState {
+            value: reader.read_str().to_string(),
+            caret: reader.read_u32(),
+            anchor: reader.read_u32(),
+            scroll: reader.read_f32(),
+            metrics: reader.load(),
+            caret_offsets: reader.read_vec(),
+        }
+    }
+}
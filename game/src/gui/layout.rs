@@ -1,51 +1,97 @@
+/// Where a component is positioned in its parent. `Auto` places the component in the parent items flow.
+/// The other origins anchor the component to a side or a corner of the parent, outside of the items flow.
 #[derive(Copy, Clone)]
 pub enum GuiLayoutOrigin {
     Auto,
-    BottomLeft
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    /// Root components only. The component is placed at the floating position of the gui, clamped to the view,
+    /// and is rendered above the other roots. Floating roots are hidden until `Gui::show_floating` is called.
+    Floating,
 }
 
 #[derive(Copy, Clone)]
 pub enum GuiSizing {
     Auto,
-    Static { width: f32, height: f32 }
+    Static { width: f32, height: f32 },
+    /// Size in percent (0-100) of the parent content size
+    Percent { width: f32, height: f32 },
+    /// Fills the space left by the other items of the parent on the parent main axis, and the whole parent on the cross axis.
+    /// The remaining space is split evenly between the `Fill` items.
+    Fill,
 }
 
 #[derive(Copy, Clone, Default, Debug)]
 pub struct GuiPadding {
     pub left: f32,
     pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+}
+
+impl GuiPadding {
+    pub const fn all(value: f32) -> Self {
+        GuiPadding { left: value, top: value, right: value, bottom: value }
+    }
+
+    pub const fn scaled(&self, scale: f32) -> Self {
+        GuiPadding { left: self.left * scale, top: self.top * scale, right: self.right * scale, bottom: self.bottom * scale }
+    }
 }
 
+/// Space around a component. Uses the same layout as the padding.
+pub type GuiMargin = GuiPadding;
+
 #[derive(Copy, Clone)]
 pub struct GuiAlignSelf {
     pub origin: GuiLayoutOrigin,
     pub sizing: GuiSizing,
     pub padding: GuiPadding,
+    pub margin: GuiMargin,
 }
 
 #[derive(Copy, Clone)]
 pub enum ItemsDirection {
     Column,
-    Row
+    Row,
+    /// Items are placed in rows. A new row is started when an item would overflow the container width.
+    RowWrap,
 }
 
+/// Alignment of the items on the cross axis of the parent
 #[derive(Copy, Clone)]
 pub enum ItemsAlign {
     Start,
     Center,
+    End,
+    /// There's only one item per line on the cross axis, so this is the same as `Start`
+    SpaceBetween,
 }
 
+/// Position of the items on the main axis of the parent
 #[derive(Copy, Clone)]
 pub enum ItemsPosition {
     Start,
     Center,
+    End,
+    /// The free space of the parent is split evenly between the items
+    SpaceBetween,
 }
 
 #[derive(Copy, Clone)]
 pub struct GuiAlignItems {
     pub direction: ItemsDirection,
     pub alignment: ItemsAlign,
-    pub position: ItemsPosition
+    pub position: ItemsPosition,
+    /// Space between two items
+    pub spacing: f32,
 }
 
 
@@ -55,19 +101,40 @@ pub struct GuiLayout {
     pub align_items: GuiAlignItems,
 }
 
+impl GuiLayout {
+    /// Maximum width of a row of items before wrapping. Containers without a static size never wrap.
+    pub fn wrap_width(&self) -> f32 {
+        let padding = self.align_self.padding;
+        match self.align_self.sizing {
+            GuiSizing::Static { width, .. } => width - padding.left - padding.right,
+            _ => f32::INFINITY,
+        }
+    }
+
+    /// Returns the layout with its pixel sizes multiplied by `scale`. Percent sizes are left as is.
+    pub fn scaled(&self, scale: f32) -> Self {
+        let mut layout = *self;
+        if let GuiSizing::Static { width, height } = layout.align_self.sizing {
+            layout.align_self.sizing = GuiSizing::Static { width: width * scale, height: height * scale };
+        }
+
+        layout.align_self.padding = layout.align_self.padding.scaled(scale);
+        layout.align_self.margin = layout.align_self.margin.scaled(scale);
+        layout.align_items.spacing *= scale;
+        layout
+    }
+}
+
 impl Default for GuiLayout {
     fn default() -> Self {
         GuiLayout {
-            align_self: GuiAlignSelf { 
+            align_self: GuiAlignSelf {
                 origin: GuiLayoutOrigin::Auto,
                 sizing: GuiSizing::Auto,
                 padding: GuiPadding::default(),
+                margin: GuiMargin::default(),
             },
-            align_items: GuiAlignItems {
-                direction: ItemsDirection::Column,
-                alignment: ItemsAlign::Center,
-                position: ItemsPosition::Center,
-            }
+            align_items: GuiAlignItems::default(),
         }
     }
 }
@@ -78,6 +145,7 @@ impl Default for GuiAlignItems {
             direction: ItemsDirection::Column,
             alignment: ItemsAlign::Center,
             position: ItemsPosition::Center,
+            spacing: 0.0,
         }
     }
 }
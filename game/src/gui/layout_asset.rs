@@ -0,0 +1,422 @@
+//! Builds the gui components described in a `GuiLayoutAsset`
+use fnv::FnvHashMap;
+use crate::assets::{GuiBundle, GuiLayoutAsset, GuiLayoutNode, GuiLayoutNodeType, TextAlign};
+use crate::error::Error;
+use super::*;
+
+/// A dynamic resource or a component created by a layout
+#[derive(Copy, Clone)]
+pub enum GuiBinding {
+    Image(GuiImageId),
+    Text(GuiStaticTextId),
+    Progress(GuiProgressId),
+    Button(GuiButtonId),
+    ProgressBar(GuiProgressBarId),
+    TextInput(GuiTextInputId),
+}
+
+pub trait FromGuiBinding: Sized {
+    fn from_binding(binding: GuiBinding) -> Option<Self>;
+}
+
+macro_rules! impl_from_binding {
+    ($ty:ty, $variant:ident) => {
+        impl FromGuiBinding for $ty {
+            fn from_binding(binding: GuiBinding) -> Option<Self> {
+                match binding {
+                    GuiBinding::$variant(value) => Some(value),
+                    _ => None
+                }
+            }
+        }
+    };
+}
+
+impl_from_binding!(GuiImageId, Image);
+impl_from_binding!(GuiStaticTextId, Text);
+impl_from_binding!(GuiProgressId, Progress);
+impl_from_binding!(GuiButtonId, Button);
+impl_from_binding!(GuiProgressBarId, ProgressBar);
+impl_from_binding!(GuiTextInputId, TextInput);
+
+/// The values created by a layout, by name. Nodes built more than once in a `repeat` block
+/// have one value per repetition, in order.
+#[derive(Default)]
+pub struct GuiLayoutBindings {
+    values: FnvHashMap<String, Vec<GuiBinding>>,
+}
+
+impl GuiLayoutBindings {
+
+    /// Returns the value bound to `name`
+    pub fn get<T: FromGuiBinding>(&self, name: &str) -> Result<T, Error> {
+        self.values.get(name)
+            .and_then(|values| values.first() )
+            .and_then(|&value| T::from_binding(value) )
+            .ok_or_else(|| gui_err!("Missing gui binding {:?} or binding has the wrong type", name) )
+    }
+
+    /// Copies all the values bound to `name` in `out`. The number of values must match the length of `out`.
+    pub fn get_all<T: FromGuiBinding>(&self, name: &str, out: &mut [T]) -> Result<(), Error> {
+        let values = self.values.get(name).map(|values| values.as_slice() ).unwrap_or(&[]);
+        if values.len() != out.len() {
+            return Err(gui_err!("Gui binding {:?} has {} values, expected {}", name, values.len(), out.len()));
+        }
+
+        for (&value, out) in values.iter().zip(out.iter_mut()) {
+            *out = T::from_binding(value)
+                .ok_or_else(|| gui_err!("Gui binding {:?} has the wrong type", name) )?;
+        }
+
+        Ok(())
+    }
+
+    fn push(&mut self, name: &str, binding: GuiBinding) {
+        match self.values.get_mut(name) {
+            Some(values) => values.push(binding),
+            None => { self.values.insert(name.to_string(), vec![binding]); }
+        }
+    }
+
+}
+
+struct LayoutContext<'a> {
+    bundle: &'a GuiBundle,
+    bindings: &'a mut GuiLayoutBindings,
+    /// Images created for the sprites of the bundle, so a sprite used by many nodes is only added once
+    images: FnvHashMap<&'a str, GuiImageId>,
+}
+
+impl<'a> GuiBuilder<'a> {
+
+    /// Builds the components of `layout`. Sprite names are the names of the gui sprites in `bundle`.
+    /// The dynamic resources and the components with a name are added to `bindings`.
+    ///
+    /// Layout attributes, accepted by every node except `repeat`:
+    ///  - `origin=auto|top_left|top|top_right|left|center|right|bottom_left|bottom|bottom_right|floating`
+    ///  - `size=auto|fill|{width}x{height}|{width}%x{height}%`
+    ///  - `padding=` and `margin=`: either one value for all sides or `{left},{top},{right},{bottom}`
+    ///  - `items={direction},{position},{align}` with the values of `ItemsDirection`, `ItemsPosition` and `ItemsAlign` in snake case
+    ///  - `spacing={value}`
+    ///
+    /// Nodes:
+    ///  - `frame sprite=` A frame using a sprite as background. Sprites with nine-slice borders are drawn as nine-slice frames.
+    ///  - `group` An invisible container
+    ///  - `scroll track= thumb=` A scroll frame
+    ///  - `repeat count=` Builds its children `count` times
+    ///  - `spacer size={width}x{height}`
+    ///  - `label name= color= align=left|center|right wrap_width=` A label with a dynamic text. `align` and `wrap_width` are used by `Gui::set_label_text`
+    ///  - `image sprite=|name= width=` A static sprite or a dynamic image if `name` is used. `width` scales the image.
+    ///  - `button sprite= hover= pressed= name= icon= label= label_color=` `icon` and `label` bind a dynamic icon and text
+    ///  - `progress background= fill= color= name= progress=` `progress` binds the progress value of the bar
+    ///  - `input background= color= name= max_length= font_size=`
+    ///
+    /// Colors are written as `{r},{g},{b}`.
+    pub fn layout<'l>(&mut self, layout: &'l GuiLayoutAsset, bundle: &'l GuiBundle, bindings: &'l mut GuiLayoutBindings) {
+        let mut context = LayoutContext {
+            bundle,
+            bindings,
+            images: FnvHashMap::default(),
+        };
+
+        for node in layout.roots.iter() {
+            build_node(self, &mut context, node);
+        }
+    }
+
+}
+
+fn build_node<'a>(gui: &mut GuiBuilder, context: &mut LayoutContext<'a>, node: &'a GuiLayoutNode) {
+    if let Err(error) = try_build_node(gui, context, node) {
+        gui.set_error(error);
+    }
+}
+
+fn build_children<'a>(gui: &mut GuiBuilder, context: &mut LayoutContext<'a>, node: &'a GuiLayoutNode) {
+    for child in node.children.iter() {
+        build_node(gui, context, child);
+    }
+}
+
+fn try_build_node<'a>(gui: &mut GuiBuilder, context: &mut LayoutContext<'a>, node: &'a GuiLayoutNode) -> Result<(), Error> {
+    if node.ty == GuiLayoutNodeType::Repeat {
+        let count: u32 = parse_attribute(node, "count")?;
+        for _ in 0..count {
+            build_children(gui, context, node);
+        }
+
+        return Ok(());
+    }
+
+    apply_layout(gui, node)?;
+
+    match node.ty {
+        GuiLayoutNodeType::Frame => {
+            let sprite = required_attribute(node, "sprite")?;
+            let background = sprite_image(gui, context, node, sprite)?;
+            match context.bundle.nine_slice(sprite) {
+                Some(insets) => gui.nine_slice_frame(background, insets, |gui| build_children(gui, context, node) ),
+                None => gui.simple_frame(background, |gui| build_children(gui, context, node) ),
+            }
+        },
+        GuiLayoutNodeType::Group => {
+            gui.group(|gui| build_children(gui, context, node) );
+        },
+        GuiLayoutNodeType::ScrollFrame => {
+            let track = sprite_image(gui, context, node, required_attribute(node, "track")?)?;
+            let thumb = sprite_image(gui, context, node, required_attribute(node, "thumb")?)?;
+            gui.scroll_frame(GuiScrollFrame::from_images(track, thumb), |gui| build_children(gui, context, node) );
+        },
+        GuiLayoutNodeType::Spacer => {
+            let (width, height) = parse_size(node, required_attribute(node, "size")?)?;
+            gui.spacer(width, height);
+        },
+        GuiLayoutNodeType::Label => {
+            let text = gui.dyn_static_text();
+            bind(context, node, "name", GuiBinding::Text(text));
+
+            let mut label = GuiLabel::from_static_text_and_color(text, color_attribute(node, "color")?);
+            if let Some(align) = node.attribute("align") {
+                label = label.with_align(match align {
+                    "left" => TextAlign::Left,
+                    "center" => TextAlign::Center,
+                    "right" => TextAlign::Right,
+                    _ => { return Err(attribute_error(node, "align")); }
+                });
+            }
+
+            if node.attribute("wrap_width").is_some() {
+                label = label.with_wrap_width(parse_attribute(node, "wrap_width")?);
+            }
+
+            gui.label(label);
+        },
+        GuiLayoutNodeType::Image => {
+            let image = match node.attribute("sprite") {
+                Some(sprite) => sprite_image(gui, context, node, sprite)?,
+                None => {
+                    let image = gui.dyn_image();
+                    bind(context, node, "name", GuiBinding::Image(image));
+                    image
+                }
+            };
+
+            let display = match node.attribute("width") {
+                Some(_) => GuiImageDisplay::from_image_and_scaled_width(image, parse_attribute(node, "width")?),
+                None => GuiImageDisplay::from_image(image)
+            };
+
+            gui.image_display(display);
+        },
+        GuiLayoutNodeType::Button => {
+            let normal = sprite_image(gui, context, node, required_attribute(node, "sprite")?)?;
+            let hover = sprite_image(gui, context, node, required_attribute(node, "hover")?)?;
+            let pressed = sprite_image(gui, context, node, required_attribute(node, "pressed")?)?;
+            let mut button = GuiButton::from_images(normal, hover, pressed);
+
+            if node.attribute("icon").is_some() {
+                let icon = gui.dyn_image();
+                bind(context, node, "icon", GuiBinding::Image(icon));
+                button = button.with_icon(icon);
+            }
+
+            if node.attribute("label").is_some() {
+                let text = gui.dyn_static_text();
+                bind(context, node, "label", GuiBinding::Text(text));
+                button = button.with_label(GuiLabel::from_static_text_and_color(text, color_attribute(node, "label_color")?));
+            }
+
+            let button = gui.button(button);
+            bind(context, node, "name", GuiBinding::Button(button));
+        },
+        GuiLayoutNodeType::ProgressBar => {
+            let background = sprite_image(gui, context, node, required_attribute(node, "background")?)?;
+            let fill = sprite_image(gui, context, node, required_attribute(node, "fill")?)?;
+            let progress = gui.dyn_progress(color_attribute(node, "color")?);
+            bind(context, node, "progress", GuiBinding::Progress(progress));
+
+            let bar = gui.progress_bar(GuiProgressBar::from_images(background, fill, progress));
+            bind(context, node, "name", GuiBinding::ProgressBar(bar));
+        },
+        GuiLayoutNodeType::TextInput => {
+            let background = sprite_image(gui, context, node, required_attribute(node, "background")?)?;
+            let mut input = GuiTextInput::from_image_and_color(background, color_attribute(node, "color")?);
+            if node.attribute("font_size").is_some() {
+                input = input.with_font_size(parse_attribute(node, "font_size")?);
+            }
+
+            if node.attribute("max_length").is_some() {
+                input.max_length = parse_attribute(node, "max_length")?;
+            }
+
+            let input = gui.text_input(input);
+            bind(context, node, "name", GuiBinding::TextInput(input));
+        },
+        GuiLayoutNodeType::Repeat => {}
+    }
+
+    Ok(())
+}
+
+fn apply_layout(gui: &mut GuiBuilder, node: &GuiLayoutNode) -> Result<(), Error> {
+    if let Some(origin) = node.attribute("origin") {
+        let origin = match origin {
+            "auto" => GuiLayoutOrigin::Auto,
+            "top_left" => GuiLayoutOrigin::TopLeft,
+            "top" => GuiLayoutOrigin::Top,
+            "top_right" => GuiLayoutOrigin::TopRight,
+            "left" => GuiLayoutOrigin::Left,
+            "center" => GuiLayoutOrigin::Center,
+            "right" => GuiLayoutOrigin::Right,
+            "bottom_left" => GuiLayoutOrigin::BottomLeft,
+            "bottom" => GuiLayoutOrigin::Bottom,
+            "bottom_right" => GuiLayoutOrigin::BottomRight,
+            "floating" => GuiLayoutOrigin::Floating,
+            _ => { return Err(attribute_error(node, "origin")); }
+        };
+
+        gui.origin(origin);
+    }
+
+    // The size of a spacer is not a layout sizing
+    if let Some(sizing) = node.attribute("size").filter(|_| node.ty != GuiLayoutNodeType::Spacer ) {
+        let sizing = match sizing {
+            "auto" => GuiSizing::Auto,
+            "fill" => GuiSizing::Fill,
+            value if value.contains('%') => {
+                let (width, height) = parse_size(node, &value.replace('%', ""))?;
+                GuiSizing::Percent { width, height }
+            },
+            value => {
+                let (width, height) = parse_size(node, value)?;
+                GuiSizing::Static { width, height }
+            }
+        };
+
+        gui.sizing(sizing);
+    }
+
+    if node.attribute("padding").is_some() {
+        gui.padding(padding_attribute(node, "padding")?);
+    }
+
+    if node.attribute("margin").is_some() {
+        gui.margin(padding_attribute(node, "margin")?);
+    }
+
+    if let Some(items) = node.attribute("items") {
+        let mut values = items.split(',');
+        let direction = match values.next() {
+            Some("column") => ItemsDirection::Column,
+            Some("row") => ItemsDirection::Row,
+            Some("row_wrap") => ItemsDirection::RowWrap,
+            _ => { return Err(attribute_error(node, "items")); }
+        };
+
+        let position = match values.next() {
+            Some("start") => ItemsPosition::Start,
+            Some("center") => ItemsPosition::Center,
+            Some("end") => ItemsPosition::End,
+            Some("space_between") => ItemsPosition::SpaceBetween,
+            _ => { return Err(attribute_error(node, "items")); }
+        };
+
+        let alignment = match values.next() {
+            Some("start") => ItemsAlign::Start,
+            Some("center") => ItemsAlign::Center,
+            Some("end") => ItemsAlign::End,
+            Some("space_between") => ItemsAlign::SpaceBetween,
+            _ => { return Err(attribute_error(node, "items")); }
+        };
+
+        gui.items_align(direction, position, alignment);
+    }
+
+    if node.attribute("spacing").is_some() {
+        gui.items_spacing(parse_attribute(node, "spacing")?);
+    }
+
+    Ok(())
+}
+
+//
+// Helpers
+//
+
+fn bind(context: &mut LayoutContext, node: &GuiLayoutNode, attribute: &str, binding: GuiBinding) {
+    if let Some(name) = node.attribute(attribute) {
+        context.bindings.push(name, binding);
+    }
+}
+
+fn sprite_image<'a>(gui: &mut GuiBuilder, context: &mut LayoutContext<'a>, node: &GuiLayoutNode, sprite: &'a str) -> Result<GuiImageId, Error> {
+    if let Some(&image) = context.images.get(sprite) {
+        return Ok(image);
+    }
+
+    let texcoord = context.bundle.sprite(sprite)
+        .ok_or_else(|| gui_err!("Line {}: unknown gui sprite {:?}", node.line, sprite) )?;
+
+    let image = gui.image(texcoord);
+    context.images.insert(sprite, image);
+
+    Ok(image)
+}
+
+fn required_attribute<'a>(node: &'a GuiLayoutNode, name: &str) -> Result<&'a str, Error> {
+    node.attribute(name)
+        .ok_or_else(|| gui_err!("Line {}: missing attribute {:?}", node.line, name) )
+}
+
+fn parse_attribute<T: ::std::str::FromStr>(node: &GuiLayoutNode, name: &str) -> Result<T, Error> {
+    required_attribute(node, name)?
+        .parse::<T>()
+        .map_err(|_| attribute_error(node, name) )
+}
+
+fn parse_size(node: &GuiLayoutNode, value: &str) -> Result<(f32, f32), Error> {
+    let (width, height) = value.split_once('x')
+        .ok_or_else(|| gui_err!("Line {}: expected a size formatted as {{width}}x{{height}}, got {:?}", node.line, value) )?;
+
+    match (width.parse::<f32>(), height.parse::<f32>()) {
+        (Ok(width), Ok(height)) => Ok((width, height)),
+        _ => Err(gui_err!("Line {}: invalid size {:?}", node.line, value))
+    }
+}
+
+fn parse_values<const N: usize>(value: &str) -> Option<[f32; N]> {
+    let mut out = [0.0; N];
+    let mut count = 0;
+    for value in value.split(',') {
+        *out.get_mut(count)? = value.parse::<f32>().ok()?;
+        count += 1;
+    }
+
+    match count == N {
+        true => Some(out),
+        false => None,
+    }
+}
+
+fn padding_attribute(node: &GuiLayoutNode, name: &str) -> Result<GuiPadding, Error> {
+    let value = required_attribute(node, name)?;
+    if let Some([all]) = parse_values::<1>(value) {
+        return Ok(GuiPadding::all(all));
+    }
+
+    let [left, top, right, bottom] = parse_values::<4>(value)
+        .ok_or_else(|| attribute_error(node, name) )?;
+
+    Ok(GuiPadding { left, top, right, bottom })
+}
+
+fn color_attribute(node: &GuiLayoutNode, name: &str) -> Result<GuiColor, Error> {
+    let [r, g, b] = parse_values::<3>(required_attribute(node, name)?)
+        .ok_or_else(|| attribute_error(node, name) )?;
+
+    Ok(GuiColor::rgb(r as u8, g as u8, b as u8))
+}
+
+fn attribute_error(node: &GuiLayoutNode, name: &str) -> Error {
+    gui_err!("Line {}: invalid value {:?} for attribute {:?}", node.line, node.attribute(name).unwrap_or(""), name)
+}
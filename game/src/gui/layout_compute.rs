@@ -8,13 +8,36 @@ use super::{
 
 struct LayoutSizingParent {
     pub align_items: GuiAlignItems,
+    /// Size of the items in the parent flow, including the spacing and the items margins
     pub size: Size<f32>,
+    /// Content size of the parent. Used by the `Percent` and `Fill` sizing.
+    pub available: Size<f32>,
+    /// Main axis size of the `Fill` items. Zero while the other items are being measured.
+    pub fill_main: f32,
+    pub fill_count: u32,
+    pub items_count: u32,
+    /// Size of the last row of items in a `RowWrap` parent
+    pub line_size: Size<f32>,
 }
 
+#[derive(Copy, Clone)]
 struct LayoutPositionParent {
     pub view: GuiComponentView,
-    pub align_items: GuiAlignItems,
+    pub layout: GuiLayout,
     pub child_offsets: [f32; 2],
+    /// Extra space between items added by `ItemsPosition::SpaceBetween`
+    pub extra_spacing: f32,
+    pub items_count: u32,
+    pub line_start: f32,
+    pub line_height: f32,
+}
+
+/// Alignment of an anchored component on one axis
+#[derive(Copy, Clone)]
+enum Anchor {
+    Start,
+    Center,
+    End,
 }
 
 pub(super) fn layout_compute(gui: &mut Gui) {
@@ -35,11 +58,24 @@ pub(super) fn layout_compute(gui: &mut Gui) {
 // Sizing pass
 //
 
+impl LayoutSizingParent {
+    fn new(align_items: GuiAlignItems, available: Size<f32>) -> Self {
+        LayoutSizingParent {
+            align_items,
+            size: size(0.0, 0.0),
+            available,
+            fill_main: 0.0,
+            fill_count: 0,
+            items_count: 0,
+            line_size: size(0.0, 0.0),
+        }
+    }
+}
+
 fn sizing_pass(gui: &mut Gui) {
-    let mut parent = LayoutSizingParent {
-        align_items: GuiAlignItems::default(),
-        size: gui.view_size
-    };
+    // Root components filling the screen take the whole view
+    let mut parent = LayoutSizingParent::new(GuiAlignItems::default(), gui.view_size);
+    parent.fill_main = gui.view_size.height;
 
     let mut index = 0;
     while index < gui.components.len() {
@@ -47,14 +83,22 @@ fn sizing_pass(gui: &mut Gui) {
         if node.dirty {
             layout_size(gui, &mut index, &mut parent);
         } else {
+            // Clean roots keep their size, but they still take their place in the roots flow
+            if let GuiSizing::Fill = gui.components_layout[index].align_self.sizing {
+                parent.fill_count += 1;
+            }
+
+            update_parent_size(&mut parent, &get_layout(gui, index), get_view(gui, index).size);
             index += (node.descendants_count + 1) as usize;
         }
     }
 }
 
+/// Size of a component in view pixels
 fn get_component_size(gui: &Gui, index: usize) -> Size<f32> {
-    match get_component(gui, index) {
-        GuiComponent::Container(_) | GuiComponent::Group => size(0.0, 0.0),
+    let scale = gui.scale();
+    let base_size = match get_component(gui, index) {
+        GuiComponent::Container(_) | GuiComponent::ScrollFrame(_) | GuiComponent::Group => size(0.0, 0.0),
         GuiComponent::Spacer(size) => size,
         GuiComponent::ImageDisplay(image_display) => {
             let mut texture_size = gui.images[image_display.image.index()].texcoord.size();
@@ -71,19 +115,62 @@ fn get_component_size(gui: &Gui, index: usize) -> Size<f32> {
         },
         GuiComponent::Label(label) => {
             gui.text[label.text.index()].size
+        },
+        GuiComponent::Button(button) => {
+            gui.images[button.normal.index()].texcoord.size()
+        },
+        GuiComponent::ProgressBar(bar) => {
+            gui.images[bar.background.index()].texcoord.size()
+        },
+        GuiComponent::TextInput(input) => {
+            gui.images[input.background.index()].texcoord.size()
         }
-    }
+    };
+
+    size(base_size.width * scale, base_size.height * scale)
 }
 
-fn update_parent_size(parent: &mut LayoutSizingParent, base_size: Size<f32>) {
+fn update_parent_size(parent: &mut LayoutSizingParent, layout: &GuiLayout, base_size: Size<f32>) {
+    // Anchored components are not part of the parent items flow
+    if !matches!(layout.align_self.origin, GuiLayoutOrigin::Auto) {
+        return;
+    }
+
+    let margin = layout.align_self.margin;
+    let outer_size = size(base_size.width + margin.left + margin.right, base_size.height + margin.top + margin.bottom);
+    let spacing = match parent.items_count {
+        0 => 0.0,
+        _ => parent.align_items.spacing
+    };
+
+    parent.items_count += 1;
+
     match parent.align_items.direction {
         ItemsDirection::Column => {
-            parent.size.width = f32::max(parent.size.width, base_size.width);
-            parent.size.height += base_size.height;
+            parent.size.width = f32::max(parent.size.width, outer_size.width);
+            parent.size.height += spacing + outer_size.height;
         },
         ItemsDirection::Row => {
-            parent.size.width += base_size.width;
-            parent.size.height = f32::max(parent.size.height, base_size.height);
+            parent.size.width += spacing + outer_size.width;
+            parent.size.height = f32::max(parent.size.height, outer_size.height);
+        },
+        ItemsDirection::RowWrap => {
+            let spacing = parent.align_items.spacing;
+            let mut lines_height = parent.size.height - parent.line_size.height;
+            if parent.line_size.width > 0.0 && parent.line_size.width + spacing + outer_size.width > parent.available.width {
+                lines_height += parent.line_size.height + spacing;
+                parent.line_size = outer_size;
+            } else {
+                if parent.line_size.width > 0.0 {
+                    parent.line_size.width += spacing;
+                }
+
+                parent.line_size.width += outer_size.width;
+                parent.line_size.height = f32::max(parent.line_size.height, outer_size.height);
+            }
+
+            parent.size.width = f32::max(parent.size.width, parent.line_size.width);
+            parent.size.height = lines_height + parent.line_size.height;
         }
     }
 }
@@ -95,33 +182,68 @@ fn layout_size(gui: &mut Gui, index: &mut usize, parent: &mut LayoutSizingParent
     let node = get_node1(gui, i);
     let layout = get_layout(gui, i);
     let mut view = get_view(gui, i);
+    let margin = layout.align_self.margin;
+    let padding = layout.align_self.padding;
+
     let base_size = match layout.align_self.sizing {
+        GuiSizing::Auto => get_component_size(gui, i),
         GuiSizing::Static { width, height } => size(width, height),
-        GuiSizing::Auto => get_component_size(gui, i)
+        GuiSizing::Percent { width, height } => size(parent.available.width * width / 100.0, parent.available.height * height / 100.0),
+        GuiSizing::Fill => {
+            parent.fill_count += 1;
+            let mut fill_size = size(parent.available.width - margin.left - margin.right, parent.available.height - margin.top - margin.bottom);
+            set_axis(&mut fill_size, main_axis(parent.align_items.direction), parent.fill_main);
+            size(f32::max(fill_size.width, 0.0), f32::max(fill_size.height, 0.0))
+        }
     };
 
     if node.children_count == 0 {
         view.size = base_size;
-        update_parent_size(parent, base_size);
+        update_parent_size(parent, &layout, base_size);
         set_view(gui, i, view);
         return;
     }
 
-    let mut child_sizing = LayoutSizingParent {
-        align_items: layout.align_items,
-        size: size(0.0, 0.0)
+    // Auto sized components don't know their size yet, so they give the space of their parent to their children
+    let outer_available = match layout.align_self.sizing {
+        GuiSizing::Auto => size(parent.available.width - margin.left - margin.right, parent.available.height - margin.top - margin.bottom),
+        _ => base_size
     };
+    let available = size(
+        f32::max(outer_available.width - padding.left - padding.right, 0.0),
+        f32::max(outer_available.height - padding.top - padding.bottom, 0.0),
+    );
+
+    let first_child = *index;
+    let mut child_sizing = LayoutSizingParent::new(layout.align_items, available);
     for _ in 0..node.children_count {
         layout_size(gui, index, &mut child_sizing)
     }
+
+    // Fill items are measured once the size of the other items is known
+    if child_sizing.fill_count > 0 {
+        let main = main_axis(layout.align_items.direction);
+        let remaining = f32::max(axis(available, main) - axis(child_sizing.size, main), 0.0);
+        let fill_count = child_sizing.fill_count;
+
+        *index = first_child;
+        child_sizing = LayoutSizingParent::new(layout.align_items, available);
+        child_sizing.fill_main = remaining / (fill_count as f32);
+        for _ in 0..node.children_count {
+            layout_size(gui, index, &mut child_sizing)
+        }
+    }
     
     view.items_size = child_sizing.size;
     view.size = match layout.align_self.sizing {
-        GuiSizing::Auto => child_sizing.size,
-        _ => view.size,
+        GuiSizing::Auto => size(
+            child_sizing.size.width + padding.left + padding.right,
+            child_sizing.size.height + padding.top + padding.bottom
+        ),
+        _ => base_size,
     };
 
-    update_parent_size(parent, view.size);
+    update_parent_size(parent, &layout, view.size);
     set_view(gui, i, view);
 }
 
@@ -133,8 +255,12 @@ fn layout_size(gui: &mut Gui, index: &mut usize, parent: &mut LayoutSizingParent
 fn position_pass(gui: &mut Gui) {
     let mut parent = LayoutPositionParent {
         view: GuiComponentView { position: pos(0.0, 0.0), size: gui.view_size, items_size: size(0.0, 0.0) },
-        align_items: GuiAlignItems::default(),
+        layout: GuiLayout::default(),
         child_offsets: [0.0, 0.0],
+        extra_spacing: 0.0,
+        items_count: 0,
+        line_start: 0.0,
+        line_height: 0.0,
     };
 
     let mut index = 0;
@@ -142,12 +268,114 @@ fn position_pass(gui: &mut Gui) {
         let node = gui.components_nodes[index];
         if node.dirty {
             layout_position(gui, &mut index, &mut parent);
+            continue;
+        }
+
+        // Clean roots in the roots flow are placed again, because the dirty roots before them may have changed size.
+        // Their children are only positioned again if the root moved.
+        let layout = get_layout(gui, index);
+        if !matches!(layout.align_self.origin, GuiLayoutOrigin::Auto) {
+            index += (node.descendants_count + 1) as usize;
+            continue;
+        }
+
+        let previous_parent = parent;
+        let mut view = get_view(gui, index);
+        let old_position = view.position;
+        flow_position(&mut parent, &layout, &mut view);
+
+        if view.position != old_position {
+            parent = previous_parent;
+            layout_position(gui, &mut index, &mut parent);
         } else {
             index += (node.descendants_count + 1) as usize;
         }
     }
 }
 
+fn origin_anchors(origin: GuiLayoutOrigin) -> (Anchor, Anchor) {
+    match origin {
+        GuiLayoutOrigin::Auto | GuiLayoutOrigin::TopLeft | GuiLayoutOrigin::Floating => (Anchor::Start, Anchor::Start),
+        GuiLayoutOrigin::Top => (Anchor::Center, Anchor::Start),
+        GuiLayoutOrigin::TopRight => (Anchor::End, Anchor::Start),
+        GuiLayoutOrigin::Left => (Anchor::Start, Anchor::Center),
+        GuiLayoutOrigin::Center => (Anchor::Center, Anchor::Center),
+        GuiLayoutOrigin::Right => (Anchor::End, Anchor::Center),
+        GuiLayoutOrigin::BottomLeft => (Anchor::Start, Anchor::End),
+        GuiLayoutOrigin::Bottom => (Anchor::Center, Anchor::End),
+        GuiLayoutOrigin::BottomRight => (Anchor::End, Anchor::End),
+    }
+}
+
+fn anchor_offset(anchor: Anchor, parent_size: f32, component_size: f32, margin_start: f32, margin_end: f32) -> f32 {
+    match anchor {
+        Anchor::Start => margin_start,
+        Anchor::Center => ((parent_size - component_size) / 2.0) + margin_start - margin_end,
+        Anchor::End => parent_size - component_size - margin_end,
+    }
+}
+
+fn cross_axis_offset(alignment: ItemsAlign, content_size: f32, item_size: f32) -> f32 {
+    match alignment {
+        ItemsAlign::Start | ItemsAlign::SpaceBetween => 0.0,
+        ItemsAlign::Center => (content_size - item_size) / 2.0,
+        ItemsAlign::End => content_size - item_size,
+    }
+}
+
+/// Position a component with the `Auto` origin in the items flow of its parent
+fn flow_position(parent: &mut LayoutPositionParent, layout: &GuiLayout, view: &mut GuiComponentView) {
+    let align_items = parent.layout.align_items;
+    let padding = parent.layout.align_self.padding;
+    let margin = layout.align_self.margin;
+    let parent_position = parent.view.position;
+    let content_size = size(
+        parent.view.size.width - padding.left - padding.right,
+        parent.view.size.height - padding.top - padding.bottom
+    );
+    let outer_size = size(view.size.width + margin.left + margin.right, view.size.height + margin.top + margin.bottom);
+
+    let spacing = match parent.items_count {
+        0 => 0.0,
+        _ => align_items.spacing + parent.extra_spacing
+    };
+
+    parent.items_count += 1;
+
+    match align_items.direction {
+        ItemsDirection::Column => {
+            parent.child_offsets[1] += spacing;
+            view.position.x = parent_position.x + padding.left + cross_axis_offset(align_items.alignment, content_size.width, outer_size.width) + margin.left;
+            view.position.y = parent_position.y + parent.child_offsets[1] + margin.top;
+            parent.child_offsets[1] += outer_size.height;
+        },
+        ItemsDirection::Row => {
+            parent.child_offsets[0] += spacing;
+            view.position.x = parent_position.x + parent.child_offsets[0] + margin.left;
+            view.position.y = parent_position.y + padding.top + cross_axis_offset(align_items.alignment, content_size.height, outer_size.height) + margin.top;
+            parent.child_offsets[0] += outer_size.width;
+        },
+        ItemsDirection::RowWrap => {
+            let spacing = align_items.spacing;
+            let line_width = parent.child_offsets[0] - parent.line_start;
+            if line_width > 0.0 {
+                if line_width + spacing + outer_size.width > content_size.width {
+                    parent.child_offsets[0] = parent.line_start;
+                    parent.child_offsets[1] += parent.line_height + spacing;
+                    parent.line_height = 0.0;
+                } else {
+                    parent.child_offsets[0] += spacing;
+                }
+            }
+
+            view.position.x = parent_position.x + parent.child_offsets[0] + margin.left;
+            view.position.y = parent_position.y + parent.child_offsets[1] + margin.top;
+            parent.child_offsets[0] += outer_size.width;
+            parent.line_height = f32::max(parent.line_height, outer_size.height);
+        },
+    }
+}
+
 fn layout_position(gui: &mut Gui, index: &mut usize, parent: &mut LayoutPositionParent) {
     let i = *index;
     *index += 1;
@@ -156,35 +384,17 @@ fn layout_position(gui: &mut Gui, index: &mut usize, parent: &mut LayoutPosition
     let mut view = get_view(gui, i);
 
     match layout.align_self.origin {
-        GuiLayoutOrigin::Auto => {
-            // Auto use the parent layout to position the children
-            let align_items = parent.align_items;
-            match (align_items.direction, align_items.alignment) {
-                (ItemsDirection::Column, ItemsAlign::Start) => {
-                    view.position.x = parent.view.position.x + parent.child_offsets[0];
-                    view.position.y = parent.view.position.y + parent.child_offsets[1];
-                    parent.child_offsets[1] += view.size.height;
-                },
-                (ItemsDirection::Column, ItemsAlign::Center) => {
-                    view.position.x = parent.view.position.x + ((parent.view.size.width - view.size.width) / 2.0);
-                    view.position.y = parent.view.position.y + parent.child_offsets[1];
-                    parent.child_offsets[1] += view.size.height;
-                },
-                (ItemsDirection::Row, ItemsAlign::Start) => {
-                    view.position.x = parent.view.position.x + parent.child_offsets[0];
-                    view.position.y = parent.view.position.y + parent.child_offsets[1];
-                    parent.child_offsets[0] += view.size.width;
-                },
-                (ItemsDirection::Row, ItemsAlign::Center) => {
-                    view.position.x = parent.view.position.x + parent.child_offsets[0];
-                    view.position.y = parent.view.position.y + ((parent.view.size.height - view.size.height) / 2.0);
-                    parent.child_offsets[0] += view.size.width;
-                },
-            }
+        GuiLayoutOrigin::Auto => flow_position(parent, &layout, &mut view),
+        GuiLayoutOrigin::Floating => {
+            let position = gui.floating.position;
+            view.position.x = f32::max(f32::min(position.x, gui.view_size.width - view.size.width), 0.0);
+            view.position.y = f32::max(f32::min(position.y, gui.view_size.height - view.size.height), 0.0);
         },
-        GuiLayoutOrigin::BottomLeft => {
-            view.position.x = parent.view.position.x;
-            view.position.y = parent.view.position.y + (parent.view.size.height - view.size.height);
+        origin => {
+            let (horizontal, vertical) = origin_anchors(origin);
+            let margin = layout.align_self.margin;
+            view.position.x = parent.view.position.x + anchor_offset(horizontal, parent.view.size.width, view.size.width, margin.left, margin.right);
+            view.position.y = parent.view.position.y + anchor_offset(vertical, parent.view.size.height, view.size.height, margin.top, margin.bottom);
         }
     }
 
@@ -195,17 +405,45 @@ fn layout_position(gui: &mut Gui, index: &mut usize, parent: &mut LayoutPosition
         return;
     }
 
-    let mut parent = LayoutPositionParent { view, align_items: layout.align_items, child_offsets: [0.0; 2] };
-    match (layout.align_items.direction, layout.align_items.position) {
-        (_, ItemsPosition::Start) => {
-            parent.child_offsets = [layout.align_self.padding.left, layout.align_self.padding.top];
-        },
-        (ItemsDirection::Column, ItemsPosition::Center) => {
-            parent.child_offsets[1] = (view.size.height - view.items_size.height) / 2.0;
-        },
-        (ItemsDirection::Row, ItemsPosition::Center) => {
-            parent.child_offsets[0] = (view.size.width - view.items_size.width) / 2.0;
-        },
+    let padding = layout.align_self.padding;
+    let main = main_axis(layout.align_items.direction);
+    let (padding_start, padding_end) = match main {
+        0 => (padding.left, padding.right),
+        _ => (padding.top, padding.bottom),
+    };
+
+    let free_space = axis(view.size, main) - padding_start - padding_end - axis(view.items_size, main);
+    let mut extra_spacing = 0.0;
+    let start = match layout.align_items.position {
+        ItemsPosition::Start => 0.0,
+        ItemsPosition::Center => free_space / 2.0,
+        ItemsPosition::End => free_space,
+        ItemsPosition::SpaceBetween => {
+            if node.children_count > 1 {
+                extra_spacing = f32::max(free_space, 0.0) / ((node.children_count - 1) as f32);
+            }
+            0.0
+        }
+    };
+
+    let mut parent = LayoutPositionParent {
+        view,
+        layout,
+        child_offsets: [padding.left, padding.top],
+        extra_spacing,
+        items_count: 0,
+        line_start: 0.0,
+        line_height: 0.0,
+    };
+
+    parent.child_offsets[main] += start;
+    parent.line_start = parent.child_offsets[0];
+
+    // Children of scroll frames are moved up by the scrolling. The scrolling is clamped in case the content got smaller.
+    let max_scroll = super::gui_scroll::max_scroll(gui, i);
+    if let GuiComponent::ScrollFrame(frame) = &mut gui.components[i] {
+        frame.scroll = frame.scroll.clamp(0.0, max_scroll);
+        parent.child_offsets[1] -= frame.scroll;
     }
 
     for _ in 0..node.children_count {
@@ -225,22 +463,24 @@ fn get_node1(gui: &Gui, index: usize) -> GuiNode {
     }
 }
 
-// Used while positioning. Also clears the node dirty flags
+// Used while positioning. Also clears the node dirty flags and tags the node sprites
 #[inline(always)]
 fn get_node2(gui: &mut Gui, index: usize) -> GuiNode {
     match gui.components_nodes.get_mut(index) {
         Some(node) => {
             node.dirty = false;
+            node.sprites_dirty = true;
             *node
         },
         None => unsafe { unreachable_unchecked() }
     }
 }
 
+/// Layout of the component with the gui scale applied
 #[inline(always)]
 fn get_layout(gui: &Gui, index: usize) -> GuiLayout {
     match gui.components_layout.get(index) {
-        Some(layout) => *layout,
+        Some(layout) => layout.scaled(gui.scale()),
         None => unsafe { unreachable_unchecked() }
     }
 }
@@ -269,3 +509,27 @@ fn get_component(gui: &Gui, index: usize) -> GuiComponent {
     }
 }
 
+/// Index of the main axis of `direction`. `0` is the x axis, `1` is the y axis
+#[inline(always)]
+fn main_axis(direction: ItemsDirection) -> usize {
+    match direction {
+        ItemsDirection::Column => 1,
+        ItemsDirection::Row | ItemsDirection::RowWrap => 0,
+    }
+}
+
+#[inline(always)]
+fn axis(size: Size<f32>, axis: usize) -> f32 {
+    match axis {
+        0 => size.width,
+        _ => size.height,
+    }
+}
+
+#[inline(always)]
+fn set_axis(size: &mut Size<f32>, axis: usize, value: f32) {
+    match axis {
+        0 => { size.width = value; },
+        _ => { size.height = value; },
+    }
+}
@@ -1,19 +1,83 @@
 use crate::shared::{Position, Size, pos, size};
 
-#[derive(Copy, Clone, Debug)]
+pub const KEY_COUNT: usize = 19;
+
+/// Maximum number of characters typed in a single frame. Extra characters are dropped.
+pub const MAX_TYPED_CHARS: usize = 16;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum Key {
-    CtrlLeft,
+    CtrlLeft = 0,
+    ShiftLeft,
     Space,
+    Escape,
+    A,
+    B,
+    C,
+    H,
+    M,
+    P,
+    S,
+    W,
+    Backspace,
+    Delete,
+    ArrowLeft,
+    ArrowRight,
+    Home,
+    End,
+    Enter,
 }
 
 impl Key {
     pub fn from_name(name: &str) -> Option<Key> {
         match name {
             "ControlLeft" => Some(Key::CtrlLeft),
+            "ShiftLeft" => Some(Key::ShiftLeft),
             "Space" => Some(Key::Space),
+            "Escape" => Some(Key::Escape),
+            "KeyA" => Some(Key::A),
+            "KeyB" => Some(Key::B),
+            "KeyC" => Some(Key::C),
+            "KeyH" => Some(Key::H),
+            "KeyM" => Some(Key::M),
+            "KeyP" => Some(Key::P),
+            "KeyS" => Some(Key::S),
+            "KeyW" => Some(Key::W),
+            "Backspace" => Some(Key::Backspace),
+            "Delete" => Some(Key::Delete),
+            "ArrowLeft" => Some(Key::ArrowLeft),
+            "ArrowRight" => Some(Key::ArrowRight),
+            "Home" => Some(Key::Home),
+            "End" => Some(Key::End),
+            "Enter" => Some(Key::Enter),
             _ => None
         }
     }
+
+    /// Name of the key as displayed in the gui
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Key::CtrlLeft => "Ctrl",
+            Key::ShiftLeft => "Shift",
+            Key::Space => "Space",
+            Key::Escape => "Esc",
+            Key::A => "A",
+            Key::B => "B",
+            Key::C => "C",
+            Key::H => "H",
+            Key::M => "M",
+            Key::P => "P",
+            Key::S => "S",
+            Key::W => "W",
+            Key::Backspace => "Backspace",
+            Key::Delete => "Del",
+            Key::ArrowLeft => "Left",
+            Key::ArrowRight => "Right",
+            Key::Home => "Home",
+            Key::End => "End",
+            Key::Enter => "Enter",
+        }
+    }
 }
 
 #[derive(Copy, Clone, PartialEq)]
@@ -53,11 +117,17 @@ pub enum MouseButton {
 pub struct InputState {
     pub last_view_size: Size<f32>,
     pub view_size: Size<f32>,
+    /// Number of view pixels in a css pixel (`window.devicePixelRatio`)
+    pub pixel_ratio: f32,
     pub last_mouse_position: Position<f32>,
     pub mouse_position: Position<f32>,
     pub mouse_buttons: [ButtonState; 3],
-    pub space: ButtonState,
-    pub left_ctrl: ButtonState,
+    /// Vertical scrolling of the mouse wheel this frame, in css pixels
+    pub mouse_wheel: f32,
+    pub keys: [ButtonState; KEY_COUNT],
+    /// Characters typed by the user this frame
+    pub typed_chars: [char; MAX_TYPED_CHARS],
+    pub typed_count: u32,
 }
 
 impl InputState {
@@ -66,6 +136,14 @@ impl InputState {
         self.mouse_buttons[button as usize]
     }
 
+    pub fn key_state(&self, key: Key) -> ButtonState {
+        self.keys[key as usize]
+    }
+
+    pub fn typed_chars(&self) -> &[char] {
+        &self.typed_chars[0..(self.typed_count as usize)]
+    }
+
     pub fn right_mouse_clicked(&self) -> bool {
         self.mouse_button_state(MouseButton::Right) == ButtonState::JustPressed
     }
@@ -101,10 +179,23 @@ impl InputState {
         self.mouse_buttons[index] = pressed;
     }
 
+    pub fn update_mouse_wheel(&mut self, delta: f32) {
+        self.mouse_wheel += delta;
+    }
+
     pub fn update_keys(&mut self, key: Key, pressed: ButtonState) {
-        match key {
-            Key::Space => { self.space = pressed; }
-            Key::CtrlLeft => { self.left_ctrl = pressed; }
+        self.keys[key as usize] = pressed;
+    }
+
+    pub fn update_typed_chars(&mut self, text: &str) {
+        for chr in text.chars().filter(|chr| !chr.is_control() ) {
+            let index = self.typed_count as usize;
+            if index >= MAX_TYPED_CHARS {
+                break;
+            }
+
+            self.typed_chars[index] = chr;
+            self.typed_count += 1;
         }
     }
 
@@ -120,11 +211,14 @@ impl Default for InputState {
         InputState {
             last_view_size: size(0.0, 0.0),
             view_size: size(0.0, 0.0),
+            pixel_ratio: 1.0,
             last_mouse_position: pos(0.0, 0.0),
             mouse_position: pos(0.0, 0.0),
             mouse_buttons: [ButtonState::Released; 3],
-            space: ButtonState::Released,
-            left_ctrl: ButtonState::Released,
+            mouse_wheel: 0.0,
+            keys: [ButtonState::Released; KEY_COUNT],
+            typed_chars: ['\0'; MAX_TYPED_CHARS],
+            typed_count: 0,
         }
     }
 }
@@ -26,8 +26,6 @@ use wasm_bindgen::prelude::*;
 
 static LAST_ERROR: Mutex<Option<error::Error>> = Mutex::new(None);
 
-const ANIMATION_INTERVAL: f64 = 1000.0 / 16.0; // 16fps
-
 /// Initial data to initialize the game state 
 #[wasm_bindgen]
 pub struct DemoGameInit {
@@ -36,6 +34,7 @@ pub struct DemoGameInit {
     pub(crate) assets_bundle: String,
     pub(crate) seed: u64,
     pub(crate) initial_window_size: Size<f32>,
+    pub(crate) initial_pixel_ratio: f32,
 }
 
 #[wasm_bindgen]
@@ -47,6 +46,7 @@ impl DemoGameInit {
             assets_bundle: String::new(),
             seed: 0,
             initial_window_size: Size::default(),
+            initial_pixel_ratio: 1.0,
         }
     }
 
@@ -54,8 +54,9 @@ impl DemoGameInit {
         self.assets_bundle = text;
     }
 
-    pub fn set_initial_window_size(&mut self, width: f32, height: f32) {
+    pub fn set_initial_window_size(&mut self, width: f32, height: f32, pixel_ratio: f32) {
         self.initial_window_size = Size { width, height };
+        self.initial_pixel_ratio = pixel_ratio;
     }
 
     pub fn upload_text_asset(&mut self, name: String, value: String) {
@@ -89,6 +90,8 @@ impl DemoGame {
         demo_app.data.global.seed = init.seed;
         demo_app.data.inputs.view_size = init.initial_window_size;
         demo_app.data.inputs.last_view_size = init.initial_window_size;
+        demo_app.data.inputs.pixel_ratio = init.initial_pixel_ratio;
+        demo_app.data.gui.set_pixel_ratio(init.initial_pixel_ratio);
 
         fastrand::seed(init.seed);
 
@@ -130,6 +133,28 @@ impl DemoGame {
         return true;
     }
 
+    /// Replaces a text asset modified on the development server. Returns false if the asset could not be reloaded.
+    pub fn reload_text_asset(&mut self, name: &str, value: &str) -> bool {
+        match assets::reload_text_asset(self, name, value) {
+            Ok(()) => true,
+            Err(e) => {
+                set_last_error(e);
+                false
+            }
+        }
+    }
+
+    /// Selects the language of the game text (ex: "en", "fr"). Returns false if there is no text for the language.
+    pub fn set_language(&mut self, language: &str) -> bool {
+        match assets::set_language(self, language) {
+            Ok(()) => true,
+            Err(e) => {
+                set_last_error(e);
+                false
+            }
+        }
+    }
+
     pub fn update(&mut self, time: f64) -> bool {
         self.update_timing(time);
         state::update(self);
@@ -142,9 +167,15 @@ impl DemoGame {
         self.output.output_index
     }
 
-    pub fn update_view_size(&mut self, width: f32, height: f32) {
+    pub fn update_view_size(&mut self, width: f32, height: f32, pixel_ratio: f32) {
         self.data.inputs.view_size.width = width;
         self.data.inputs.view_size.height = height;
+        self.data.inputs.pixel_ratio = pixel_ratio;
+    }
+
+    /// Sets the scale of the gui chosen by the user. The gui is also scaled by the device pixel ratio.
+    pub fn set_ui_scale(&mut self, scale: f32) {
+        self.data.gui.set_user_scale(scale);
     }
 
     pub fn update_mouse_position(&mut self, x: f32, y: f32) {
@@ -165,6 +196,10 @@ impl DemoGame {
         self.data.inputs.update_mouse_buttons(button, state);
     }
 
+    pub fn update_mouse_wheel(&mut self, delta_y: f32) {
+        self.data.inputs.update_mouse_wheel(delta_y);
+    }
+
     pub fn update_keys(&mut self, key_name: &str, pressed: bool) {
         let key = match inputs::Key::from_name(key_name) {
             Some(key) => key,
@@ -179,6 +214,11 @@ impl DemoGame {
         self.data.inputs.update_keys(key, state);
     }
 
+    /// Characters typed by the user since the last update. Used by the gui text inputs.
+    pub fn update_text_input(&mut self, text: &str) {
+        self.data.inputs.update_typed_chars(text);
+    }
+
 }
 
 impl DemoGame { 
@@ -193,14 +233,6 @@ impl DemoGame {
         if global.frame_delta > 1000.0 {
             global.frame_delta = 0.0;
         }
-
-        // This only sets a flag
-        // Sprite animation are computed at sprite generation in `output.gen_sprites_with_animation` 
-        let delta = new_time - global.last_animation_tick;
-        if delta > ANIMATION_INTERVAL {
-            global.flags.set_update_animations();
-            global.last_animation_tick = new_time;
-        }
     }
 
 }
@@ -1,8 +1,8 @@
 /// Structures and system to transfer data from the rust app to an external reader (in this case javascript)
 /// Data with `repr(C)` will be directly read from memory by the engine
 
-use crate::shared::{aabb, Position};
-use crate::world::{BaseAnimated, BaseProjectile};
+use crate::shared::{aabb, pos, size, AABB, Position, Size};
+use crate::world::{BaseAnimated, BaseProjectile, CommandMarker, World, WorldObject};
 use crate::DemoGame;
 
 /// Tells the engine which "module" to use to process a draw update
@@ -19,6 +19,8 @@ pub enum DrawUpdateType {
     UpdateGui = 5,
     DrawProjectileSprites = 6,
     DrawDebugInfo = 7,
+    DrawWorldOverlay = 8,
+    DrawWorldText = 9,
 }
 
 #[repr(C)]
@@ -47,8 +49,11 @@ pub struct DrawTerrainChunkParams {
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub struct UpdateGuiParams {
-    pub indices_count: u32,
+    /// Number of vertex in the gui mesh. The mesh has 6 indices for every 4 vertex.
     pub vertex_count: u32,
+    /// First vertex of the range of vertex updated since the last update
+    pub update_offset: u32,
+    pub update_count: u32,
 }
 
 /// DrawDebugParams doesn't have any parameters
@@ -56,6 +61,19 @@ pub struct UpdateGuiParams {
 #[derive(Copy, Clone)]
 pub struct DrawDebugParams;
 
+/// The vertex are read from `OutputIndex::overlay_vertex_ptr`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct DrawWorldOverlayParams {
+    /// The first `ground_vertex_count` vertex are rendered under the sprites (ex: selection markers), the others over the sprites.
+    pub ground_vertex_count: u32,
+}
+
+/// DrawWorldTextParams doesn't have any parameters. The vertex are read from `OutputIndex::world_text_vertex_ptr`
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct DrawWorldTextParams;
+
 #[repr(C)]
 #[derive(Copy, Clone)]
 pub union DrawUpdateParams {
@@ -65,6 +83,8 @@ pub union DrawUpdateParams {
     pub update_view_offset: Position<f32>,
     pub update_gui: UpdateGuiParams,
     pub draw_debug: DrawDebugParams,
+    pub draw_world_overlay: DrawWorldOverlayParams,
+    pub draw_world_text: DrawWorldTextParams,
 }
 
 /// A generic draw update that will be read by the renderere
@@ -112,7 +132,8 @@ pub struct TerrainChunkTexcoord {
     pub v3: [f32; 2],
 }
 
-/// A vertex used in the gui pipeline
+/// A vertex used in the gui pipeline. Also used by the world text pipeline, where the alpha channel packs the opacity of the text (bits 1 to 7)
+/// and the font of the glyph (bit 0, set for the fallback font).
 #[repr(C)]
 #[derive(Copy, Clone, Default)]
 pub struct GuiVertex {
@@ -121,7 +142,7 @@ pub struct GuiVertex {
     pub color: [u8; 4],
 }
 
-/// A vertex in the debug pipeline
+/// A vertex in the debug pipeline. Also used by the world overlay, which is rendered with the debug shader.
 #[repr(C)]
 #[derive(Copy, Clone, Default)]
 pub struct DebugVertex {
@@ -155,6 +176,10 @@ pub struct OutputIndex {
     pub gui_vertex_count: usize,
     pub debug_vertex_ptr: *const DebugVertex,
     pub debug_vertex_count: usize,
+    pub overlay_vertex_ptr: *const DebugVertex,
+    pub overlay_vertex_count: usize,
+    pub world_text_vertex_ptr: *const GuiVertex,
+    pub world_text_vertex_count: usize,
     pub validation: usize
 }
 
@@ -181,6 +206,12 @@ pub struct GameOutput {
     /// Buffer holding the vertex of the debug info. Debug info do not use an index buffer
     pub debug_vertex: Vec<DebugVertex>,
 
+    /// Buffer holding the vertex of the world overlay (ex: health bars). Rendered over the sprites and under the gui. Do not use an index buffer
+    pub overlay_vertex: Vec<DebugVertex>,
+
+    /// Buffer holding the glyphs vertex of the floating texts. Rendered over the world overlay and under the gui. Do not use an index buffer
+    pub world_text_vertex: Vec<GuiVertex>,
+
     /// Buffers of the generated draw update for the current frame.
     pub commands: Vec<DrawUpdate>,
 
@@ -197,6 +228,8 @@ impl GameOutput {
         self.sprite_data_buffer.clear();
         self.terrain_data.clear();
         self.debug_vertex.clear();
+        self.overlay_vertex.clear();
+        self.world_text_vertex.clear();
     }
 
     pub fn write_index(&mut self) {
@@ -215,6 +248,10 @@ impl GameOutput {
         index.gui_vertex_count = self.gui_vertex.len();
         index.debug_vertex_ptr = self.debug_vertex.as_ptr();
         index.debug_vertex_count = self.debug_vertex.len();
+        index.overlay_vertex_ptr = self.overlay_vertex.as_ptr();
+        index.overlay_vertex_count = self.overlay_vertex.len();
+        index.world_text_vertex_ptr = self.world_text_vertex.as_ptr();
+        index.world_text_vertex_count = self.world_text_vertex.len();
     }
 
 }
@@ -226,6 +263,8 @@ pub fn update(game: &mut DemoGame) {
     render_terrain(game);
     render_sprites(game);
     render_projectiles(game);
+    render_world_overlay(game);
+    render_world_text(game);
     render_gui(game);
 
     #[cfg(feature="debug")]
@@ -332,26 +371,26 @@ fn render_terrain(game: &mut DemoGame) {
     * Generate batches of commands for the engine to render
 */
 fn render_sprites(game: &mut DemoGame) {
-    let flags = &mut game.data.global.flags;
-    let world = &mut game.data.world;
+    let world = &game.data.world;
+    let assets = &game.data.assets;
     let output = &mut game.output;
 
-    let total_sprites = world.total_sprites();
+    let total_sprites = world.total_sprites() + world.particles.particles.len();
     if total_sprites == 0 {
         return;
     }
 
     output.sprites_builder.reserve(total_sprites);
 
+    // Sprites outside of the view are skipped
+    let view = aabb(game.data.global.view_offset, game.data.inputs.view_size);
+
     // Generate sprites
-    if flags.get_update_animations() {
-        gen_sprites_with_animation(world, output);
-        flags.clear_update_animations();
-    } else {
-        gen_sprites(world, output);
-    }
+    gen_sprites(world, output, view);
 
-    gen_static_sprites(world, output);
+    gen_static_sprites(world, output, view);
+
+    gen_particle_sprites(assets, world, output, view);
 
     // Order
     order_sprites(output);
@@ -360,21 +399,26 @@ fn render_sprites(game: &mut DemoGame) {
     gen_commands(output);
 }
 
-fn gen_sprites(world: &crate::world::World, output: &mut GameOutput) {
-    let sprite_groups: [(u32, &[BaseAnimated]); 8] = [
-        (world.units_texture.id, &world.pawns),
-        (world.units_texture.id, &world.warriors),
-        (world.units_texture.id, &world.archers),
-        (world.units_texture.id, &world.torch_goblins),
-        (world.units_texture.id, &world.tnt_goblins),
-        (world.units_texture.id, &world.sheeps),
-        (world.static_resources_texture.id, &world.resources_spawn),
-        (world.static_resources_texture.id, &world.trees),
+/// Sprites are only generated for the units in the view. Animations are advanced in `behaviour::update_animations`.
+/// Trees and resource spawns are generated from the static grid in `gen_static_sprites`.
+fn gen_sprites(world: &crate::world::World, output: &mut GameOutput, view: AABB) {
+    let texture_id = world.units_texture.id;
+    let sprite_groups: [&[BaseAnimated]; 6] = [
+        &world.pawns,
+        &world.warriors,
+        &world.archers,
+        &world.torch_goblins,
+        &world.tnt_goblins,
+        &world.sheeps,
     ];
 
     let builder = &mut output.sprites_builder;
-    for (texture_id, sprites) in sprite_groups {
+    for sprites in sprite_groups {
         for unit in sprites.iter() {
+            if !view.intersects(&unit.aabb()) {
+                continue;
+            }
+
             let sprite = build_actor_sprite(unit);
             builder.push(TempSprite {
                 texture_id,
@@ -385,51 +429,51 @@ fn gen_sprites(world: &crate::world::World, output: &mut GameOutput) {
     }
 }
 
-fn gen_sprites_with_animation(world: &mut crate::world::World, output: &mut GameOutput) {
-    let sprite_groups: [(u32, &mut [BaseAnimated]); 8] = [
-        (world.units_texture.id, &mut world.pawns),
-        (world.units_texture.id, &mut world.warriors),
-        (world.units_texture.id, &mut world.archers),
-        (world.units_texture.id, &mut world.torch_goblins),
-        (world.units_texture.id, &mut world.tnt_goblins),
-        (world.units_texture.id, &mut world.sheeps),
-        (world.static_resources_texture.id, &mut world.resources_spawn),
-        (world.static_resources_texture.id, &mut world.trees),
-    ];
+fn gen_static_sprites(world: &crate::world::World, output: &mut GameOutput, view: AABB) {
+    use crate::world::WorldObjectType;
+    use crate::world::queries::SPRITE_QUERY_MARGIN;
 
+    let texture_id = world.static_resources_texture.id;
     let builder = &mut output.sprites_builder;
-    for (texture_id, sprites) in sprite_groups {
-        for unit in sprites.iter_mut() {
-            unit.current_frame += 1;
-            unit.current_frame = unit.current_frame * ((unit.current_frame <= unit.animation.last_frame) as u8);
 
-            let sprite = build_actor_sprite(unit);
-            builder.push(TempSprite {
-                texture_id,
-                y: unit.position.y,
-                sprite
-            });
-        }
-    }
-}
+    let mut search_area = view;
+    search_area.left -= SPRITE_QUERY_MARGIN;
+    search_area.right += SPRITE_QUERY_MARGIN;
+    search_area.bottom += SPRITE_QUERY_MARGIN;
+
+    world.static_grid.query(search_area, |obj| {
+        let index = obj.id as usize;
+        let (y, sprite_aabb, sprite) = match obj.ty {
+            WorldObjectType::Tree => {
+                let unit = &world.trees[index];
+                (unit.position.y, unit.aabb(), build_actor_sprite(unit))
+            },
+            WorldObjectType::ResourceSpawn => {
+                let unit = &world.resources_spawn[index];
+                (unit.position.y, unit.aabb(), build_actor_sprite(unit))
+            },
+            WorldObjectType::Decoration => {
+                let unit = &world.decorations[index];
+                (unit.position.y, unit.aabb(), build_static_sprite(unit))
+            },
+            WorldObjectType::Structure => {
+                let unit = &world.structures[index];
+                (unit.position.y, unit.aabb(), build_static_sprite(unit))
+            },
+            _ => { return; }
+        };
 
-fn gen_static_sprites(world: &crate::world::World, output: &mut GameOutput) {
-    let texture_id = world.static_resources_texture.id;
-    let sprites_groups = [&world.decorations, &world.structures];
-    let builder = &mut output.sprites_builder;
-    for group in sprites_groups {
-        for unit in group {
-            let sprite = build_static_sprite(unit);
-            builder.push(TempSprite {
-                texture_id,
-                y: unit.position.y,
-                sprite
-            });
+        if view.intersects(&sprite_aabb) {
+            builder.push(TempSprite { texture_id, y, sprite });
         }
-    }
+    });
 
     // Grabbed resource have a different y position if they are grabbed
     for (resource, resource_data) in world.resources.iter().zip(world.resources_data.iter()) {
+        if !view.intersects(&resource.aabb()) {
+            continue;
+        }
+
         let sprite = build_static_sprite(resource);
         let y = match resource_data.grabbed {
             true => resource.position.y + 60.0,
@@ -444,6 +488,45 @@ fn gen_static_sprites(world: &crate::world::World, output: &mut GameOutput) {
     }
 }
 
+/// Particles are ordered with the other sprites using their ground position. Their transparency is stored in the bits 8-15 of the sprite data.
+fn gen_particle_sprites(assets: &crate::assets::Assets, world: &crate::world::World, output: &mut GameOutput, view: AABB) {
+    use crate::world::ParticleType;
+
+    let texture_id = world.static_resources_texture.id;
+    let resources = &assets.resources;
+    let builder = &mut output.sprites_builder;
+
+    for particle in world.particles.particles.iter() {
+        let texcoord = match particle.ty {
+            ParticleType::Dust => resources.particle_dust,
+            ParticleType::WoodChip => resources.particle_wood_chip,
+            ParticleType::Spark => resources.particle_spark,
+            ParticleType::Smoke => resources.particle_smoke,
+        };
+
+        let half_size = particle.size * 0.5;
+        let position = pos(particle.position.x - half_size, particle.position.y - particle.height - half_size);
+        if !view.intersects(&aabb(position, size(particle.size, particle.size))) {
+            continue;
+        }
+
+        let transparency = ((1.0 - particle.alpha()) * 255.0) as i32;
+        let sprite = SpriteData {
+            position: [position.x, position.y],
+            size: [particle.size, particle.size],
+            texcoord_offset: [texcoord.left, texcoord.top],
+            texcoord_size: [texcoord.width(), texcoord.height()],
+            data: transparency << 8,
+        };
+
+        builder.push(TempSprite {
+            texture_id,
+            y: particle.position.y,
+            sprite
+        });
+    }
+}
+
 fn order_sprites(output: &mut GameOutput) {
     use std::cmp::Ordering;
 
@@ -530,7 +613,7 @@ fn build_static_sprite(base: &crate::world::BaseStatic) -> SpriteData {
     Also projectile sprites don't need to be y-ordered, they all share the same texture (so far), and are always rendered on top of the other sprites.
 */
 fn render_projectiles(game: &mut DemoGame) {
-    let world = &mut game.data.world;
+    let world = &game.data.world;
     let output = &mut game.output;
 
     let total_sprites = world.total_projectile_sprites();
@@ -575,7 +658,300 @@ fn render_projectiles(game: &mut DemoGame) {
 }
 
 /**
-    Generate the gui sprites. If the gui wasn't updated since the last frame, this doesn't do anything
+    Generate the world overlay. The overlay has two layers:
+     - the ground layer, rendered under the sprites: selection markers under the units and the pulse on the target of the last command
+     - the top layer, rendered over the sprites: selection brackets around the structures and the health bars
+    Health bars of damaged objects stay visible for `HEALTH_BAR_VISIBLE_TIME` after the last damage, then fade out.
+    Expired markers are removed in `World::expire_overlay_markers`.
+*/
+fn render_world_overlay(game: &mut DemoGame) {
+    use crate::world::{WorldObjectType, HEALTH_BAR_VISIBLE_TIME, HEALTH_BAR_FADE_TIME, COMMAND_MARKER_TIME};
+
+    let data = &game.data;
+    let time = data.global.time;
+    let view = aabb(data.global.view_offset, data.inputs.view_size);
+
+    let world = &data.world;
+    let hovered = match data.gui.captures_mouse() {
+        true => None,
+        false => hovered_damageable(world, data.inputs.mouse_position + data.global.view_offset)
+    };
+
+    let vertex = &mut game.output.overlay_vertex;
+
+    // The command marker shrinks and fades out
+    let command_pulse = world.command_marker.map(|marker| {
+        let t = ((time - marker.timestamp) / COMMAND_MARKER_TIME) as f32;
+        (marker, 1.25 - (t * 0.25), 1.0 - t)
+    });
+
+    // Ground layer
+    for &obj in world.selected.iter() {
+        if obj.ty != WorldObjectType::Structure {
+            gen_selection_marker(vertex, world, view, obj, 1.0, 1.0);
+        }
+    }
+
+    if let Some((marker, scale, alpha)) = command_pulse {
+        match marker.target {
+            Some(obj) if obj.ty != WorldObjectType::Structure => {
+                gen_selection_marker(vertex, world, view, obj, scale, alpha);
+            },
+            None => {
+                let radius = size(20.0 * scale, 9.0 * scale);
+                let bounds = aabb(marker.position - pos(radius.width, radius.height), size(radius.width * 2.0, radius.height * 2.0));
+                if view.intersects(&bounds) {
+                    overlay_ellipse(vertex, marker.position, radius, [240, 240, 240, (alpha * 255.0) as u8]);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    let ground_vertex_count = vertex.len() as u32;
+
+    // Top layer
+    for &obj in world.selected.iter() {
+        if obj.ty == WorldObjectType::Structure {
+            gen_selection_marker(vertex, world, view, obj, 1.0, 1.0);
+        }
+    }
+
+    if let Some((CommandMarker { target: Some(obj), .. }, scale, alpha)) = command_pulse {
+        if obj.ty == WorldObjectType::Structure {
+            gen_selection_marker(vertex, world, view, obj, scale, alpha);
+        }
+    }
+
+    for &obj in world.selected.iter() {
+        gen_health_bar(vertex, world, view, obj, 1.0);
+    }
+
+    if let Some(obj) = hovered.filter(|obj| !world.selected.contains(obj) ) {
+        gen_health_bar(vertex, world, view, obj, 1.0);
+    }
+
+    for &(obj, timestamp) in world.damaged.iter() {
+        if hovered == Some(obj) || world.selected.contains(&obj) {
+            continue;
+        }
+
+        let fade = (time - timestamp - HEALTH_BAR_VISIBLE_TIME) / HEALTH_BAR_FADE_TIME;
+        let alpha = 1.0 - f64::clamp(fade, 0.0, 1.0);
+        gen_health_bar(vertex, world, view, obj, alpha as f32);
+    }
+
+    if vertex.len() > 0 {
+        game.output.commands.push(DrawUpdate {
+            graphics: DrawUpdateType::DrawWorldOverlay,
+            params: DrawUpdateParams { draw_world_overlay: DrawWorldOverlayParams { ground_vertex_count } },
+        });
+    }
+}
+
+/// Returns the object with a health bar under `position`. If multiple objects overlap, the one drawn on top is returned.
+fn hovered_damageable(world: &World, position: Position<f32>) -> Option<WorldObject> {
+    use crate::world::WorldObjectType;
+    use crate::world::queries::QueryFilter;
+
+    // Only sheeps and structures have health. See `World::health`
+    const DAMAGEABLE: &[WorldObjectType] = &[WorldObjectType::Sheep, WorldObjectType::Structure];
+
+    let mut hovered = None;
+    let mut y = f32::NEG_INFINITY;
+    world.query_point(position, QueryFilter::types(DAMAGEABLE), |obj| {
+        match (world.health(obj), world.object_bounds(obj)) {
+            (Some(_), Some((_, sprite))) if sprite.bottom > y => {
+                hovered = Some(obj);
+                y = sprite.bottom;
+            },
+            _ => {}
+        }
+    });
+
+    hovered
+}
+
+/// Structures are marked by brackets around their sprite, other objects by an ellipse at their feet.
+/// The color depends on the owner of the object.
+fn gen_selection_marker(vertex: &mut Vec<DebugVertex>, world: &World, view: AABB, obj: WorldObject, scale: f32, alpha: f32) {
+    use crate::world::WorldObjectType;
+    use crate::world::queries::WorldObjectOwner;
+
+    let (position, sprite) = match world.object_bounds(obj) {
+        Some(bounds) => bounds,
+        None => { return; }
+    };
+
+    let [r, g, b] = match world.owner(obj) {
+        WorldObjectOwner::Player => [90, 220, 90],
+        WorldObjectOwner::Enemy => [230, 64, 52],
+        WorldObjectOwner::Neutral => [235, 208, 88],
+    };
+
+    let color = [r, g, b, (alpha * 255.0) as u8];
+
+    if obj.ty == WorldObjectType::Structure {
+        let grow_x = (sprite.width() * (scale - 1.0) * 0.5) + 4.0;
+        let grow_y = (sprite.height() * (scale - 1.0) * 0.5) + 4.0;
+        let area = AABB {
+            left: sprite.left - grow_x,
+            top: sprite.top - grow_y,
+            right: sprite.right + grow_x,
+            bottom: sprite.bottom + grow_y,
+        };
+
+        if view.intersects(&area) {
+            overlay_brackets(vertex, &area, color);
+        }
+    } else {
+        let radius_x = f32::clamp(sprite.width() * 0.22, 14.0, 48.0) * scale;
+        let radius = size(radius_x, radius_x * 0.45);
+        let bounds = aabb(position - pos(radius.width, radius.height), size(radius.width * 2.0, radius.height * 2.0));
+        if view.intersects(&bounds) {
+            overlay_ellipse(vertex, position, radius, color);
+        }
+    }
+}
+
+/// Health bars are drawn centered over the top of the object sprite
+fn gen_health_bar(vertex: &mut Vec<DebugVertex>, world: &World, view: AABB, obj: WorldObject, alpha: f32) {
+    const BAR_HEIGHT: f32 = 7.0;
+    const BAR_MARGIN: f32 = 4.0;
+    const BORDER: f32 = 1.0;
+
+    let (current, max) = match world.health(obj) {
+        Some(health) => health,
+        None => { return; }
+    };
+
+    let sprite = match world.object_bounds(obj) {
+        Some((_, sprite)) => sprite,
+        None => { return; }
+    };
+
+    let width = f32::clamp(sprite.width() * 0.5, 32.0, 96.0);
+    let position = pos(sprite.center().x - (width * 0.5), sprite.top - BAR_MARGIN - BAR_HEIGHT);
+    let background = aabb(position, size(width, BAR_HEIGHT));
+    if !view.intersects(&background) {
+        return;
+    }
+
+    let ratio = f32::min(current as f32 / max as f32, 1.0);
+    let [r, g, b] = match ratio {
+        r if r > 0.6 => [72, 199, 72],
+        r if r > 0.3 => [230, 196, 40],
+        _ => [214, 58, 48],
+    };
+
+    let a = (alpha * 255.0) as u8;
+    let fill_width = (width - (BORDER * 2.0)) * ratio;
+    let fill = aabb(pos(position.x + BORDER, position.y + BORDER), size(fill_width, BAR_HEIGHT - (BORDER * 2.0)));
+
+    overlay_rect(vertex, &background, [20, 20, 20, a]);
+    overlay_rect(vertex, &fill, [r, g, b, a]);
+}
+
+/**
+    Generate the glyphs of the floating texts. Texts are centered over their position.
+    The text layout is computed when the text is spawned. See `FloatingTexts::add`
+*/
+fn render_world_text(game: &mut DemoGame) {
+    let data = &game.data;
+    let view = aabb(data.global.view_offset, data.inputs.view_size);
+    let vertex = &mut game.output.world_text_vertex;
+
+    for text in data.world.floating_texts.texts.iter() {
+        let metrics = &text.metrics;
+        let origin = pos(text.position.x - (metrics.size.width * 0.5), text.position.y - metrics.size.height);
+        if !view.intersects(&aabb(origin, metrics.size)) {
+            continue;
+        }
+
+        let [r, g, b] = text.color;
+        let opacity = ((text.alpha() * 127.0) as u8) << 1;
+        for glyph in metrics.glyphs.iter() {
+            // Bit 0 of the alpha channel selects the fallback font
+            let color = [r, g, b, opacity | (glyph.font.min(1) as u8)];
+            let [left, top, right, bottom] = glyph.position.splat();
+            let [left, top, right, bottom] = [left + origin.x, top + origin.y, right + origin.x, bottom + origin.y];
+            let [tleft, ttop, tright, tbottom] = glyph.texcoord.splat();
+            vertex.extend_from_slice(&[
+                GuiVertex { position: [left, top], texcoord: [tleft, ttop], color },
+                GuiVertex { position: [right, bottom], texcoord: [tright, tbottom], color },
+                GuiVertex { position: [left, bottom], texcoord: [tleft, tbottom], color },
+                GuiVertex { position: [right, top], texcoord: [tright, ttop], color },
+                GuiVertex { position: [left, top], texcoord: [tleft, ttop], color },
+                GuiVertex { position: [right, bottom], texcoord: [tright, tbottom], color },
+            ]);
+        }
+    }
+
+    if vertex.len() > 0 {
+        game.output.commands.push(DrawUpdate {
+            graphics: DrawUpdateType::DrawWorldText,
+            params: DrawUpdateParams { draw_world_text: DrawWorldTextParams },
+        });
+    }
+}
+
+fn overlay_rect(vertex: &mut Vec<DebugVertex>, aabb: &AABB, color: [u8; 4]) {
+    vertex.extend_from_slice(&[
+        DebugVertex { position: [aabb.left, aabb.top],     color },
+        DebugVertex { position: [aabb.left, aabb.bottom],  color },
+        DebugVertex { position: [aabb.right, aabb.bottom], color },
+        DebugVertex { position: [aabb.right, aabb.top],    color },
+        DebugVertex { position: [aabb.left, aabb.top],     color },
+        DebugVertex { position: [aabb.right, aabb.bottom], color },
+    ]);
+}
+
+/// An ellipse outline centered on `center`
+fn overlay_ellipse(vertex: &mut Vec<DebugVertex>, center: Position<f32>, radius: Size<f32>, color: [u8; 4]) {
+    const SEGMENTS: usize = 24;
+    const THICKNESS: f32 = 2.0;
+
+    let step = std::f32::consts::TAU / (SEGMENTS as f32);
+    let point = |angle: f32, offset: f32| {
+        [center.x + (f32::cos(angle) * (radius.width - offset)), center.y + (f32::sin(angle) * (radius.height - offset))]
+    };
+
+    for i in 0..SEGMENTS {
+        let a0 = step * (i as f32);
+        let a1 = a0 + step;
+        let (outer0, inner0, outer1, inner1) = (point(a0, 0.0), point(a0, THICKNESS), point(a1, 0.0), point(a1, THICKNESS));
+        vertex.extend_from_slice(&[
+            DebugVertex { position: outer0, color },
+            DebugVertex { position: inner0, color },
+            DebugVertex { position: inner1, color },
+            DebugVertex { position: outer1, color },
+            DebugVertex { position: outer0, color },
+            DebugVertex { position: inner1, color },
+        ]);
+    }
+}
+
+/// Four corner brackets on the edges of `area`
+fn overlay_brackets(vertex: &mut Vec<DebugVertex>, area: &AABB, color: [u8; 4]) {
+    const THICKNESS: f32 = 3.0;
+
+    let length = f32::clamp(f32::min(area.width(), area.height()) * 0.2, 8.0, 32.0);
+    let (left, top, right, bottom) = (area.left, area.top, area.right, area.bottom);
+
+    // Horizontal, then vertical part of each corner
+    overlay_rect(vertex, &aabb(pos(left, top), size(length, THICKNESS)), color);
+    overlay_rect(vertex, &aabb(pos(left, top), size(THICKNESS, length)), color);
+    overlay_rect(vertex, &aabb(pos(right - length, top), size(length, THICKNESS)), color);
+    overlay_rect(vertex, &aabb(pos(right - THICKNESS, top), size(THICKNESS, length)), color);
+    overlay_rect(vertex, &aabb(pos(left, bottom - THICKNESS), size(length, THICKNESS)), color);
+    overlay_rect(vertex, &aabb(pos(left, bottom - length), size(THICKNESS, length)), color);
+    overlay_rect(vertex, &aabb(pos(right - length, bottom - THICKNESS), size(length, THICKNESS)), color);
+    overlay_rect(vertex, &aabb(pos(right - THICKNESS, bottom - length), size(THICKNESS, length)), color);
+}
+
+/**
+    Generate the gui sprites. If the gui wasn't updated since the last frame, this doesn't do anything.
+    Only the vertex of the updated sprites are sent to the engine.
 */
 fn render_gui(game: &mut DemoGame) {
     let gui = &mut game.data.gui;
@@ -586,13 +962,21 @@ fn render_gui(game: &mut DemoGame) {
     }
 
     gui.build_sprites();
-    output.gui_indices.clear();
-    output.gui_vertex.clear();
 
-    let mut v = 0;
-    for sprite in gui.sprites().iter() {
+    let update = gui.take_sprites_update();
+    let sprites = gui.sprites();
+    let old_vertex_count = output.gui_vertex.len();
+
+    // The indices only depend on the number of sprites, so they are only generated when the gui grows
+    let mut v = (output.gui_indices.len() / 6 * 4) as u16;
+    while output.gui_indices.len() < sprites.len() * 6 {
         output.gui_indices.extend_from_slice(&[v+0, v+3, v+2, v+1, v+0, v+3]);
- 
+        v += 4;
+    }
+
+    output.gui_vertex.resize(sprites.len() * 4, GuiVertex::default());
+
+    for (index, sprite) in sprites[update.clone()].iter().enumerate() {
         let [left, top, right, bottom] = sprite.positions.splat();
         let [tleft, ttop, tright, tbottom] = sprite.texcoord.splat();
         
@@ -600,23 +984,23 @@ fn render_gui(game: &mut DemoGame) {
         let [r, g, b] = sprite.color.splat();
         let color = [r, g, b, sprite.flags];
 
-        output.gui_vertex.extend_from_slice(&[
+        let v = (update.start + index) * 4;
+        output.gui_vertex[v..v+4].copy_from_slice(&[
             GuiVertex { position: [left, top], texcoord: [tleft, ttop], color },
             GuiVertex { position: [right, top], texcoord: [tright, ttop], color },
             GuiVertex { position: [left, bottom], texcoord: [tleft, tbottom], color },
             GuiVertex { position: [right, bottom], texcoord: [tright, tbottom], color },
         ]);
-
-        v += 4;
     }
 
-    if output.gui_indices.len() > 0 {
+    if update.len() > 0 || output.gui_vertex.len() != old_vertex_count {
         output.commands.push(DrawUpdate {
             graphics: DrawUpdateType::UpdateGui,
             params: DrawUpdateParams { 
                 update_gui: UpdateGuiParams {
-                    indices_count: output.gui_indices.len() as u32,
                     vertex_count: output.gui_vertex.len() as u32,
+                    update_offset: (update.start * 4) as u32,
+                    update_count: (update.len() * 4) as u32,
                 } 
             },
         });
@@ -709,6 +1093,8 @@ impl Default for GameOutput {
             gui_indices: Vec::with_capacity(1500),
             gui_vertex: Vec::with_capacity(1000),
             debug_vertex: Vec::with_capacity(256),
+            overlay_vertex: Vec::with_capacity(256),
+            world_text_vertex: Vec::with_capacity(256),
             commands: Vec::with_capacity(32),
             sprites_builder: Vec::with_capacity(64),
         }
@@ -734,6 +1120,10 @@ impl Default for OutputIndex {
             gui_vertex_count: 0,
             debug_vertex_ptr: ::std::ptr::null(),
             debug_vertex_count: 0,
+            overlay_vertex_ptr: ::std::ptr::null(),
+            overlay_vertex_count: 0,
+            world_text_vertex_ptr: ::std::ptr::null(),
+            world_text_vertex_count: 0,
             validation: 33355,
         }
     }
@@ -151,6 +151,10 @@ impl AABB {
     pub const fn size(&self) -> Size<f32> {
         Size { width: self.width(), height: self.height() }
     }
+
+    pub const fn center(&self) -> Position<f32> {
+        Position { x: self.left + (self.width() / 2.0), y: self.top + (self.height() / 2.0) }
+    }
    
     pub fn offset(&mut self, offset: Position<f32>) {
         self.left += offset.x;
@@ -203,11 +207,12 @@ pub const fn aabb(position: Position<f32>, size: Size<f32>) -> AABB {
 pub fn split_csv<const MAX_ARGS: usize, CB: FnMut(&[&str])>(csv: &str, mut callback: CB) {
     let mut start = 0;
     let mut end = 0;
-    let mut chars_iter = csv.chars();
+    // Offsets are in bytes. Multibyte characters never contain the '\n' byte.
+    let mut bytes_iter = csv.bytes();
     let mut args: [&str; MAX_ARGS] = [""; MAX_ARGS];
-    while let Some(c) = chars_iter.next() {
+    while let Some(c) = bytes_iter.next() {
         end += 1;
-        if c == '\n' {
+        if c == b'\n' {
             let line = &csv[start..end];
             let mut args_count = 0;
             for substr in line.split(';') {
@@ -1,4 +1,5 @@
 mod gameplay_gui_state;
+mod gameplay_commands;
 
 pub mod gameplay;
 pub use gameplay::GameplayState;
@@ -28,9 +29,15 @@ pub fn update(game: &mut DemoGame) {
     let state = &mut game.state;
 
     if data.inputs.view_resized() {
+        data.gui.set_pixel_ratio(data.inputs.pixel_ratio);
         data.gui.resize(data.inputs.view_size);
     }
 
+    data.gui.process_inputs(&data.inputs);
+
+    // Mouse clicks over the gui must not reach the world
+    let gui_captures_mouse = data.gui.captures_mouse();
+
     match state {
         GameState::MainMenu => {
 
@@ -39,11 +46,11 @@ pub fn update(game: &mut DemoGame) {
             
         },
         GameState::Editor(_) => {
-            if data.inputs.left_mouse_clicked() {
+            if data.inputs.left_mouse_clicked() && !gui_captures_mouse {
                 crate::state::editor::on_left_mouse(state, data);
             }
 
-            if data.inputs.right_mouse_clicked() {
+            if data.inputs.right_mouse_clicked() && !gui_captures_mouse {
                 crate::state::editor::on_right_mouse(state, data);
             }
 
@@ -59,13 +66,16 @@ pub fn update(game: &mut DemoGame) {
 fn clear_inputs_after_state_process(inputs: &mut InputState) {
     inputs.last_mouse_position = inputs.mouse_position;
     inputs.last_view_size = inputs.view_size;
+    inputs.typed_count = 0;
+    inputs.mouse_wheel = 0.0;
     
     for state in inputs.mouse_buttons.iter_mut() {
         state.flip();
     }
     
-    inputs.space.flip();
-    inputs.left_ctrl.flip();
+    for state in inputs.keys.iter_mut() {
+        state.flip();
+    }
 }
 
 impl SaveAndLoad for GameState {
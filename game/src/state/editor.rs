@@ -1,8 +1,9 @@
 //! Special debugging state to test features
 use crate::behaviour;
+use crate::inputs::Key;
 use crate::error::Error;
 use crate::state::GameState;
-use crate::world::{StructureData, WorldObject, WorldObjectType};
+use crate::world::{CommandMarker, StructureData, WorldObject, WorldObjectType};
 use crate::{DemoGame, DemoGameData, pos};
 
 use super::gameplay_gui_state::GameplayGuiState;
@@ -58,6 +59,10 @@ pub fn init(game: &mut DemoGame, test: TestId) -> Result<(), Error> {
         TestId::PathfindingAi => init_pathfinding_ai(&mut game.data),
     }
 
+    // Index the test objects so that they can be queried before the first update
+    game.data.world.update_static_grid();
+    game.data.world.update_dynamic_grid();
+
     inner_state.gui.build(&mut game.data)?;
 
     game.state = GameState::Editor(inner_state);
@@ -213,6 +218,21 @@ pub fn on_update(state: &mut GameState, data: &mut DemoGameData) {
         }
     }
 
+    // A gui layout was modified on the development server or the language was changed
+    if data.global.flags.get_reload_gui() {
+        data.global.flags.clear_reload_gui();
+        if let Err(e) = state.gui.build(data) {
+            log_err!(e);
+        }
+    }
+
+    while let Some(event) = data.gui.pop_event() {
+        state.gui.on_gui_event(data, event);
+    }
+
+    state.gui.process_hotkeys(data);
+    state.gui.update(data);
+
     let mouse_position = data.inputs.mouse_position + data.global.view_offset;
     let pawn_position = data.world.pawns[0].position;
 
@@ -230,42 +250,63 @@ pub fn on_left_mouse(state: &mut GameState, data: &mut DemoGameData) {
     let cursor_world_position = data.inputs.mouse_position + data.global.view_offset;
     let new_selected = data.world.object_at(cursor_world_position);
 
-    match (state.gui.details_frame.displayed_object, new_selected) {
-        (None, None) | (Some(_), None) => {},
-        (None, Some(new)) => {
-            data.world.set_object_selected(new, true);
-            state.gui.set_selected_object(data, new);
+    if state.gui.execute_pending_command(data, cursor_world_position, new_selected) {
+        return;
+    }
+
+    match new_selected {
+        Some(new) if data.inputs.key_state(Key::ShiftLeft).pressed() => {
+            // Shift+click adds or removes an object from the selection
+            let selected = data.world.selected.contains(&new);
+            data.world.set_object_selected(new, !selected);
+            state.gui.sync_selection(data);
         },
-        (Some(old), Some(new)) => {
-            data.world.set_object_selected(old, false);
+        Some(new) => {
+            data.world.clear_selection();
             data.world.set_object_selected(new, true);
             state.gui.set_selected_object(data, new);
         },
+        None => {}
     }
 }
 
 pub fn on_right_mouse(state: &mut GameState, data: &mut DemoGameData) {
     let state = get_state(state);
-    let selected_object = match state.gui.details_frame.displayed_object {
-        Some(obj) => obj,
-        None => { return; }
-    };
+    if state.gui.cancel_pending_command() {
+        return;
+    }
 
     let cursor_world_position = data.inputs.mouse_position + data.global.view_offset;
     let target_object = data.world.object_at(cursor_world_position);
 
-    match selected_object.ty {
-        WorldObjectType::Pawn => pawn_actions(data, selected_object, target_object),
-        WorldObjectType::Warrior => warrior_actions(data, selected_object, target_object),
-        WorldObjectType::Archer => archer_actions(data, selected_object, target_object),
-        _ => {},
+    let selected_objects = data.world.selected.clone();
+    let commands_units = selected_objects.iter()
+        .any(|obj| matches!(obj.ty, WorldObjectType::Pawn | WorldObjectType::Warrior | WorldObjectType::Archer) );
+
+    if commands_units {
+        // Ctrl+click always moves the units, even over an object
+        let forced_move = data.inputs.key_state(Key::CtrlLeft).pressed();
+        data.world.command_marker = Some(CommandMarker {
+            target: target_object.filter(|_| !forced_move ),
+            position: cursor_world_position,
+            timestamp: data.global.time,
+        });
+    }
+
+    for selected_object in selected_objects {
+        match selected_object.ty {
+            WorldObjectType::Pawn => pawn_actions(data, selected_object, target_object),
+            WorldObjectType::Warrior => warrior_actions(data, selected_object, target_object),
+            WorldObjectType::Archer => archer_actions(data, selected_object, target_object),
+            _ => {},
+        }
     }
 }
 
 fn pawn_actions(game: &mut DemoGameData, pawn: WorldObject, target_object: Option<WorldObject>) {
     let cursor_world_position = game.inputs.mouse_position + game.global.view_offset;
 
-    if target_object.is_none() || game.inputs.left_ctrl.pressed() {
+    if target_object.is_none() || game.inputs.key_state(Key::CtrlLeft).pressed() {
         behaviour::pawn::pawn_move::new(game, pawn, cursor_world_position);
         return;
     }
@@ -291,7 +332,7 @@ fn pawn_actions(game: &mut DemoGameData, pawn: WorldObject, target_object: Optio
 fn warrior_actions(game: &mut DemoGameData, warrior: WorldObject, target_object: Option<WorldObject>) {
     let cursor_world_position = game.inputs.mouse_position + game.global.view_offset;
 
-    if target_object.is_none() || game.inputs.left_ctrl.pressed() {
+    if target_object.is_none() || game.inputs.key_state(Key::CtrlLeft).pressed() {
         behaviour::warrior::warrior_move::new(game, warrior, cursor_world_position);
         return;
     }
@@ -307,7 +348,7 @@ fn warrior_actions(game: &mut DemoGameData, warrior: WorldObject, target_object:
 fn archer_actions(game: &mut DemoGameData, archer: WorldObject, target_object: Option<WorldObject>) {
     let cursor_world_position = game.inputs.mouse_position + game.global.view_offset;
 
-    if target_object.is_none() || game.inputs.left_ctrl.pressed() {
+    if target_object.is_none() || game.inputs.key_state(Key::CtrlLeft).pressed() {
         behaviour::archer::archer_move::new(game, archer, cursor_world_position);
         return;
     }
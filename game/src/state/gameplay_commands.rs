@@ -0,0 +1,287 @@
+//! Actions available in the command card of the gameplay gui. Shared between the `editor` state and the `gameplay` state
+use crate::assets::Assets;
+use crate::behaviour::{self, pawn::{PawnBehaviour, PawnBehaviourType}, warrior::WarriorBehaviour, archer::ArcherBehaviour};
+use crate::inputs::Key;
+use crate::shared::{Position, AABB};
+use crate::world::{StructureData, WorldObject, WorldObjectType, MAX_CASTLE_HP, MAX_HOUSE_HP, MAX_TOWER_HP};
+use crate::DemoGameData;
+
+pub const COMMAND_CARD_SIZE: usize = 6;
+
+/// Offset from the castle position where trained units are spawned
+const TRAIN_SPAWN_OFFSET: Position<f32> = Position { x: 0.0, y: 30.0 };
+
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub enum GameplayCommand {
+    Move,
+    Stop,
+    Attack,
+    Harvest,
+    Build,
+    TrainPawn,
+    TrainWarrior,
+    TrainArcher,
+    CancelConstruction,
+}
+
+impl GameplayCommand {
+
+    pub fn hotkey(self) -> Key {
+        match self {
+            Self::Move => Key::M,
+            Self::Stop => Key::S,
+            Self::Attack => Key::A,
+            Self::Harvest => Key::H,
+            Self::Build => Key::B,
+            Self::TrainPawn => Key::P,
+            Self::TrainWarrior => Key::W,
+            Self::TrainArcher => Key::A,
+            Self::CancelConstruction => Key::C,
+        }
+    }
+
+    pub fn icon(self, assets: &Assets) -> AABB {
+        let gui = &assets.gui;
+        match self {
+            Self::Move => gui.move_icon,
+            Self::Stop => gui.stop_icon,
+            Self::Attack => gui.attack_icon,
+            Self::Harvest => gui.harvest_icon,
+            Self::Build => gui.build_icon,
+            Self::TrainPawn => gui.pawn_portrait,
+            Self::TrainWarrior => gui.warrior_portrait,
+            Self::TrainArcher => gui.archer_portrait,
+            Self::CancelConstruction => gui.cancel_icon,
+        }
+    }
+
+    /// Key of the command name in the string tables
+    pub fn name_key(self) -> &'static str {
+        match self {
+            Self::Move => "command.move",
+            Self::Stop => "command.stop",
+            Self::Attack => "command.attack",
+            Self::Harvest => "command.harvest",
+            Self::Build => "command.build",
+            Self::TrainPawn => "command.train_pawn",
+            Self::TrainWarrior => "command.train_warrior",
+            Self::TrainArcher => "command.train_archer",
+            Self::CancelConstruction => "command.cancel_construction",
+        }
+    }
+
+    pub fn description_key(self) -> &'static str {
+        match self {
+            Self::Move => "command.move.description",
+            Self::Stop => "command.stop.description",
+            Self::Attack => "command.attack.description",
+            Self::Harvest => "command.harvest.description",
+            Self::Build => "command.build.description",
+            Self::TrainPawn => "command.train_pawn.description",
+            Self::TrainWarrior => "command.train_warrior.description",
+            Self::TrainArcher => "command.train_archer.description",
+            Self::CancelConstruction => "command.cancel_construction.description",
+        }
+    }
+
+    /// Key of the resources spent when the command is executed, as displayed in the gui
+    pub fn cost_key(self) -> Option<&'static str> {
+        match self {
+            Self::TrainPawn | Self::TrainWarrior | Self::TrainArcher => Some("cost.population"),
+            _ => None
+        }
+    }
+
+    /// If the command must wait for the player to pick a target in the world before being executed
+    pub fn needs_target(self) -> bool {
+        match self {
+            Self::Move | Self::Attack | Self::Harvest | Self::Build => true,
+            _ => false
+        }
+    }
+
+}
+
+/// Returns the commands available for `obj`. Unused slots are set to `None`.
+pub fn commands_for(data: &DemoGameData, obj: WorldObject) -> [Option<GameplayCommand>; COMMAND_CARD_SIZE] {
+    use GameplayCommand::*;
+
+    let mut commands = [None; COMMAND_CARD_SIZE];
+    let available: &[GameplayCommand] = match obj.ty {
+        WorldObjectType::Pawn => &[Move, Stop, Attack, Harvest, Build],
+        WorldObjectType::Warrior | WorldObjectType::Archer => &[Move, Stop, Attack],
+        WorldObjectType::Structure => match data.world.structures_data[obj.id as usize] {
+            StructureData::Castle(castle) if !castle.destroyed && !castle.building => &[TrainPawn, TrainWarrior, TrainArcher],
+            StructureData::Castle(castle) if !castle.destroyed && castle.hp < MAX_CASTLE_HP => &[CancelConstruction],
+            StructureData::Tower(tower) if !tower.destroyed && tower.building && tower.hp < MAX_TOWER_HP => &[CancelConstruction],
+            StructureData::House(house) if !house.destroyed && house.building && house.hp < MAX_HOUSE_HP => &[CancelConstruction],
+            _ => &[]
+        },
+        _ => &[]
+    };
+
+    for (slot, &command) in commands.iter_mut().zip(available) {
+        *slot = Some(command);
+    }
+
+    commands
+}
+
+/// Executes a command that does not need a target
+pub fn execute(data: &mut DemoGameData, command: GameplayCommand, selected: WorldObject) {
+    match command {
+        GameplayCommand::Stop => stop(data, selected),
+        GameplayCommand::TrainPawn | GameplayCommand::TrainWarrior | GameplayCommand::TrainArcher => train(data, command, selected),
+        GameplayCommand::CancelConstruction => cancel_construction(data, selected),
+        _ => {}
+    }
+}
+
+/// Executes a command using the world position and the object picked by the player
+pub fn execute_targeted(
+    data: &mut DemoGameData,
+    command: GameplayCommand,
+    selected: WorldObject,
+    target_position: Position<f32>,
+    target_object: Option<WorldObject>
+) {
+    match command {
+        GameplayCommand::Move => move_to(data, selected, target_position),
+        GameplayCommand::Attack => attack(data, selected, target_object),
+        GameplayCommand::Harvest => harvest(data, selected, target_object),
+        GameplayCommand::Build => build(data, selected, target_object),
+        _ => execute(data, command, selected),
+    }
+}
+
+fn move_to(data: &mut DemoGameData, selected: WorldObject, target_position: Position<f32>) {
+    match selected.ty {
+        WorldObjectType::Pawn => behaviour::pawn::pawn_move::new(data, selected, target_position),
+        WorldObjectType::Warrior => behaviour::warrior::warrior_move::new(data, selected, target_position),
+        WorldObjectType::Archer => behaviour::archer::archer_move::new(data, selected, target_position),
+        _ => {}
+    }
+}
+
+fn stop(data: &mut DemoGameData, selected: WorldObject) {
+    let index = selected.id as usize;
+    match selected.ty {
+        WorldObjectType::Pawn => {
+            PawnBehaviour::cancel(data, selected.id, false);
+            if let PawnBehaviourType::MoveTo { pathfinding_state } = data.world.pawns_behaviour[index].ty {
+                data.world.pathfinding.free_path(pathfinding_state);
+            }
+
+            data.world.pawns_behaviour[index] = PawnBehaviour::idle();
+        },
+        WorldObjectType::Warrior => {
+            data.world.warriors_behaviour[index] = WarriorBehaviour::idle();
+        },
+        WorldObjectType::Archer => {
+            data.world.archers_behaviour[index] = ArcherBehaviour::idle();
+        },
+        _ => {}
+    }
+}
+
+fn attack(data: &mut DemoGameData, selected: WorldObject, target_object: Option<WorldObject>) {
+    let target = match target_object {
+        Some(target) => target,
+        None => { return; }
+    };
+
+    match (selected.ty, target.ty) {
+        (WorldObjectType::Pawn, WorldObjectType::Sheep) => behaviour::pawn::hunt_sheep::new(data, selected, target),
+        (WorldObjectType::Warrior, WorldObjectType::Sheep | WorldObjectType::Structure) => behaviour::warrior::warrior_attack::new(data, selected, target),
+        (WorldObjectType::Archer, WorldObjectType::Sheep | WorldObjectType::Structure) => behaviour::archer::shoot::new(data, selected, target),
+        _ => {}
+    }
+}
+
+fn harvest(data: &mut DemoGameData, selected: WorldObject, target_object: Option<WorldObject>) {
+    let target = match target_object {
+        Some(target) => target,
+        None => { return; }
+    };
+
+    match target.ty {
+        WorldObjectType::Tree => behaviour::pawn::harvest_wood::new(data, selected, target),
+        WorldObjectType::Resource => behaviour::pawn::grab_resource::new(data, selected, target),
+        WorldObjectType::Structure => {
+            if let StructureData::GoldMine(_) = data.world.structures_data[target.id as usize] {
+                behaviour::pawn::harvest_gold::new(data, selected, target);
+            }
+        },
+        _ => {}
+    }
+}
+
+fn build(data: &mut DemoGameData, selected: WorldObject, target_object: Option<WorldObject>) {
+    if let Some(target) = target_object {
+        if target.ty == WorldObjectType::Structure {
+            behaviour::pawn::build_structure::new(data, selected, target);
+        }
+    }
+}
+
+fn train(data: &mut DemoGameData, command: GameplayCommand, castle: WorldObject) {
+    if castle.ty != WorldObjectType::Structure {
+        return;
+    }
+
+    let castle_index = castle.id as usize;
+    match data.world.structures_data[castle_index] {
+        StructureData::Castle(castle_data) if !castle_data.building && !castle_data.destroyed => {},
+        _ => { return; }
+    }
+
+    if data.world.population() >= data.world.population_capacity() {
+        return;
+    }
+
+    let position = data.world.structures[castle_index].position + TRAIN_SPAWN_OFFSET;
+    match command {
+        GameplayCommand::TrainPawn => data.world.create_pawn(position),
+        GameplayCommand::TrainWarrior => data.world.create_warrior(position),
+        GameplayCommand::TrainArcher => data.world.create_archer(position),
+        _ => {}
+    }
+}
+
+fn cancel_construction(data: &mut DemoGameData, structure: WorldObject) {
+    if structure.ty != WorldObjectType::Structure {
+        return;
+    }
+
+    let structure_index = structure.id as usize;
+    let assets = &data.assets.structures;
+    let sprite = match &mut data.world.structures_data[structure_index] {
+        StructureData::Castle(castle) if castle.building => {
+            castle.hp = 0;
+            castle.destroyed = true;
+            assets.knights_castle_destroyed
+        },
+        StructureData::Tower(tower) if tower.building => {
+            tower.hp = 0;
+            tower.destroyed = true;
+            assets.knights_tower_destroyed
+        },
+        StructureData::House(house) if house.building => {
+            house.hp = 0;
+            house.destroyed = true;
+            assets.knights_house_destroyed
+        },
+        _ => { return; }
+    };
+
+    data.world.structures[structure_index].sprite = sprite;
+
+    // Pawns working on the construction site are sent back to idle
+    for behaviour in data.world.pawns_behaviour.iter_mut() {
+        if let PawnBehaviourType::BuildStructure { structure_id, .. } = behaviour.ty {
+            if structure_id == structure.id {
+                *behaviour = PawnBehaviour::idle();
+            }
+        }
+    }
+}
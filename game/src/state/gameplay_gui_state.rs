@@ -1,8 +1,26 @@
 //! The gameplay gui state. Shared between the `editor` state and the `gameplay` state
 use crate::error::Error;
-use crate::gui::{GuiImageId, GuiStaticTextId};
-use crate::world::{WorldObject, WorldObjectType, StructureData, ResourceType};
+use crate::gui::{GuiButtonId, GuiColor, GuiEvent, GuiImageId, GuiProgressBarId, GuiProgressId, GuiStaticTextId};
+use crate::shared::Position;
+use crate::world::{World, WorldObject, WorldObjectType, StructureData, ResourceType, Stockpile};
 use crate::DemoGameData;
+use super::gameplay_commands::{self, GameplayCommand, COMMAND_CARD_SIZE};
+
+/// Maximum number of portraits displayed when more than one object is selected
+pub const MAX_SELECTION_PORTRAITS: usize = 30;
+
+/// Name of the gui layout asset of the gameplay hud
+const GAMEPLAY_LAYOUT: &str = "gameplay_gui";
+
+/// Time in milliseconds the mouse must stay over a button or a world object before its tooltip is shown
+const TOOLTIP_DELAY: f32 = 500.0;
+
+/// Offset between the mouse and the top left corner of the tooltip
+const TOOLTIP_OFFSET: Position<f32> = Position { x: 16.0, y: 20.0 };
+
+const HEALTH_BAR_COLOR: GuiColor = GuiColor::rgb(70, 180, 60);
+const CONSTRUCTION_BAR_COLOR: GuiColor = GuiColor::rgb(70, 130, 220);
+const GOLD_BAR_COLOR: GuiColor = GuiColor::rgb(230, 190, 40);
 
 #[derive(Default)]
 #[derive(Copy, Clone)]
@@ -12,178 +30,457 @@ pub struct GameplayGuiBindings {
     pub selected_name2: GuiStaticTextId,
     pub details_icon1: GuiImageId,
     pub details_text1: GuiStaticTextId,
+    pub details_bar: GuiProgressBarId,
+    pub details_progress: GuiProgressId,
+    pub command_buttons: [GuiButtonId; COMMAND_CARD_SIZE],
+    pub command_icons: [GuiImageId; COMMAND_CARD_SIZE],
+    pub command_hotkeys: [GuiStaticTextId; COMMAND_CARD_SIZE],
+    pub stockpile_wood: GuiStaticTextId,
+    pub stockpile_gold: GuiStaticTextId,
+    pub stockpile_food: GuiStaticTextId,
+    pub population: GuiStaticTextId,
+    pub selection_buttons: [GuiButtonId; MAX_SELECTION_PORTRAITS],
+    pub selection_portraits: [GuiImageId; MAX_SELECTION_PORTRAITS],
+    pub selection_bars: [GuiProgressBarId; MAX_SELECTION_PORTRAITS],
+    pub selection_health: [GuiProgressId; MAX_SELECTION_PORTRAITS],
+    pub tooltip_title: GuiStaticTextId,
+    pub tooltip_hotkey: GuiStaticTextId,
+    pub tooltip_cost: GuiStaticTextId,
+    pub tooltip_description: GuiStaticTextId,
 }
 
 #[derive(Default)]
 #[derive(Copy, Clone)]
 pub struct DetailsFrameState {
-    pub displayed_object: Option<WorldObject>
+    pub displayed_object: Option<WorldObject>,
+    /// Current and max value displayed in the details text (ex: hp, remaining gold)
+    pub displayed_value: Option<(u8, u8)>,
+}
+
+/// Objects displayed in the portraits grid when more than one object is selected
+#[derive(Default)]
+#[derive(Copy, Clone)]
+pub struct SelectionGridState {
+    pub objects: [Option<WorldObject>; MAX_SELECTION_PORTRAITS],
+    /// Health percentage displayed under each portrait. `None` if the object has no health and its bar is hidden.
+    pub health: [Option<u8>; MAX_SELECTION_PORTRAITS],
+}
+
+#[derive(Default)]
+#[derive(Copy, Clone)]
+pub struct CommandCardState {
+    pub commands: [Option<GameplayCommand>; COMMAND_CARD_SIZE],
+    /// Command waiting for the player to pick a target in the world
+    pub pending: Option<GameplayCommand>,
+}
+
+/// Element described by the tooltip
+#[derive(Copy, Clone, PartialEq)]
+pub enum TooltipTarget {
+    Command(GameplayCommand),
+    Object(WorldObject),
+}
+
+#[derive(Default)]
+#[derive(Copy, Clone)]
+pub struct TooltipState {
+    /// Element under the mouse
+    pub target: Option<TooltipTarget>,
+    /// Time in milliseconds since the mouse entered `target`
+    pub hover_time: f32,
+}
+
+/// Values currently displayed in the resource bar
+#[derive(Copy, Clone)]
+pub struct ResourceBarState {
+    pub stockpile: Stockpile,
+    pub population: u32,
+    pub population_capacity: u32,
 }
 
 #[derive(Default)]
 #[derive(Copy, Clone)]
 pub struct GameplayGuiState {
     pub bindings: GameplayGuiBindings,
-    pub details_frame: DetailsFrameState
+    pub details_frame: DetailsFrameState,
+    pub selection_grid: SelectionGridState,
+    pub command_card: CommandCardState,
+    pub resource_bar: ResourceBarState,
+    pub tooltip: TooltipState,
+}
+
+impl Default for ResourceBarState {
+    fn default() -> Self {
+        // Initial values that can't match the world values so everything is rendered on the first update
+        let stockpile = Stockpile { wood: u32::MAX, gold: u32::MAX, food: u32::MAX };
+        ResourceBarState {
+            stockpile,
+            population: u32::MAX,
+            population_capacity: u32::MAX,
+        }
+    }
 }
 
 impl GameplayGuiState {
 
     pub fn build(&mut self, data: &mut DemoGameData) -> Result<(), Error> {
-        use crate::gui::*;
+        use crate::gui::GuiLayoutBindings;
+
+        let assets = ::std::sync::Arc::clone(&data.assets);
+        let layout = assets.gui_layouts.get(GAMEPLAY_LAYOUT)
+            .ok_or_else(|| gui_err!("Missing gui layout {:?}", GAMEPLAY_LAYOUT) )?;
+
+        let mut layout_bindings = GuiLayoutBindings::default();
 
-        let bindings = &mut self.bindings;
-    
         data.gui.clear();
         data.gui.resize(data.inputs.view_size);
-    
-        data.gui.build(|gui| {
-            let text_color = GuiColor::rgb(40, 30, 20);
-            let info_panel = gui.image(data.assets.gui.info_panel);
-    
-            gui.origin(GuiLayoutOrigin::BottomLeft);
-            gui.sizing(GuiSizing::Static { width: 450.0, height: 196.0 });
-            gui.items_align(ItemsDirection::Row, ItemsPosition::Start, ItemsAlign::Center);
-            gui.simple_frame(info_panel, |gui| {
-                
-                gui.sizing(GuiSizing::Static { width: 200.0, height: 200.0 });
-                gui.items_align(ItemsDirection::Column, ItemsPosition::Center, ItemsAlign::Center);
-                gui.group(|gui| {
-                    bindings.selected_image = gui.dyn_image();
-                    gui.image_display(GuiImageDisplay::from_image(bindings.selected_image));
-    
-                    gui.spacer(0.0, 10.0);
-    
-                    bindings.selected_name1 = gui.dyn_static_text();
-                    gui.label(GuiLabel::from_static_text_and_color(bindings.selected_name1, text_color));
-    
-                    gui.spacer(0.0, 15.0);
-    
-                    bindings.selected_name2 = gui.dyn_static_text();
-                    gui.label(GuiLabel::from_static_text_and_color(bindings.selected_name2, text_color));
-                });
-    
-                gui.sizing(GuiSizing::Static { width: 250.0, height: 200.0 });
-                gui.padding(GuiPadding { left: 15.0, top: 25.0 });
-                gui.items_align(ItemsDirection::Column, ItemsPosition::Start, ItemsAlign::Start);
-                gui.group(|gui| {
-                    
-                    gui.items_align(ItemsDirection::Row, ItemsPosition::Center, ItemsAlign::Center);
-                    gui.group(|gui| {
-                        bindings.details_icon1 = gui.dyn_image();
-                        gui.image_display(GuiImageDisplay::from_image_and_scaled_width(bindings.details_icon1, 28.0));
-    
-                        bindings.details_text1 = gui.dyn_static_text();
-                        gui.label(GuiLabel::from_static_text_and_color(bindings.details_text1, text_color));
-                    });
-    
-                });
-            });
-        })
+        data.gui.build(|gui| gui.layout(layout, &assets.gui, &mut layout_bindings) )?;
+
+        let bindings = &mut self.bindings;
+        bindings.stockpile_wood = layout_bindings.get("stockpile_wood")?;
+        bindings.stockpile_gold = layout_bindings.get("stockpile_gold")?;
+        bindings.stockpile_food = layout_bindings.get("stockpile_food")?;
+        bindings.population = layout_bindings.get("population")?;
+        bindings.selected_image = layout_bindings.get("selected_image")?;
+        bindings.selected_name1 = layout_bindings.get("selected_name1")?;
+        bindings.selected_name2 = layout_bindings.get("selected_name2")?;
+        bindings.details_icon1 = layout_bindings.get("details_icon1")?;
+        bindings.details_text1 = layout_bindings.get("details_text1")?;
+        bindings.details_bar = layout_bindings.get("details_bar")?;
+        bindings.details_progress = layout_bindings.get("details_progress")?;
+        layout_bindings.get_all("selection_buttons", &mut bindings.selection_buttons)?;
+        layout_bindings.get_all("selection_portraits", &mut bindings.selection_portraits)?;
+        layout_bindings.get_all("selection_bars", &mut bindings.selection_bars)?;
+        layout_bindings.get_all("selection_health", &mut bindings.selection_health)?;
+        layout_bindings.get_all("command_buttons", &mut bindings.command_buttons)?;
+        layout_bindings.get_all("command_icons", &mut bindings.command_icons)?;
+        layout_bindings.get_all("command_hotkeys", &mut bindings.command_hotkeys)?;
+        bindings.tooltip_title = layout_bindings.get("tooltip_title")?;
+        bindings.tooltip_hotkey = layout_bindings.get("tooltip_hotkey")?;
+        bindings.tooltip_cost = layout_bindings.get("tooltip_cost")?;
+        bindings.tooltip_description = layout_bindings.get("tooltip_description")?;
+
+        self.set_command_card(data, None);
+        self.clear_selection_grid(data);
+        data.gui.set_progress_bar_visible(self.bindings.details_bar, false);
+        self.resource_bar = ResourceBarState::default();
+        self.update_resource_bar(data);
+        self.tooltip = TooltipState::default();
+
+        // Renders the selected objects again in the new gui
+        self.details_frame = DetailsFrameState::default();
+        self.sync_selection(data);
+
+        Ok(())
+    }
+
+    /// Updates the values displayed in the gui. Must be called every frame.
+    pub fn update(&mut self, data: &mut DemoGameData) {
+        self.update_resource_bar(data);
+        self.update_details_frame(data);
+        self.update_tooltip(data);
+    }
+
+    /// Shows the tooltip of the button or the world object under the mouse once the mouse stayed over it for `TOOLTIP_DELAY`
+    fn update_tooltip(&mut self, data: &mut DemoGameData) {
+        let target = match data.gui.captures_mouse() {
+            true => {
+                let slot = data.gui.hovered_button()
+                    .and_then(|button| self.bindings.command_buttons.iter().position(|&id| id == button) );
+
+                slot.and_then(|slot| self.command_card.commands[slot] )
+                    .map(TooltipTarget::Command)
+            },
+            false => {
+                data.world.object_at(data.inputs.mouse_position + data.global.view_offset)
+                    .map(TooltipTarget::Object)
+            }
+        };
+
+        if self.tooltip.target != target {
+            self.tooltip.target = target;
+            self.tooltip.hover_time = 0.0;
+            data.gui.hide_floating();
+            return;
+        }
+
+        let target = match target {
+            Some(target) => target,
+            None => { return; }
+        };
+
+        if data.gui.floating_visible() {
+            return;
+        }
+
+        self.tooltip.hover_time += data.global.frame_delta;
+        if self.tooltip.hover_time >= TOOLTIP_DELAY {
+            self.show_tooltip(data, target);
+        }
+    }
+
+    fn show_tooltip(&mut self, data: &mut DemoGameData, target: TooltipTarget) {
+        let gui = &mut data.gui;
+        let font = &data.assets.fonts;
+        let strings = &data.assets.strings;
+        let bindings = &self.bindings;
+
+        let (title, hotkey, cost, description) = match target {
+            TooltipTarget::Command(command) => (
+                command.name_key(),
+                Some(command.hotkey().display_name()),
+                command.cost_key(),
+                command.description_key(),
+            ),
+            TooltipTarget::Object(obj) => (
+                object_name_key(&data.world, obj),
+                None,
+                None,
+                object_description_key(obj),
+            ),
+        };
+
+        gui.set_text(bindings.tooltip_title, font.compute_text_metrics(strings.text(title), 24.0));
+
+        match hotkey {
+            Some(hotkey) => gui.set_text(bindings.tooltip_hotkey, font.compute_text_metrics(&strings.format("gui.hotkey", &[&hotkey]), 18.0)),
+            None => gui.clear_text(bindings.tooltip_hotkey),
+        }
+
+        match cost {
+            Some(cost) => gui.set_text(bindings.tooltip_cost, font.compute_text_metrics(&strings.format("gui.cost", &[&strings.text(cost)]), 18.0)),
+            None => gui.clear_text(bindings.tooltip_cost),
+        }
+
+        // The description is wrapped using the `wrap_width` of its label in the layout
+        gui.set_label_text(bindings.tooltip_description, strings.text(description), 18.0);
+
+        let scale = gui.scale();
+        let offset = Position { x: TOOLTIP_OFFSET.x * scale, y: TOOLTIP_OFFSET.y * scale };
+        gui.show_floating(data.inputs.mouse_position + offset);
+    }
+
+    /// Updates the resource bar texts that changed since the last call
+    fn update_resource_bar(&mut self, data: &mut DemoGameData) {
+        let gui = &mut data.gui;
+        let font = &data.assets.fonts;
+        let bindings = &self.bindings;
+        let displayed = &mut self.resource_bar;
+
+        let stockpile = data.world.stockpile;
+        let population = data.world.population();
+        let population_capacity = data.world.population_capacity();
+
+        if displayed.stockpile.wood != stockpile.wood {
+            gui.set_text(bindings.stockpile_wood, font.compute_text_metrics(&stockpile.wood.to_string(), 24.0));
+        }
+
+        if displayed.stockpile.gold != stockpile.gold {
+            gui.set_text(bindings.stockpile_gold, font.compute_text_metrics(&stockpile.gold.to_string(), 24.0));
+        }
+
+        if displayed.stockpile.food != stockpile.food {
+            gui.set_text(bindings.stockpile_food, font.compute_text_metrics(&stockpile.food.to_string(), 24.0));
+        }
+
+        if displayed.population != population || displayed.population_capacity != population_capacity {
+            let text = format!("{} / {}", population, population_capacity);
+            gui.set_text(bindings.population, font.compute_text_metrics(&text, 24.0));
+        }
+
+        displayed.stockpile = stockpile;
+        displayed.population = population;
+        displayed.population_capacity = population_capacity;
+    }
+
+    /// Handles the events generated by the gui. See `Gui::pop_event`
+    pub fn on_gui_event(&mut self, data: &mut DemoGameData, event: GuiEvent) {
+        match event {
+            GuiEvent::ButtonClicked(button) => {
+                if let Some(slot) = self.bindings.selection_buttons.iter().position(|&id| id == button) {
+                    self.select_portrait(data, slot);
+                    return;
+                }
+
+                let slot = self.bindings.command_buttons.iter().position(|&id| id == button);
+                if let Some(command) = slot.and_then(|slot| self.command_card.commands[slot]) {
+                    self.trigger_command(data, command);
+                }
+            },
+            GuiEvent::TextInputSubmitted(input) => {
+                // No gameplay layout binds a text input yet
+                dbg!("Text input submitted: {:?}", data.gui.text_input_value(input));
+            },
+        }
+    }
+
+    /// Triggers the command card actions using their hotkeys
+    pub fn process_hotkeys(&mut self, data: &mut DemoGameData) {
+        use crate::inputs::Key;
+
+        // Keys are typed in the focused text input
+        if data.gui.captures_keyboard() {
+            return;
+        }
+
+        if data.inputs.key_state(Key::Escape).just_pressed() {
+            self.command_card.pending = None;
+        }
+
+        for command in self.command_card.commands {
+            if let Some(command) = command {
+                if data.inputs.key_state(command.hotkey()).just_pressed() {
+                    self.trigger_command(data, command);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Executes the pending command of the command card on the world target picked by the player.
+    /// Returns `false` if there was no pending command.
+    pub fn execute_pending_command(&mut self, data: &mut DemoGameData, target_position: Position<f32>, target_object: Option<WorldObject>) -> bool {
+        let command = match self.command_card.pending.take() {
+            Some(command) => command,
+            None => { return false; }
+        };
+
+        for selected in command_targets(data, command) {
+            gameplay_commands::execute_targeted(data, command, selected, target_position, target_object);
+        }
+
+        true
+    }
+
+    /// Cancels the pending command of the command card. Returns `false` if there was no pending command.
+    pub fn cancel_pending_command(&mut self) -> bool {
+        self.command_card.pending.take().is_some()
+    }
+
+    fn trigger_command(&mut self, data: &mut DemoGameData, command: GameplayCommand) {
+        if self.details_frame.displayed_object.is_none() {
+            return;
+        }
+
+        if command.needs_target() {
+            self.command_card.pending = Some(command);
+            return;
+        }
+
+        self.command_card.pending = None;
+        for selected in command_targets(data, command) {
+            gameplay_commands::execute(data, command, selected);
+        }
+
+        // Commands can change the state of the selected objects (ex: canceling a construction)
+        self.sync_selection(data);
+    }
+
+    fn set_command_card(&mut self, data: &mut DemoGameData, selected: Option<WorldObject>) {
+        let card = &mut self.command_card;
+        card.pending = None;
+        card.commands = match selected {
+            Some(selected) => gameplay_commands::commands_for(data, selected),
+            None => [None; COMMAND_CARD_SIZE],
+        };
+
+        let bindings = &self.bindings;
+        let gui = &mut data.gui;
+        let font = &data.assets.fonts;
+
+        for (slot, command) in card.commands.iter().enumerate() {
+            match command {
+                Some(command) => {
+                    gui.set_image(bindings.command_icons[slot], command.icon(&data.assets));
+                    gui.set_text(bindings.command_hotkeys[slot], font.compute_text_metrics(command.hotkey().display_name(), 16.0));
+                    gui.set_button_visible(bindings.command_buttons[slot], true);
+                },
+                None => {
+                    gui.set_button_visible(bindings.command_buttons[slot], false);
+                }
+            }
+        }
+    }
+
+    /// Updates the details frame from the objects selected in the world
+    pub fn sync_selection(&mut self, data: &mut DemoGameData) {
+        match data.world.selected.len() {
+            0 => self.clear_selected_object(data),
+            1 => {
+                let selected = data.world.selected[0];
+                self.set_selected_object(data, selected);
+            },
+            _ => self.set_selection_grid(data),
+        }
     }
 
     pub fn set_selected_object(&mut self, data: &mut DemoGameData, new_selected: WorldObject) {
+        self.clear_selection_grid(data);
+
         let gui = &mut data.gui;
         let bindings = &self.bindings;
-        let font = &data.assets.fonts.roboto;
+        let font = &data.assets.fonts;
 
         self.details_frame.displayed_object = Some(new_selected);
 
         let image_asset = data.assets.object_gui_image(new_selected.ty);
         gui.set_image(bindings.selected_image, image_asset);
     
-        let text = font.compute_text_metrics(new_selected.ty.name(), 26.0);
+        let text = font.compute_text_metrics(data.assets.strings.text(new_selected.ty.name_key()), 26.0);
         gui.set_text(bindings.selected_name1, text);
 
         match new_selected.ty {
             WorldObjectType::Structure => self.select_structure(data, new_selected),
             WorldObjectType::Resource => self.select_resource(data, new_selected),
-            WorldObjectType::Sheep => self.select_sheep(data, new_selected),
-            WorldObjectType::Tree => self.select_tree(data, new_selected),
+            WorldObjectType::Sheep => self.select_sheep(data),
+            WorldObjectType::Tree => self.select_tree(data),
             _ => self.select_other(data)
         }
+
+        // Forces the details values to be rendered on the next update
+        self.details_frame.displayed_value = Some((u8::MAX, u8::MAX));
+        self.set_command_card(data, Some(new_selected));
+        self.update_details_frame(data);
     }
 
     fn select_structure(&mut self, data: &mut DemoGameData, new_selected: WorldObject) {
         let gui = &mut data.gui;
-        let font = &data.assets.fonts.roboto;
+        let font = &data.assets.fonts;
         let bindings = &self.bindings;
-        match data.world.structures_data[new_selected.id as usize] {
-            StructureData::GoldMine(mine_data) => {
-                let text_1 = font.compute_text_metrics("Gold Mine", 22.0);
-                let text_2 = font.compute_text_metrics(&format!("  {} / {}", mine_data.remaining_gold, crate::world::MAX_GOLD_MINE_AMOUNT), 28.0);
-
-                gui.set_text(bindings.selected_name2, text_1);
-                gui.set_image(self.bindings.details_icon1, data.assets.gui.gold_icon);
-                gui.set_text(self.bindings.details_text1, text_2);
-            },
-            StructureData::Castle(castle_data) => {
-                let text_1 = font.compute_text_metrics("Castle", 22.0);
-                let text_2 = font.compute_text_metrics(&format!("  {} / {}", castle_data.hp, crate::world::MAX_CASTLE_HP), 28.0);
-
-                gui.set_text(bindings.selected_name2, text_1);
-                gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-                gui.set_text(self.bindings.details_text1, text_2);
-            },
-            StructureData::Tower(tower_data) => {
-                let text_1 = font.compute_text_metrics("Tower", 22.0);
-                let text_2 = font.compute_text_metrics(&format!("  {} / {}", tower_data.hp, crate::world::MAX_TOWER_HP), 28.0);
-
-                gui.set_text(bindings.selected_name2, text_1);
-                gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-                gui.set_text(self.bindings.details_text1, text_2);
-            },
-            StructureData::House(house_data) => {
-                let text_1 = font.compute_text_metrics("House", 22.0);
-                let text_2 = font.compute_text_metrics(&format!("  {} / {}", house_data.hp, crate::world::MAX_HOUSE_HP), 28.0);
-
-                gui.set_text(bindings.selected_name2, text_1);
-                gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-                gui.set_text(self.bindings.details_text1, text_2);
-            },
-            StructureData::GoblinHut(house_data) => {
-                let text_1 = font.compute_text_metrics("Goblin Hut", 22.0);
-                let text_2 = font.compute_text_metrics(&format!("  {} / {}", house_data.hp, crate::world::MAX_GOBIN_HUT_LIFE), 28.0);
+        let icon = match data.world.structures_data[new_selected.id as usize] {
+            StructureData::GoldMine(_) => data.assets.gui.gold_icon,
+            _ => data.assets.gui.life_icon,
+        };
 
-                gui.set_text(bindings.selected_name2, text_1);
-                gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-                gui.set_text(self.bindings.details_text1, text_2);
-            }
-        }
+        let name = data.assets.strings.text(object_name_key(&data.world, new_selected));
+        gui.set_text(bindings.selected_name2, font.compute_text_metrics(name, 22.0));
+        gui.set_image(bindings.details_icon1, icon);
     }
 
     fn select_resource(&mut self, data: &mut DemoGameData, new_selected: WorldObject) {
         let gui = &mut data.gui;
-        let font = &data.assets.fonts.roboto;
+        let font = &data.assets.fonts;
         let bindings = &self.bindings;
         
-        let (image, name) = match data.world.resources_data[new_selected.id as usize].resource_type {
-            ResourceType::Food => (data.assets.gui.meat_icon, "Meat"),
-            ResourceType::Gold => (data.assets.gui.gold_icon, "Gold"),
-            ResourceType::Wood => (data.assets.gui.wood_icon, "Wood"),
+        let image = match data.world.resources_data[new_selected.id as usize].resource_type {
+            ResourceType::Food => data.assets.gui.meat_icon,
+            ResourceType::Gold => data.assets.gui.gold_icon,
+            ResourceType::Wood => data.assets.gui.wood_icon,
         };
 
+        let name = data.assets.strings.text(object_name_key(&data.world, new_selected));
         gui.set_image(bindings.selected_image, image);
         gui.set_text(bindings.selected_name2, font.compute_text_metrics(name, 22.0));
         gui.clear_image(bindings.details_icon1);
-        gui.clear_text(bindings.details_text1);
     }
 
-    fn select_tree(&mut self, data: &mut DemoGameData, new_selected: WorldObject) {
-        let tree_data = data.world.trees_data[new_selected.id as usize];
-        let text = data.assets.fonts.roboto.compute_text_metrics(&format!("  {} / {}", tree_data.life, crate::world::MAX_TREE_LIFE), 28.0);
-
+    fn select_tree(&mut self, data: &mut DemoGameData) {
         data.gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-        data.gui.set_text(self.bindings.details_text1, text);
         data.gui.clear_text(self.bindings.selected_name2);
     }
 
-    fn select_sheep(&mut self, data: &mut DemoGameData, new_selected: WorldObject) {
-        let sheep_data = data.world.sheeps_data[new_selected.id as usize];
-        let text = data.assets.fonts.roboto.compute_text_metrics(&format!("  {} / {}", sheep_data.life, crate::world::MAX_SHEEP_LIFE), 28.0);
-
+    fn select_sheep(&mut self, data: &mut DemoGameData) {
         data.gui.set_image(self.bindings.details_icon1, data.assets.gui.life_icon);
-        data.gui.set_text(self.bindings.details_text1, text);
         data.gui.clear_text(self.bindings.selected_name2);
     }
 
@@ -192,6 +489,251 @@ impl GameplayGuiState {
         let gui = &mut data.gui;
         gui.clear_text(bindings.selected_name2);
         gui.clear_image(bindings.details_icon1);
+    }
+
+    /// Clears the details frame and unselects the displayed object
+    pub fn clear_selected_object(&mut self, data: &mut DemoGameData) {
+        if self.details_frame.displayed_object.take().is_none() {
+            return;
+        }
+
+        data.world.clear_selection();
+        self.clear_selection_grid(data);
+
+        let bindings = &self.bindings;
+        let gui = &mut data.gui;
+        gui.clear_image(bindings.selected_image);
+        gui.clear_text(bindings.selected_name1);
+        gui.clear_text(bindings.selected_name2);
+        gui.clear_image(bindings.details_icon1);
+        gui.clear_text(bindings.details_text1);
+        gui.set_progress_bar_visible(bindings.details_bar, false);
+        self.details_frame.displayed_value = None;
+
+        self.set_command_card(data, None);
+    }
+
+    /// Updates the details frame values that changed since the last call.
+    /// Clears the details frame if the displayed object was removed from the world.
+    fn update_details_frame(&mut self, data: &mut DemoGameData) {
+        if self.selection_grid.objects[0].is_some() {
+            self.update_selection_grid(data);
+            return;
+        }
+
+        let selected = match self.details_frame.displayed_object {
+            Some(selected) => selected,
+            None => { return; }
+        };
+
+        if data.world.is_object_removed(selected) {
+            self.clear_selected_object(data);
+            return;
+        }
+
+        let value = details_value(data, selected);
+        if self.details_frame.displayed_value != value {
+            let bindings = &self.bindings;
+            match value {
+                Some((value, max)) => {
+                    let text = data.assets.fonts.compute_text_metrics(&format!("  {} / {}", value, max), 28.0);
+                    data.gui.set_text(bindings.details_text1, text);
+                    data.gui.set_progress(bindings.details_progress, (value as f32) / f32::max(max as f32, 1.0));
+                    data.gui.set_progress_color(bindings.details_progress, details_bar_color(data, selected));
+                    data.gui.set_progress_bar_visible(bindings.details_bar, true);
+                },
+                None => {
+                    data.gui.clear_text(bindings.details_text1);
+                    data.gui.set_progress_bar_visible(bindings.details_bar, false);
+                }
+            }
+
+            self.details_frame.displayed_value = value;
+        }
+
+        // Available commands change with the object state (ex: a construction being completed)
+        if self.command_card.commands != gameplay_commands::commands_for(data, selected) {
+            self.set_command_card(data, Some(selected));
+        }
+    }
+
+    /// Shows the portraits of the selected objects in the details frame
+    fn set_selection_grid(&mut self, data: &mut DemoGameData) {
+        let first = data.world.selected[0];
+        self.details_frame.displayed_object = Some(first);
+        self.details_frame.displayed_value = None;
+
+        let gui = &mut data.gui;
+        let font = &data.assets.fonts;
+        let bindings = &self.bindings;
+
+        let selected_count = data.world.selected.len();
+        let text = font.compute_text_metrics(&data.assets.strings.format("gui.selected_count", &[&selected_count]), 26.0);
+        gui.clear_image(bindings.selected_image);
+        gui.set_text(bindings.selected_name1, text);
+        gui.clear_text(bindings.selected_name2);
+        gui.clear_image(bindings.details_icon1);
         gui.clear_text(bindings.details_text1);
+        gui.set_progress_bar_visible(bindings.details_bar, false);
+
+        let grid = &mut self.selection_grid;
+        for slot in 0..MAX_SELECTION_PORTRAITS {
+            match data.world.selected.get(slot) {
+                Some(&obj) => {
+                    grid.objects[slot] = Some(obj);
+                    // Forces the health bar update in `update_selection_grid`
+                    grid.health[slot] = Some(u8::MAX);
+                    gui.set_image(bindings.selection_portraits[slot], data.assets.object_gui_image(obj.ty));
+                    gui.set_button_visible(bindings.selection_buttons[slot], true);
+                },
+                None => {
+                    grid.objects[slot] = None;
+                    gui.set_button_visible(bindings.selection_buttons[slot], false);
+                    gui.set_progress_bar_visible(bindings.selection_bars[slot], false);
+                }
+            }
+        }
+
+        self.set_command_card(data, Some(first));
+        self.update_selection_grid(data);
+    }
+
+    fn clear_selection_grid(&mut self, data: &mut DemoGameData) {
+        for slot in 0..MAX_SELECTION_PORTRAITS {
+            data.gui.set_button_visible(self.bindings.selection_buttons[slot], false);
+            data.gui.set_progress_bar_visible(self.bindings.selection_bars[slot], false);
+            self.selection_grid.objects[slot] = None;
+        }
+    }
+
+    /// Updates the health bars of the portraits grid. Objects removed from the world are removed from the selection.
+    fn update_selection_grid(&mut self, data: &mut DemoGameData) {
+        let removed: Vec<WorldObject> = data.world.selected.iter()
+            .copied()
+            .filter(|&obj| data.world.is_object_removed(obj))
+            .collect();
+
+        if !removed.is_empty() {
+            for obj in removed {
+                data.world.set_object_selected(obj, false);
+            }
+
+            self.sync_selection(data);
+            return;
+        }
+
+        let grid = &mut self.selection_grid;
+        for slot in 0..MAX_SELECTION_PORTRAITS {
+            let obj = match grid.objects[slot] {
+                Some(obj) => obj,
+                None => { break; }
+            };
+
+            let health = health_percent(data, obj);
+            if grid.health[slot] != health {
+                if let Some(health) = health {
+                    data.gui.set_progress(self.bindings.selection_health[slot], health as f32 / 100.0);
+                }
+
+                data.gui.set_progress_bar_visible(self.bindings.selection_bars[slot], health.is_some());
+                grid.health[slot] = health;
+            }
+        }
+    }
+
+    /// Clicking a portrait narrows the selection to its object. Shift+click removes the object from the selection instead.
+    fn select_portrait(&mut self, data: &mut DemoGameData, slot: usize) {
+        use crate::inputs::Key;
+
+        let obj = match self.selection_grid.objects[slot] {
+            Some(obj) => obj,
+            None => { return; }
+        };
+
+        if data.inputs.key_state(Key::ShiftLeft).pressed() {
+            data.world.set_object_selected(obj, false);
+        } else {
+            data.world.clear_selection();
+            data.world.set_object_selected(obj, true);
+        }
+
+        self.sync_selection(data);
+    }
+}
+
+/// Key of the name of the structure or the resource type for structures and resources. Name of the object type for the other objects.
+fn object_name_key(world: &World, obj: WorldObject) -> &'static str {
+    match obj.ty {
+        WorldObjectType::Structure => match world.structures_data[obj.id as usize] {
+            StructureData::GoldMine(_) => "structure.gold_mine",
+            StructureData::Castle(_) => "structure.castle",
+            StructureData::Tower(_) => "structure.tower",
+            StructureData::House(_) => "structure.house",
+            StructureData::GoblinHut(_) => "structure.goblin_hut",
+        },
+        WorldObjectType::Resource => world.resources_data[obj.id as usize].resource_type.name_key(),
+        ty => ty.name_key()
+    }
+}
+
+fn object_description_key(obj: WorldObject) -> &'static str {
+    match obj.ty {
+        WorldObjectType::Pawn => "object.pawn.description",
+        WorldObjectType::Warrior => "object.warrior.description",
+        WorldObjectType::Archer => "object.archer.description",
+        WorldObjectType::TorchGoblin => "object.torch_goblin.description",
+        WorldObjectType::DynamiteGoblin => "object.dynamite_goblin.description",
+        WorldObjectType::Sheep => "object.sheep.description",
+        WorldObjectType::Tree => "object.tree.description",
+        WorldObjectType::Structure => "object.structure.description",
+        WorldObjectType::Resource => "object.resource.description",
+        _ => ""
+    }
+}
+
+/// Current and max value displayed in the details frame for `obj`. `None` if the object has no value to display.
+fn details_value(data: &DemoGameData, obj: WorldObject) -> Option<(u8, u8)> {
+    use crate::world::*;
+
+    let index = obj.id as usize;
+    match obj.ty {
+        WorldObjectType::Structure => match data.world.structures_data[index] {
+            StructureData::GoldMine(mine_data) => Some((mine_data.remaining_gold, MAX_GOLD_MINE_AMOUNT)),
+            StructureData::Castle(castle_data) => Some((castle_data.hp, MAX_CASTLE_HP)),
+            StructureData::Tower(tower_data) => Some((tower_data.hp, MAX_TOWER_HP)),
+            StructureData::House(house_data) => Some((house_data.hp, MAX_HOUSE_HP)),
+            StructureData::GoblinHut(hut_data) => Some((hut_data.hp, MAX_GOBIN_HUT_LIFE)),
+        },
+        WorldObjectType::Tree => Some((data.world.trees_data[index].life, MAX_TREE_LIFE)),
+        WorldObjectType::Sheep => Some((data.world.sheeps_data[index].life, MAX_SHEEP_LIFE)),
+        _ => None
+    }
+}
+
+/// Color of the details progress bar. Structures being built show their construction progress instead of their health.
+fn details_bar_color(data: &DemoGameData, obj: WorldObject) -> GuiColor {
+    if obj.ty != WorldObjectType::Structure {
+        return HEALTH_BAR_COLOR;
+    }
+
+    match data.world.structures_data[obj.id as usize] {
+        StructureData::GoldMine(_) => GOLD_BAR_COLOR,
+        StructureData::Castle(castle) if castle.building => CONSTRUCTION_BAR_COLOR,
+        StructureData::Tower(tower) if tower.building => CONSTRUCTION_BAR_COLOR,
+        StructureData::House(house) if house.building => CONSTRUCTION_BAR_COLOR,
+        _ => HEALTH_BAR_COLOR,
     }
 }
+
+/// Health of `obj` in percent, or `None` if `obj` has no health (ex: units and gold mines). See `World::health`
+fn health_percent(data: &DemoGameData, obj: WorldObject) -> Option<u8> {
+    data.world.health(obj).map(|(value, max)| ((value as u32 * 100) / u32::max(max as u32, 1)) as u8 )
+}
+
+/// Selected objects that can execute `command`
+fn command_targets(data: &DemoGameData, command: GameplayCommand) -> Vec<WorldObject> {
+    data.world.selected.iter()
+        .copied()
+        .filter(|&obj| gameplay_commands::commands_for(data, obj).contains(&Some(command)))
+        .collect()
+}
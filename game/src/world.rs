@@ -6,10 +6,21 @@ pub use extra_data::*;
 
 mod generate_navmesh;
 
+mod spatial_grid;
+pub use spatial_grid::{SpatialGrid, DynamicGrid};
+
+pub mod queries;
+
+mod particles;
+pub use particles::{Particles, ParticleType};
+
+mod floating_text;
+pub use floating_text::{FloatingTexts, DAMAGE_TEXT_COLOR, RESOURCE_TEXT_COLOR};
+
 
 use std::hint::unreachable_unchecked;
 use std::sync::Arc;
-use crate::assets::{Assets, AnimationBase, Texture};
+use crate::assets::{Assets, AnimationBase, AnimationEvents, Texture};
 use crate::behaviour;
 use crate::pathfinding::PathfindingState;
 use crate::shared::{AABB, aabb, size, pos};
@@ -33,18 +44,19 @@ pub enum WorldObjectType {
 }
 
 impl WorldObjectType {
-    pub fn name(&self) -> &'static str {
+    /// Key of the name of the object type in the string tables. See `assets::Strings`
+    pub fn name_key(&self) -> &'static str {
         match self {
-            Self::Pawn => "Pawn",
-            Self::Warrior => "Warrior",
-            Self::Archer => "Archer",
-            Self::TorchGoblin => "Torch Goblin",
-            Self::DynamiteGoblin => "Dynamite Goblin",
-            Self::Sheep => "Sheep",
-            Self::Decoration => "Decoration",
-            Self::Structure => "Structure",
-            Self::Tree => "Tree",
-            Self::Resource => "Resource",
+            Self::Pawn => "object.pawn",
+            Self::Warrior => "object.warrior",
+            Self::Archer => "object.archer",
+            Self::TorchGoblin => "object.torch_goblin",
+            Self::DynamiteGoblin => "object.dynamite_goblin",
+            Self::Sheep => "object.sheep",
+            Self::Decoration => "object.decoration",
+            Self::Structure => "object.structure",
+            Self::Tree => "object.tree",
+            Self::Resource => "object.resource",
             _ => ""
         }
     }
@@ -56,11 +68,31 @@ pub struct WorldObject {
     pub ty: WorldObjectType,
 }
 
+/// How an animation continues after its last frame
+#[derive(Copy, Clone, Default, PartialEq, Eq)]
+pub enum AnimationMode {
+    /// Starts again from the first frame
+    #[default]
+    Loop,
+    /// Holds the last frame
+    Once,
+    /// Switches to the idle animation of the actor
+    OnceThenIdle,
+}
+
 #[derive(Copy, Clone, Default)]
 pub struct BaseAnimated {
     pub position: Position<f32>,
     pub animation: AnimationBase,
     pub current_frame: u8,
+    /// Time in milliseconds spent on `current_frame`
+    pub frame_time: f32,
+    /// Events of the frames entered during the last animation update
+    pub events: AnimationEvents,
+    pub mode: AnimationMode,
+    /// Set when an animation completed and holds its last frame (`AnimationMode::Once`, or `AnimationMode::OnceThenIdle` without an idle animation).
+    /// Cleared when a new animation is played. The update completing the animation also sets `AnimationEvents::FINISHED`.
+    pub finished: bool,
     pub selected: bool,
     pub flipped: bool,
     pub deleted: bool,
@@ -75,10 +107,72 @@ impl BaseAnimated {
         aabb(position, size)
     }
 
+    /// Plays `animation` in a loop from its first frame
+    pub fn play(&mut self, animation: AnimationBase) {
+        self.play_with_mode(animation, AnimationMode::Loop);
+    }
+
+    /// Plays `animation` a single time from its first frame. See `BaseAnimated::finished`
+    pub fn play_once(&mut self, animation: AnimationBase) {
+        self.play_with_mode(animation, AnimationMode::Once);
+    }
+
+    pub fn play_with_mode(&mut self, animation: AnimationBase, mode: AnimationMode) {
+        self.animation = animation;
+        self.current_frame = 0;
+        self.frame_time = 0.0;
+        self.mode = mode;
+        self.finished = false;
+    }
+
+    /// Advances the animation by `delta` milliseconds. What happens after the last frame depends on `mode`.
+    /// `idle` is played when an `AnimationMode::OnceThenIdle` animation completes. If there is none, the last frame is held.
+    pub fn update_animation(&mut self, delta: f32, idle: Option<AnimationBase>) {
+        self.events = AnimationEvents::NONE;
+
+        let frame_duration = self.animation.frame_duration;
+        if frame_duration <= 0.0 || self.finished {
+            return;
+        }
+
+        self.frame_time += delta;
+        while self.frame_time >= frame_duration {
+            if self.current_frame < self.animation.last_frame {
+                self.frame_time -= frame_duration;
+                self.current_frame += 1;
+                self.events.insert(self.animation.frame_events(self.current_frame));
+                continue;
+            }
+
+            match (self.mode, idle) {
+                (AnimationMode::Loop, _) => {
+                    self.frame_time -= frame_duration;
+                    self.current_frame = 0;
+                    self.events.insert(self.animation.frame_events(0));
+                },
+                (AnimationMode::OnceThenIdle, Some(idle)) => {
+                    // The idle animation loops, so the completion is only reported by the event
+                    self.play(idle);
+                    self.events.insert(AnimationEvents::FINISHED);
+                    break;
+                },
+                _ => {
+                    self.frame_time = 0.0;
+                    self.finished = true;
+                    self.events.insert(AnimationEvents::FINISHED);
+                    break;
+                }
+            }
+        }
+    }
+
     /// Marks this animation as "deleted".
     pub fn delete(&mut self) {
         self.position = pos(0.0, 0.0);
         self.animation = AnimationBase::default();
+        self.events = AnimationEvents::NONE;
+        self.mode = AnimationMode::Loop;
+        self.finished = false;
         self.selected = false;
         self.flipped = false;
         self.deleted = true;
@@ -119,6 +213,22 @@ pub struct BaseProjectile {
     pub deleted: bool,
 }
 
+/// Time in milliseconds a health bar stays visible after the last damage, then the time it takes to fade out
+pub const HEALTH_BAR_VISIBLE_TIME: f64 = 3000.0;
+pub const HEALTH_BAR_FADE_TIME: f64 = 1000.0;
+
+/// Time in milliseconds the command marker is shown
+pub const COMMAND_MARKER_TIME: f64 = 600.0;
+
+/// Target of the last command given to the selected units. Shown as a short pulse in the world overlay.
+#[derive(Copy, Clone)]
+pub struct CommandMarker {
+    /// The targeted object, or `None` if the command targets the ground (ex: a move command)
+    pub target: Option<WorldObject>,
+    pub position: Position<f32>,
+    pub timestamp: f64,
+}
+
 
 /// The game world data. Includes actors, terrain, and decorations
 pub struct World {
@@ -160,8 +270,29 @@ pub struct World {
 
     pub decorations: Vec<BaseStatic>,
 
+    /// Trees, resource spawns, decorations and structures. These objects never move, so the grid is only built again when they are added.
+    /// Not saved, the grid is built again after a reload.
+    pub static_grid: SpatialGrid,
+    /// Units and resources on the ground. Updated every frame after the behaviours moved the units. See `World::update_dynamic_grid`
+    /// Not saved, the grid is built again after a reload.
+    pub dynamic_grid: DynamicGrid,
+
     pub selected: Vec<WorldObject>,
 
+    /// Objects that took damage recently, with the time of the last damage. Used to show their health bar. Not saved.
+    pub damaged: Vec<(WorldObject, f64)>,
+
+    /// Not saved
+    pub command_marker: Option<CommandMarker>,
+
+    /// Not saved
+    pub particles: Particles,
+
+    /// Not saved
+    pub floating_texts: FloatingTexts,
+
+    pub stockpile: Stockpile,
+
     pub total_sprite_count: u32,
     pub static_resources_texture: Texture,
     pub units_texture: Texture,
@@ -181,6 +312,44 @@ impl World {
         self.arrows.len()
     }
 
+    /// The number of units owned by the player
+    pub fn population(&self) -> u32 {
+        let count = |units: &[BaseAnimated]| units.iter().filter(|unit| !unit.deleted).count() as u32;
+        count(&self.pawns) + count(&self.warriors) + count(&self.archers)
+    }
+
+    /// The maximum number of units the player can own. Given by the completed castles and houses.
+    pub fn population_capacity(&self) -> u32 {
+        self.structures_data.iter()
+            .map(|data| match data {
+                StructureData::Castle(castle) if !castle.building && !castle.destroyed => CASTLE_POPULATION,
+                StructureData::House(house) if !house.building && !house.destroyed => HOUSE_POPULATION,
+                _ => 0
+            })
+            .sum()
+    }
+
+    /// Builds the static objects grid again if static objects were added or removed since the last call
+    pub fn update_static_grid(&mut self) {
+        let indexed_count = self.trees.len() + self.resources_spawn.len() + self.decorations.len() + self.structures.len();
+        if !self.static_grid.dirty && self.static_grid.len() == indexed_count {
+            return;
+        }
+
+        let mut objects = Vec::with_capacity(indexed_count);
+        for (group, ty) in [(&self.trees, WorldObjectType::Tree), (&self.resources_spawn, WorldObjectType::ResourceSpawn)] {
+            objects.extend(group.iter().enumerate().map(|(id, base)| (WorldObject { id: id as u32, ty }, base.position) ));
+        }
+
+        for (group, ty) in [(&self.decorations, WorldObjectType::Decoration), (&self.structures, WorldObjectType::Structure)] {
+            objects.extend(group.iter().enumerate().map(|(id, base)| (WorldObject { id: id as u32, ty }, base.position) ));
+        }
+
+        let width = self.terrain.max_width_pixel() as f32;
+        let height = self.terrain.max_height_pixel() as f32;
+        self.static_grid.build(width, height, &objects);
+    }
+
     pub fn generate_navmesh(&mut self) {
         generate_navmesh::generate(self);
     }
@@ -220,7 +389,15 @@ impl World {
         self.arrows.clear();
         self.arrows_data.clear();
 
+        self.static_grid.dirty = true;
+        self.dynamic_grid.clear();
+
         self.selected.clear();
+        self.damaged.clear();
+        self.command_marker = None;
+        self.particles.clear();
+        self.floating_texts.clear();
+        self.stockpile = Stockpile::default();
         self.terrain.reset();
         self.pathfinding.clear();
     }
@@ -372,90 +549,6 @@ impl World {
         self.total_sprite_count += 1;
     }
 
-    pub fn object_at(&self, position: Position<f32>) -> Option<WorldObject> {
-        fn animated_at(world: &World, position: Position<f32>, out: &mut Option<WorldObject>, y_out: &mut f32) {
-            let types = [
-                WorldObjectType::Pawn,
-                WorldObjectType::Warrior,
-                WorldObjectType::Archer,
-                WorldObjectType::TorchGoblin,
-                WorldObjectType::DynamiteGoblin,
-                WorldObjectType::Sheep,
-                WorldObjectType::ResourceSpawn,
-                WorldObjectType::Tree,
-            ];
-            let groups: [&[BaseAnimated]; 8] = [
-                &world.pawns,
-                &world.warriors,
-                &world.archers,
-                &world.torch_goblins,
-                &world.tnt_goblins,
-                &world.sheeps,
-                &world.resources_spawn,
-                &world.trees,
-            ];
-    
-            let mut obj = None;
-            let mut y = *y_out;
-    
-            for (group, ty) in groups.into_iter().zip(types) {
-                for (id, actor) in group.iter().enumerate() {
-                    let aabb = actor.aabb();
-                    if aabb.point_inside(position) {
-                        if aabb.bottom > y {
-                            obj = Some(WorldObject { id: id as u32, ty });
-                            y = aabb.bottom;
-                        }
-                    }
-                }
-            }
-    
-            if obj.is_some() {
-                *out = obj;
-                *y_out = y;
-            }
-        }
-    
-        fn other_at(world: &World, position: Position<f32>, out: &mut Option<WorldObject>, y_out: &mut f32) {
-            let types = [
-                WorldObjectType::Structure,
-                WorldObjectType::Resource,
-            ];
-            let groups = [
-                &world.structures,
-                &world.resources,
-            ];
-    
-            let mut obj = None;
-            let mut y = *y_out;
-    
-            for (group, ty) in groups.into_iter().zip(types) {
-                for (id, resource) in group.iter().enumerate() {
-                    let aabb = resource.aabb();
-                    if aabb.point_inside(position) {
-                        if aabb.bottom > y {
-                            obj = Some(WorldObject { id: id as u32, ty });
-                            y = aabb.bottom;
-                        }
-                    }
-                }
-            }
-    
-            if obj.is_some() {
-                *out = obj;
-                *y_out = y;
-            }
-        }
-        
-        let mut obj = None;
-        let mut y = -10000.0;
-
-        animated_at(self, position, &mut obj, &mut y);
-        other_at(self, position, &mut obj, &mut y);
-
-        obj
-    }
-
     pub fn get_actor_mut<'a>(&'a mut self, obj: WorldObject) -> Option<&'a mut BaseAnimated> {
         let objects = match obj.ty {
             WorldObjectType::Pawn => &mut self.pawns,
@@ -482,29 +575,99 @@ impl World {
         objects.get_mut(obj.id as usize)
     }
 
+    /// Returns the current and the maximum health of `obj`, or `None` if `obj` cannot be damaged or was destroyed
+    pub fn health(&self, obj: WorldObject) -> Option<(u8, u8)> {
+        let index = obj.id as usize;
+        let health = match obj.ty {
+            WorldObjectType::Sheep => self.sheeps_data.get(index).map(|data| (data.life, MAX_SHEEP_LIFE) ),
+            WorldObjectType::Structure => match self.structures_data.get(index) {
+                Some(StructureData::Castle(data)) if !data.destroyed => Some((data.hp, MAX_CASTLE_HP)),
+                Some(StructureData::Tower(data)) if !data.destroyed => Some((data.hp, MAX_TOWER_HP)),
+                Some(StructureData::House(data)) if !data.destroyed => Some((data.hp, MAX_HOUSE_HP)),
+                Some(StructureData::GoblinHut(data)) if !data.destroyed => Some((data.hp, MAX_GOBIN_HUT_LIFE)),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        health.filter(|&(current, _)| current > 0 )
+    }
+
+    /// Remembers that `obj` was damaged at `time`. See `World::damaged`
+    pub fn set_damaged(&mut self, obj: WorldObject, time: f64) {
+        match self.damaged.iter_mut().find(|(damaged, _)| *damaged == obj ) {
+            Some(damaged) => { damaged.1 = time; },
+            None => { self.damaged.push((obj, time)); }
+        }
+    }
+
+    /// Forgets the damaged objects whose health bar faded out and the command marker once its pulse is over
+    pub fn expire_overlay_markers(&mut self, time: f64) {
+        self.damaged.retain(|&(_, timestamp)| time - timestamp < HEALTH_BAR_VISIBLE_TIME + HEALTH_BAR_FADE_TIME );
+
+        if self.command_marker.map(|marker| time - marker.timestamp > COMMAND_MARKER_TIME).unwrap_or(false) {
+            self.command_marker = None;
+        }
+    }
+
+    /// Returns `true` if `obj` was removed from the world (ex: a dead sheep) or can't be interacted with anymore (ex: a resource carried by a pawn)
+    pub fn is_object_removed(&self, obj: WorldObject) -> bool {
+        let index = obj.id as usize;
+        let actors = match obj.ty {
+            WorldObjectType::Pawn => &self.pawns,
+            WorldObjectType::Warrior => &self.warriors,
+            WorldObjectType::Archer => &self.archers,
+            WorldObjectType::TorchGoblin => &self.torch_goblins,
+            WorldObjectType::DynamiteGoblin => &self.tnt_goblins,
+            WorldObjectType::Sheep => &self.sheeps,
+            WorldObjectType::ResourceSpawn => &self.resources_spawn,
+            WorldObjectType::Tree => &self.trees,
+            WorldObjectType::Resource => {
+                return self.resources_data.get(index).map(|data| data.grabbed).unwrap_or(true);
+            },
+            WorldObjectType::Structure => {
+                return index >= self.structures.len();
+            },
+            WorldObjectType::Decoration => {
+                return index >= self.decorations.len();
+            },
+        };
+
+        actors.get(index).map(|actor| actor.deleted).unwrap_or(true)
+    }
+
+    /// Unselecting an object always removes it from `selected`, even if it was deleted in the meantime
     pub fn set_object_selected(&mut self, obj: WorldObject, selected: bool) {
         let mut add = false;
-        let mut remove = false;
 
         if let Some(actor) = self.get_actor_mut(obj) {
             add = !actor.selected && selected;
-            remove = actor.selected && !selected;
             actor.selected = selected;
         } else if let Some(statiq) = self.get_static_mut(obj) {
             add = !statiq.selected && selected;
-            remove = statiq.selected && !selected;
             statiq.selected = selected;
         }
 
         if add {
             self.selected.push(obj);
-        } else if remove {
+        } else if !selected {
             if let Some(index) = self.selected.iter().position(|&obj2| obj == obj2 ) {
                 self.selected.swap_remove(index);
             }
         }
     }
 
+    /// Unselects every selected object
+    pub fn clear_selection(&mut self) {
+        while let Some(obj) = self.selected.pop() {
+            if let Some(actor) = self.get_actor_mut(obj) {
+                actor.selected = false;
+            } else if let Some(statiq) = self.get_static_mut(obj) {
+                statiq.selected = false;
+            }
+        }
+    }
+
     fn assets(&self) -> &Assets {
         match self.assets.as_ref() {
             Some(assets) => assets,
@@ -555,6 +718,8 @@ impl SaveAndLoad for World {
 
         writer.write_slice(&self.selected);
 
+        writer.write(&self.stockpile);
+
         writer.write_u32(self.total_sprite_count);
         writer.write(&self.static_resources_texture);
         writer.write(&self.units_texture);
@@ -599,11 +764,13 @@ impl SaveAndLoad for World {
 
         let selected = reader.read_vec();
 
+        let stockpile = reader.read();
+
         let total_sprite_count = reader.read_u32();
         let static_resources_texture = reader.read();
         let units_texture = reader.read();
 
-        World {
+        let mut world = World {
             assets: None,
 
             terrain,
@@ -642,12 +809,27 @@ impl SaveAndLoad for World {
 
             decorations,
 
+            static_grid: SpatialGrid::new(),
+            dynamic_grid: DynamicGrid::new(),
+
             selected,
+            damaged: Vec::new(),
+            command_marker: None,
+            particles: Particles::default(),
+            floating_texts: FloatingTexts::default(),
+
+            stockpile,
 
             total_sprite_count,
             static_resources_texture,
             units_texture,
-        }
+        };
+
+        // The grids are not saved
+        world.update_static_grid();
+        world.update_dynamic_grid();
+
+        world
     }
 
 }
@@ -692,7 +874,16 @@ impl Default for World {
 
             decorations: Vec::with_capacity(16),
 
+            static_grid: SpatialGrid::new(),
+            dynamic_grid: DynamicGrid::new(),
+
             selected: Vec::with_capacity(8),
+            damaged: Vec::with_capacity(8),
+            command_marker: None,
+            particles: Particles::default(),
+            floating_texts: FloatingTexts::default(),
+
+            stockpile: Stockpile::default(),
 
             total_sprite_count: 0,
             static_resources_texture: Texture { id: 0 },
@@ -11,6 +11,8 @@ pub const MAX_GOLD_MINE_AMOUNT: u8 = 10;
 pub const MAX_TREE_LIFE: u8 = 15;
 pub const MAX_SHEEP_LIFE: u8 = 10;
 pub const MAX_GOBIN_HUT_LIFE: u8 = 50;
+pub const CASTLE_POPULATION: u32 = 10;
+pub const HOUSE_POPULATION: u32 = 5;
 
 #[derive(Copy, Clone)]
 pub struct TreeData {
@@ -50,12 +52,43 @@ pub enum ResourceType {
     Gold
 }
 
+impl ResourceType {
+    /// Key of the name of the resource in the string tables. See `assets::Strings`
+    pub fn name_key(&self) -> &'static str {
+        match self {
+            Self::Food => "resource.meat",
+            Self::Gold => "resource.gold",
+            Self::Wood => "resource.wood",
+        }
+    }
+}
+
 /// Align the resource to 4 bytes to allow quick store/load
 #[repr(align(4))]
 #[derive(Copy, Clone)]
 pub struct ResourceData {
     pub resource_type: ResourceType,
     pub grabbed: bool,
+    /// If the resource was already added to the player stockpile
+    pub collected: bool,
+}
+
+/// Resources owned by the player
+#[derive(Copy, Clone, Default, PartialEq)]
+pub struct Stockpile {
+    pub wood: u32,
+    pub gold: u32,
+    pub food: u32,
+}
+
+impl Stockpile {
+    pub fn add(&mut self, resource_type: ResourceType, amount: u32) {
+        match resource_type {
+            ResourceType::Wood => { self.wood += amount; },
+            ResourceType::Food => { self.food += amount; },
+            ResourceType::Gold => { self.gold += amount; },
+        }
+    }
 }
 
 #[derive(Copy, Clone)]
@@ -0,0 +1,75 @@
+//! Short lived texts shown over the world objects (ex: damage taken, resources gained).
+//!
+//! Texts rise and fade out above the position they were spawned at. The text is formatted and laid out when it is spawned,
+//! so it keeps the language selected at that time. They are not saved.
+use crate::assets::{Fonts, TextMetrics};
+use crate::shared::Position;
+
+/// Maximum number of live texts. New texts are dropped when this is reached.
+const MAX_FLOATING_TEXTS: usize = 128;
+
+/// Lifetime of a floating text in milliseconds
+const FLOATING_TEXT_LIFETIME: f32 = 1200.0;
+
+/// Rising speed of a floating text in pixels per second
+const FLOATING_TEXT_RISE: f32 = 40.0;
+
+/// Font size of the floating texts
+const FLOATING_TEXT_SIZE: f32 = 22.0;
+
+pub const DAMAGE_TEXT_COLOR: [u8; 3] = [235, 60, 50];
+pub const RESOURCE_TEXT_COLOR: [u8; 3] = [250, 250, 250];
+
+pub struct FloatingText {
+    /// Layout of the text, computed once when the text is spawned
+    pub metrics: TextMetrics,
+    /// Bottom center of the text
+    pub position: Position<f32>,
+    pub color: [u8; 3],
+    /// Age in milliseconds
+    pub age: f32,
+}
+
+impl FloatingText {
+
+    /// Texts stay opaque for the first half of their lifetime, then fade out
+    pub fn alpha(&self) -> f32 {
+        let t = f32::min(self.age / FLOATING_TEXT_LIFETIME, 1.0);
+        f32::min((1.0 - t) * 2.0, 1.0)
+    }
+
+}
+
+#[derive(Default)]
+pub struct FloatingTexts {
+    pub texts: Vec<FloatingText>,
+}
+
+impl FloatingTexts {
+
+    /// Spawns `text` with its bottom center at `position`. The text is laid out with the font chain of `fonts`.
+    pub fn add(&mut self, fonts: &Fonts, text: &str, position: Position<f32>, color: [u8; 3]) {
+        if self.texts.len() >= MAX_FLOATING_TEXTS {
+            return;
+        }
+
+        let metrics = fonts.compute_text_metrics(text, FLOATING_TEXT_SIZE);
+        self.texts.push(FloatingText { metrics, position, color, age: 0.0 });
+    }
+
+    /// Moves the texts up and removes the expired ones. `delta` is in milliseconds
+    pub fn update(&mut self, delta: f32) {
+        let rise = FLOATING_TEXT_RISE * (delta / 1000.0);
+        for text in self.texts.iter_mut() {
+            text.age += delta;
+            text.position.y -= rise;
+        }
+
+        self.texts.retain(|text| text.age < FLOATING_TEXT_LIFETIME );
+    }
+
+    pub fn clear(&mut self) {
+        self.texts.clear();
+    }
+
+}
@@ -0,0 +1,163 @@
+//! Short lived effects (dust, wood chips, sparks, smoke) spawned by the behaviours.
+//!
+//! Particles move on the ground plane with a separate height, so that falling particles (ex: wood chips) land where they were thrown.
+//! They are not saved.
+use crate::shared::Position;
+
+/// Maximum number of live particles. New particles are dropped when this is reached.
+const MAX_PARTICLES: usize = 2048;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParticleType {
+    Dust,
+    WoodChip,
+    Spark,
+    Smoke,
+}
+
+#[derive(Copy, Clone)]
+pub struct Particle {
+    pub ty: ParticleType,
+    /// Position of the particle on the ground. Also used to order the particle with the other sprites.
+    pub position: Position<f32>,
+    /// Height of the particle over `position`
+    pub height: f32,
+    /// Ground velocity in pixels per second
+    pub velocity: Position<f32>,
+    /// Height velocity in pixels per second
+    pub rise: f32,
+    /// Age and lifetime are in milliseconds
+    pub age: f32,
+    pub lifetime: f32,
+    pub size: f32,
+}
+
+impl Particle {
+
+    /// Particles fade out over their lifetime
+    pub fn alpha(&self) -> f32 {
+        let t = f32::min(self.age / self.lifetime, 1.0);
+        1.0 - (t * t)
+    }
+
+}
+
+/// Spawns particles at `position` every `interval` milliseconds until `remaining` reaches 0
+#[derive(Copy, Clone)]
+pub struct ParticleEmitter {
+    pub ty: ParticleType,
+    pub position: Position<f32>,
+    pub remaining: f32,
+    pub interval: f32,
+    pub next: f32,
+}
+
+/// How the particles of a type are spawned and moved
+struct ParticleParams {
+    speed: (f32, f32),
+    rise: (f32, f32),
+    lifetime: (f32, f32),
+    size: (f32, f32),
+    /// Added to `rise` every second. Negative values pull the particles down to the ground.
+    gravity: f32,
+    /// Fraction of the ground velocity kept every second
+    drag: f32,
+}
+
+const fn particle_params(ty: ParticleType) -> ParticleParams {
+    match ty {
+        ParticleType::Dust => ParticleParams { speed: (10.0, 30.0), rise: (5.0, 15.0), lifetime: (400.0, 700.0), size: (10.0, 16.0), gravity: 0.0, drag: 0.1 },
+        ParticleType::WoodChip => ParticleParams { speed: (40.0, 90.0), rise: (80.0, 160.0), lifetime: (600.0, 900.0), size: (6.0, 10.0), gravity: -400.0, drag: 0.3 },
+        ParticleType::Spark => ParticleParams { speed: (80.0, 160.0), rise: (40.0, 120.0), lifetime: (150.0, 300.0), size: (4.0, 8.0), gravity: -300.0, drag: 0.2 },
+        ParticleType::Smoke => ParticleParams { speed: (5.0, 15.0), rise: (20.0, 40.0), lifetime: (1500.0, 2500.0), size: (24.0, 40.0), gravity: 0.0, drag: 0.5 },
+    }
+}
+
+fn random_range((min, max): (f32, f32)) -> f32 {
+    min + (fastrand::f32() * (max - min))
+}
+
+#[derive(Default)]
+pub struct Particles {
+    pub particles: Vec<Particle>,
+    pub emitters: Vec<ParticleEmitter>,
+}
+
+impl Particles {
+
+    /// Spawns `count` particles of `ty` at `position` in random directions
+    pub fn burst(&mut self, ty: ParticleType, position: Position<f32>, count: u32) {
+        let params = particle_params(ty);
+        for _ in 0..count {
+            if self.particles.len() >= MAX_PARTICLES {
+                return;
+            }
+
+            let angle = fastrand::f32() * std::f32::consts::TAU;
+            let speed = random_range(params.speed);
+            self.particles.push(Particle {
+                ty,
+                position,
+                height: 0.0,
+                velocity: Position { x: f32::cos(angle) * speed, y: f32::sin(angle) * speed * 0.5 },
+                rise: random_range(params.rise),
+                age: 0.0,
+                lifetime: random_range(params.lifetime),
+                size: random_range(params.size),
+            });
+        }
+    }
+
+    /// Spawns a particle of `ty` at `position` every `interval` ms for `duration` ms
+    pub fn emit(&mut self, ty: ParticleType, position: Position<f32>, duration: f32, interval: f32) {
+        self.emitters.push(ParticleEmitter { ty, position, remaining: duration, interval, next: 0.0 });
+    }
+
+    /// Moves the particles and runs the emitters. `delta` is in milliseconds
+    pub fn update(&mut self, delta: f32) {
+        let mut index = 0;
+        while index < self.emitters.len() {
+            let mut emitter = self.emitters[index];
+            emitter.remaining -= delta;
+            emitter.next -= delta;
+            while emitter.next <= 0.0 && emitter.remaining > 0.0 {
+                self.burst(emitter.ty, emitter.position, 1);
+                emitter.next += emitter.interval;
+            }
+
+            if emitter.remaining <= 0.0 {
+                self.emitters.swap_remove(index);
+            } else {
+                self.emitters[index] = emitter;
+                index += 1;
+            }
+        }
+
+        let seconds = delta / 1000.0;
+        for particle in self.particles.iter_mut() {
+            let params = particle_params(particle.ty);
+            let drag = f32::powf(params.drag, seconds);
+
+            particle.age += delta;
+            particle.position.x += particle.velocity.x * seconds;
+            particle.position.y += particle.velocity.y * seconds;
+            particle.velocity.x *= drag;
+            particle.velocity.y *= drag;
+
+            particle.rise += params.gravity * seconds;
+            particle.height = f32::max(particle.height + (particle.rise * seconds), 0.0);
+            if particle.height == 0.0 {
+                // Landed
+                particle.velocity = Position { x: 0.0, y: 0.0 };
+            }
+        }
+
+        self.particles.retain(|particle| particle.age < particle.lifetime );
+    }
+
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.emitters.clear();
+    }
+
+}
@@ -0,0 +1,231 @@
+//! Spatial queries over the world objects
+//!
+//! Objects are found using the two grids of the world. `static_grid` holds objects that never move,
+//! `dynamic_grid` holds the units and the resources and is updated after the behaviours moved them.
+//! Results are always tested against the current position of the objects, so an out of date grid may miss objects, but never returns wrong ones.
+use crate::shared::{AABB, Position};
+use super::{World, WorldObject, WorldObjectType, BaseAnimated, StructureData};
+
+/// Extra space around a query area when searching the grids.
+/// Objects are indexed by the bottom center of their sprite, so this must cover half the width and the height of the largest sprite (the castle).
+pub const SPRITE_QUERY_MARGIN: f32 = 320.0;
+
+/// Who controls a world object
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WorldObjectOwner {
+    Player,
+    Enemy,
+    Neutral,
+}
+
+/// Restricts the objects returned by the world queries
+#[derive(Copy, Clone, Default)]
+pub struct QueryFilter<'a> {
+    /// Accepted object types. Every type is accepted if empty.
+    pub types: &'a [WorldObjectType],
+    pub owner: Option<WorldObjectOwner>,
+}
+
+impl<'a> QueryFilter<'a> {
+
+    pub const fn types(types: &'a [WorldObjectType]) -> Self {
+        QueryFilter { types, owner: None }
+    }
+
+    pub const fn with_owner(mut self, owner: WorldObjectOwner) -> Self {
+        self.owner = Some(owner);
+        self
+    }
+
+    fn accepts(&self, world: &World, obj: WorldObject) -> bool {
+        if !self.types.is_empty() && !self.types.contains(&obj.ty) {
+            return false;
+        }
+
+        match self.owner {
+            Some(owner) => world.owner(obj) == owner,
+            None => true
+        }
+    }
+
+}
+
+impl World {
+
+    /// Moves the units and the resources lying on the ground to their current cell in `dynamic_grid`.
+    /// Called once per frame after the behaviours moved the units. Deleted units and grabbed resources are removed from the grid.
+    pub fn update_dynamic_grid(&mut self) {
+        let width = self.terrain.max_width_pixel() as f32;
+        let height = self.terrain.max_height_pixel() as f32;
+        self.dynamic_grid.resize(width, height);
+
+        let groups: [(&[BaseAnimated], WorldObjectType); 6] = [
+            (&self.pawns, WorldObjectType::Pawn),
+            (&self.warriors, WorldObjectType::Warrior),
+            (&self.archers, WorldObjectType::Archer),
+            (&self.torch_goblins, WorldObjectType::TorchGoblin),
+            (&self.tnt_goblins, WorldObjectType::DynamiteGoblin),
+            (&self.sheeps, WorldObjectType::Sheep),
+        ];
+
+        for (group, ty) in groups {
+            for (id, actor) in group.iter().enumerate() {
+                let position = match actor.deleted {
+                    true => None,
+                    false => Some(actor.position)
+                };
+
+                self.dynamic_grid.update(WorldObject { id: id as u32, ty }, position);
+            }
+        }
+
+        for (id, (resource, data)) in self.resources.iter().zip(self.resources_data.iter()).enumerate() {
+            let position = match data.grabbed {
+                true => None,
+                false => Some(resource.position)
+            };
+
+            self.dynamic_grid.update(WorldObject { id: id as u32, ty: WorldObjectType::Resource }, position);
+        }
+    }
+
+    pub fn owner(&self, obj: WorldObject) -> WorldObjectOwner {
+        match obj.ty {
+            WorldObjectType::Pawn | WorldObjectType::Warrior | WorldObjectType::Archer => WorldObjectOwner::Player,
+            WorldObjectType::TorchGoblin | WorldObjectType::DynamiteGoblin => WorldObjectOwner::Enemy,
+            WorldObjectType::Structure => match self.structures_data.get(obj.id as usize) {
+                // The ruins of a goblin hut belong to nobody
+                Some(StructureData::GoblinHut(hut)) => match hut.destroyed {
+                    true => WorldObjectOwner::Neutral,
+                    false => WorldObjectOwner::Enemy,
+                },
+                Some(StructureData::GoldMine(_)) | None => WorldObjectOwner::Neutral,
+                Some(_) => WorldObjectOwner::Player,
+            },
+            WorldObjectType::Sheep | WorldObjectType::Decoration | WorldObjectType::Resource |
+            WorldObjectType::ResourceSpawn | WorldObjectType::Tree => WorldObjectOwner::Neutral,
+        }
+    }
+
+    /// Returns the position (the bottom center of the sprite) and the sprite aabb of `obj`
+    pub fn object_bounds(&self, obj: WorldObject) -> Option<(Position<f32>, AABB)> {
+        let index = obj.id as usize;
+        let actors = match obj.ty {
+            WorldObjectType::Pawn => &self.pawns,
+            WorldObjectType::Warrior => &self.warriors,
+            WorldObjectType::Archer => &self.archers,
+            WorldObjectType::TorchGoblin => &self.torch_goblins,
+            WorldObjectType::DynamiteGoblin => &self.tnt_goblins,
+            WorldObjectType::Sheep => &self.sheeps,
+            WorldObjectType::ResourceSpawn => &self.resources_spawn,
+            WorldObjectType::Tree => &self.trees,
+            WorldObjectType::Decoration => {
+                return self.decorations.get(index).map(|base| (base.position, base.aabb()) );
+            },
+            WorldObjectType::Structure => {
+                return self.structures.get(index).map(|base| (base.position, base.aabb()) );
+            },
+            WorldObjectType::Resource => {
+                return self.resources.get(index).map(|base| (base.position, base.aabb()) );
+            },
+        };
+
+        actors.get(index).map(|actor| (actor.position, actor.aabb()) )
+    }
+
+    /// Calls `callback` for every object accepted by `filter` with its sprite overlapping `area`
+    pub fn query_aabb<CB: FnMut(WorldObject)>(&self, area: AABB, filter: QueryFilter, mut callback: CB) {
+        let mut search_area = area;
+        search_area.left -= SPRITE_QUERY_MARGIN;
+        search_area.right += SPRITE_QUERY_MARGIN;
+        search_area.bottom += SPRITE_QUERY_MARGIN;
+
+        self.query_grids(search_area, |obj| {
+            match self.object_bounds(obj) {
+                Some((_, sprite)) if sprite.intersects(&area) && filter.accepts(self, obj) => callback(obj),
+                _ => {}
+            }
+        });
+    }
+
+    /// Calls `callback` for every object accepted by `filter` with its sprite under `point`
+    pub fn query_point<CB: FnMut(WorldObject)>(&self, point: Position<f32>, filter: QueryFilter, callback: CB) {
+        let area = AABB { left: point.x, top: point.y, right: point.x, bottom: point.y };
+        self.query_aabb(area, filter, callback);
+    }
+
+    /// Calls `callback` for every object accepted by `filter` with its position less than `radius` pixels away from `center`
+    pub fn query_radius<CB: FnMut(WorldObject)>(&self, center: Position<f32>, radius: f32, filter: QueryFilter, mut callback: CB) {
+        let search_area = AABB {
+            left: center.x - radius,
+            top: center.y - radius,
+            right: center.x + radius,
+            bottom: center.y + radius,
+        };
+
+        self.query_grids(search_area, |obj| {
+            match self.object_bounds(obj) {
+                Some((position, _)) if position.distance(center) <= radius && filter.accepts(self, obj) => callback(obj),
+                _ => {}
+            }
+        });
+    }
+
+    /// Returns the closest object to `center` accepted by `filter` in `radius`
+    pub fn closest_object(&self, center: Position<f32>, radius: f32, filter: QueryFilter) -> Option<WorldObject> {
+        let mut closest = None;
+        let mut closest_distance = f32::INFINITY;
+        self.query_radius(center, radius, filter, |obj| {
+            if let Some((position, _)) = self.object_bounds(obj) {
+                let distance = position.distance(center);
+                if distance < closest_distance {
+                    closest = Some(obj);
+                    closest_distance = distance;
+                }
+            }
+        });
+
+        closest
+    }
+
+    /// Returns the object under `position`. If multiple objects overlap, the one drawn on top is returned.
+    pub fn object_at(&self, position: Position<f32>) -> Option<WorldObject> {
+        const SELECTABLE: &[WorldObjectType] = &[
+            WorldObjectType::Pawn,
+            WorldObjectType::Warrior,
+            WorldObjectType::Archer,
+            WorldObjectType::TorchGoblin,
+            WorldObjectType::DynamiteGoblin,
+            WorldObjectType::Sheep,
+            WorldObjectType::ResourceSpawn,
+            WorldObjectType::Tree,
+            WorldObjectType::Structure,
+            WorldObjectType::Resource,
+        ];
+
+        let mut obj = None;
+        let mut y = f32::NEG_INFINITY;
+        self.query_point(position, QueryFilter::types(SELECTABLE), |found| {
+            if let Some((_, sprite)) = self.object_bounds(found) {
+                if sprite.bottom > y {
+                    obj = Some(found);
+                    y = sprite.bottom;
+                }
+            }
+        });
+
+        obj
+    }
+
+    fn query_grids<CB: FnMut(WorldObject)>(&self, area: AABB, mut callback: CB) {
+        let mut callback_present = |obj: WorldObject| {
+            if !self.is_object_removed(obj) {
+                callback(obj);
+            }
+        };
+
+        self.static_grid.query(area, &mut callback_present);
+        self.dynamic_grid.query(area, &mut callback_present);
+    }
+
+}
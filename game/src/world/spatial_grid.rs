@@ -0,0 +1,216 @@
+//! Uniform grids over the world. Objects are stored in the cell under their position (the bottom center of their sprite).
+//!
+//! `SpatialGrid` packs the objects of every cell in a single array and must be built again when an object is added or moved.
+//! `DynamicGrid` keeps a list per cell and moves an object to another cell only when its position leaves its current cell.
+use crate::shared::{AABB, Position};
+use super::{WorldObject, WorldObjectType};
+
+/// Size of a grid cell in pixels
+pub const GRID_CELL_SIZE: f32 = 256.0;
+
+pub struct SpatialGrid {
+    width: u32,
+    height: u32,
+    /// Index of the first object of each cell in `objects`. Has one more value than the number of cells.
+    cells: Vec<u32>,
+    objects: Vec<WorldObject>,
+    /// Set when the objects must be indexed again. See `World::update_static_grid`
+    pub dirty: bool,
+}
+
+impl SpatialGrid {
+
+    /// Creates an empty grid. The grid starts dirty so that it is built on first use.
+    pub fn new() -> Self {
+        SpatialGrid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            objects: Vec::new(),
+            dirty: true,
+        }
+    }
+
+    /// Indexes `objects` in a grid covering `width` by `height` pixels. Objects outside of the grid are stored in the border cells.
+    pub fn build(&mut self, width: f32, height: f32, objects: &[(WorldObject, Position<f32>)]) {
+        self.width = u32::max((width / GRID_CELL_SIZE).ceil() as u32, 1);
+        self.height = u32::max((height / GRID_CELL_SIZE).ceil() as u32, 1);
+        self.dirty = false;
+
+        // Counting sort of the objects by cell
+        let cell_count = (self.width * self.height) as usize;
+        self.cells.clear();
+        self.cells.resize(cell_count + 1, 0);
+        for &(_, position) in objects {
+            let cell = self.cell_index(position);
+            self.cells[cell + 1] += 1;
+        }
+
+        for cell in 0..cell_count {
+            self.cells[cell + 1] += self.cells[cell];
+        }
+
+        let mut fill = self.cells.clone();
+        let placeholder = WorldObject { id: 0, ty: WorldObjectType::Decoration };
+        self.objects.clear();
+        self.objects.resize(self.cells[cell_count] as usize, placeholder);
+        for &(obj, position) in objects {
+            let cell = self.cell_index(position);
+            self.objects[fill[cell] as usize] = obj;
+            fill[cell] += 1;
+        }
+    }
+
+    /// Number of objects in the grid
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Calls `callback` for every object with its position inside of the cells overlapping `area`.
+    /// The objects outside of `area` in these cells are also returned.
+    pub fn query<CB: FnMut(WorldObject)>(&self, area: AABB, mut callback: CB) {
+        if self.objects.is_empty() {
+            return;
+        }
+
+        let (left, top) = self.cell_coordinates(Position { x: area.left, y: area.top });
+        let (right, bottom) = self.cell_coordinates(Position { x: area.right, y: area.bottom });
+        for y in top..=bottom {
+            let row = y * self.width;
+            let start = self.cells[(row + left) as usize] as usize;
+            let end = self.cells[(row + right + 1) as usize] as usize;
+            for &obj in self.objects[start..end].iter() {
+                callback(obj);
+            }
+        }
+    }
+
+    fn cell_coordinates(&self, position: Position<f32>) -> (u32, u32) {
+        cell_coordinates(self.width, self.height, position)
+    }
+
+    fn cell_index(&self, position: Position<f32>) -> usize {
+        let (x, y) = self.cell_coordinates(position);
+        (y * self.width + x) as usize
+    }
+
+}
+
+/// Value of `DynamicGrid::object_cells` for the objects that are not in the grid
+const NO_CELL: u32 = u32::MAX;
+
+pub struct DynamicGrid {
+    width: u32,
+    height: u32,
+    cells: Vec<Vec<WorldObject>>,
+    /// Cell of every object in the grid, indexed by object type then by object id
+    object_cells: Vec<Vec<u32>>,
+}
+
+impl DynamicGrid {
+
+    pub fn new() -> Self {
+        DynamicGrid {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+            object_cells: Vec::new(),
+        }
+    }
+
+    /// Sets the area covered by the grid. Removes every object if the size of the grid changed.
+    pub fn resize(&mut self, width: f32, height: f32) {
+        let width = u32::max((width / GRID_CELL_SIZE).ceil() as u32, 1);
+        let height = u32::max((height / GRID_CELL_SIZE).ceil() as u32, 1);
+        if self.width == width && self.height == height {
+            return;
+        }
+
+        self.width = width;
+        self.height = height;
+        self.clear();
+        self.cells.resize_with((width * height) as usize, Vec::new);
+    }
+
+    /// Removes every object from the grid
+    pub fn clear(&mut self) {
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+
+        for cells in self.object_cells.iter_mut() {
+            cells.clear();
+        }
+    }
+
+    /// Moves `obj` to the cell under `position`. `obj` is removed from the grid if `position` is `None`.
+    /// Does nothing if `obj` is still in the same cell.
+    pub fn update(&mut self, obj: WorldObject, position: Option<Position<f32>>) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        let new_cell = match position {
+            Some(position) => {
+                let (x, y) = cell_coordinates(self.width, self.height, position);
+                y * self.width + x
+            },
+            None => NO_CELL
+        };
+
+        let ty = obj.ty as usize;
+        let id = obj.id as usize;
+        if self.object_cells.len() <= ty {
+            self.object_cells.resize_with(ty + 1, Vec::new);
+        }
+
+        let object_cells = &mut self.object_cells[ty];
+        if object_cells.len() <= id {
+            object_cells.resize(id + 1, NO_CELL);
+        }
+
+        let old_cell = object_cells[id];
+        if old_cell == new_cell {
+            return;
+        }
+
+        object_cells[id] = new_cell;
+
+        if old_cell != NO_CELL {
+            let cell = &mut self.cells[old_cell as usize];
+            if let Some(index) = cell.iter().position(|&other| other == obj) {
+                cell.swap_remove(index);
+            }
+        }
+
+        if new_cell != NO_CELL {
+            self.cells[new_cell as usize].push(obj);
+        }
+    }
+
+    /// Calls `callback` for every object with its position inside of the cells overlapping `area`.
+    /// The objects outside of `area` in these cells are also returned.
+    pub fn query<CB: FnMut(WorldObject)>(&self, area: AABB, mut callback: CB) {
+        if self.cells.is_empty() {
+            return;
+        }
+
+        let (left, top) = cell_coordinates(self.width, self.height, Position { x: area.left, y: area.top });
+        let (right, bottom) = cell_coordinates(self.width, self.height, Position { x: area.right, y: area.bottom });
+        for y in top..=bottom {
+            let row = (y * self.width) as usize;
+            for cell in self.cells[row + left as usize..=row + right as usize].iter() {
+                for &obj in cell.iter() {
+                    callback(obj);
+                }
+            }
+        }
+    }
+
+}
+
+fn cell_coordinates(width: u32, height: u32, position: Position<f32>) -> (u32, u32) {
+    let x = f32::max(position.x / GRID_CELL_SIZE, 0.0) as u32;
+    let y = f32::max(position.y / GRID_CELL_SIZE, 0.0) as u32;
+    (u32::min(x, width - 1), u32::min(y, height - 1))
+}
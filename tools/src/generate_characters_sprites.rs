@@ -61,6 +61,38 @@ static ASSETS: &[(&str, &str, SpriteInfo)] = &[
     ("death_idle", "Factions/Knights/Troops/Dead/Dead.png", SpriteInfo::sub(768, 0, 896, 128)),
 ];
 
+/// Frame duration (in milliseconds) of the animations not listed in `ANIMATION_TIMINGS`
+const DEFAULT_FRAME_DURATION: f32 = 62.5;
+
+/// Frame duration (in milliseconds) and frame events of the animations, written in the last two columns of the csv.
+/// Events are written as `frame:event` separated by spaces. See `AnimationEvents` in the game crate for the list of events.
+static ANIMATION_TIMINGS: &[(&str, f32, &str)] = &[
+    ("gobindynamite_idle", 100.0, ""),
+    ("gobindynamite_walk", 62.5, "1:footstep 4:footstep"),
+    ("gobindynamite_throw", 62.5, "5:release"),
+    ("gobintorch_idle", 100.0, ""),
+    ("gobintorch_walk", 62.5, "1:footstep 4:footstep"),
+    ("gobintorch_strike_horz", 62.5, "3:hit"),
+    ("sheep_idle", 100.0, ""),
+    ("pawn_idle", 100.0, ""),
+    ("pawn_walk", 62.5, "1:footstep 4:footstep"),
+    ("pawn_hammer", 62.5, "3:hit"),
+    ("pawn_axe", 62.5, "3:hit"),
+    ("pawn_idle_hold", 100.0, ""),
+    ("pawn_walk_hold", 62.5, "1:footstep 4:footstep"),
+    ("archer_idle", 100.0, ""),
+    ("archer_walk", 62.5, "1:footstep 4:footstep"),
+    ("archer_shoot_top", 62.5, "7:release"),
+    ("archer_shoot_top_horz", 62.5, "7:release"),
+    ("archer_shoot_horz", 62.5, "7:release"),
+    ("archer_shoot_bottom_horz", 62.5, "7:release"),
+    ("archer_shoot_bottom", 62.5, "7:release"),
+    ("warrior_idle", 100.0, ""),
+    ("warrior_walk", 62.5, "1:footstep 4:footstep"),
+    ("warrior_strike_horz1", 62.5, "3:hit"),
+    ("warrior_strike_horz2", 62.5, "3:hit"),
+];
+
 struct AssetsState {
     sprite_names: Vec<&'static str>,
     sprites_data: Vec<SpriteData>,
@@ -237,7 +269,12 @@ fn write_tilemap_csv(state: &mut AssetsState) -> Result<(), Box<dyn ::std::error
     for (i, name) in state.sprite_names.iter().enumerate() {
         let [left, top, right, bottom] = state.sprites_dst[i].splat();
         let sprite_count = state.sprites_data[i].sprite_count();
-        csv_out.push_str(&format!("{};{};{};{};{};{};\n", name, sprite_count, left, top, right, bottom));
+        let (frame_duration, events) = ANIMATION_TIMINGS.iter()
+            .find(|(animation, _, _)| animation == name )
+            .map(|&(_, frame_duration, events)| (frame_duration, events) )
+            .unwrap_or((DEFAULT_FRAME_DURATION, ""));
+
+        csv_out.push_str(&format!("{};{};{};{};{};{};{};{};\n", name, sprite_count, left, top, right, bottom, frame_duration, events));
     }
 
     let out_path = format!("{DST_ROOT}{DST_NAME_CSV}");
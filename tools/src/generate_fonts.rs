@@ -53,6 +53,16 @@ fn save_msdf_atlas_rgba(out_path: &Path, image_info: &png::OutputInfo, image_dat
     Ok(())
 }
 
+/// Characters included in the atlas of a font. The fallback font only covers the characters missing from the primary font.
+fn font_charset(input_font: &Path) -> &'static str {
+    match input_font.file_stem().and_then(|name| name.to_str() ) {
+        // Greek and cyrillic
+        Some("noto_sans") => "[0x370, 0x3FF], [0x400, 0x4FF]",
+        // Basic latin, latin-1 supplement and latin extended-A (accented characters)
+        _ => "[0x20, 0x17F]",
+    }
+}
+
 fn generate_msdf_atlas(msdf_gen_path: &str, input_font: &Path, output_image: &Path, output_json: &Path) -> Result<(), Box<dyn Error>> {
     let response = Command::new(msdf_gen_path)
         .arg("-font")
@@ -65,6 +75,8 @@ fn generate_msdf_atlas(msdf_gen_path: &str, input_font: &Path, output_image: &Pa
         .arg(output_image)
         .arg("-size")
         .arg("35")
+        .arg("-chars")
+        .arg(font_charset(input_font))
         .output()?;
 
     if response.status.code() == Some(1) {
@@ -108,6 +120,14 @@ fn compress_atlas_json(json_path: &PathBuf, bin_dst: &PathBuf) -> Result<(), Box
         pub plane_bound: [f32; 4],
     }
 
+    #[repr(C)]
+    #[derive(Copy, Clone, Default)]
+    pub struct AtlasKerning {
+        pub unicode1: u32,
+        pub unicode2: u32,
+        pub advance: f32,
+    }
+
     fn read_u32(v: &serde_json::Value) -> u32 { v.as_u64().map(|v| v as u32 ).unwrap_or(0) }
     fn read_f32(v: &serde_json::Value) -> f32 { v.as_f64().map(|v| v as f32 ).unwrap_or(0.0f32) }
     fn read_rect(v: &serde_json::Value) -> [f32; 4] {
@@ -128,9 +148,13 @@ fn compress_atlas_json(json_path: &PathBuf, bin_dst: &PathBuf) -> Result<(), Box
     let atlas = &json["atlas"];
     let metrics = &json["metrics"];
     let glyphs = &json["glyphs"].as_array().unwrap();
+    let empty_kerning = Vec::new();
+    let kerning = json["kerning"].as_array().unwrap_or(&empty_kerning);
     let mut glyph_max = 0;
 
-    let total_size_u32 = (size_of::<AtlasInfo>() + (size_of::<AtlasGlyph>() * glyphs.len())) / size_of::<u32>();
+    // Layout: [AtlasInfo][AtlasGlyph; glyph_count][kerning_count: u32][AtlasKerning; kerning_count]
+    let glyphs_size = size_of::<AtlasInfo>() + (size_of::<AtlasGlyph>() * glyphs.len());
+    let total_size_u32 = (glyphs_size + size_of::<u32>() + (size_of::<AtlasKerning>() * kerning.len())) / size_of::<u32>();
     let mut output: Vec<u32> = vec![0; total_size_u32];
 
     // Glyph
@@ -151,6 +175,21 @@ fn compress_atlas_json(json_path: &PathBuf, bin_dst: &PathBuf) -> Result<(), Box
         }
     }
 
+    // Kerning
+    unsafe {
+        let kerning_count_dst = output.as_mut_ptr().add(glyphs_size / 4);
+        *kerning_count_dst = kerning.len() as u32;
+
+        let kerning_dst_base = kerning_count_dst.add(1) as *mut AtlasKerning;
+        for (offset, pair) in kerning.iter().enumerate() {
+            *kerning_dst_base.add(offset) = AtlasKerning {
+                unicode1: read_u32(&pair["unicode1"]),
+                unicode2: read_u32(&pair["unicode2"]),
+                advance: read_f32(&pair["advance"]),
+            };
+        }
+    }
+
     // Info
     unsafe {
         let info_dst = output.as_mut_ptr() as *mut AtlasInfo;
@@ -34,6 +34,29 @@ static ASSETS: &[(&str, &str, SpriteInfo)] = &[
     ("gold_icon", "gold.png", SpriteInfo::auto()),
 
     ("life_icon", "life2.png", SpriteInfo::auto()),
+
+    ("button", "button.png", SpriteInfo::auto()),
+    ("button_hover", "button_hover.png", SpriteInfo::auto()),
+    ("button_pressed", "button_pressed.png", SpriteInfo::auto()),
+
+    ("move_icon", "command_icons.png", SpriteInfo::sub(0, 0, 64, 64)),
+    ("stop_icon", "command_icons.png", SpriteInfo::sub(64, 0, 128, 64)),
+    ("attack_icon", "command_icons.png", SpriteInfo::sub(128, 0, 192, 64)),
+    ("harvest_icon", "command_icons.png", SpriteInfo::sub(0, 64, 64, 128)),
+    ("build_icon", "command_icons.png", SpriteInfo::sub(64, 64, 128, 128)),
+    ("cancel_icon", "command_icons.png", SpriteInfo::sub(128, 64, 192, 128)),
+
+    ("progress_bar", "progress_bar.png", SpriteInfo::auto()),
+    ("progress_bar_fill", "progress_bar_fill.png", SpriteInfo::auto()),
+
+    ("scrollbar", "scrollbar.png", SpriteInfo::auto()),
+    ("scrollbar_thumb", "scrollbar_thumb.png", SpriteInfo::auto()),
+];
+
+/// Nine-slice borders (left, top, right, bottom) of the sprites used as frame backgrounds.
+/// Written after the sprite uv offsets in the csv
+static NINE_SLICES: &[(&str, [u32; 4])] = &[
+    ("info_panel", [24, 24, 24, 24]),
 ];
 
 struct AssetsState {
@@ -218,7 +241,11 @@ fn write_tilemap_csv(state: &mut AssetsState) -> Result<(), Box<dyn ::std::error
     for (i, name) in state.sprite_names.iter().enumerate() {
         let [left, top, right, bottom] = state.sprites_dst[i].splat();
         let sprite_count = state.sprites_data[i].sprite_count();
-        csv_out.push_str(&format!("{};{};{};{};{};{};\n", name, sprite_count, left+PADDING, top+PADDING, right-PADDING, bottom-PADDING));
+        csv_out.push_str(&format!("{};{};{};{};{};{};", name, sprite_count, left+PADDING, top+PADDING, right-PADDING, bottom-PADDING));
+        if let Some((_, [left, top, right, bottom])) = NINE_SLICES.iter().find(|(slice_name, _)| slice_name == name ) {
+            csv_out.push_str(&format!("{};{};{};{};", left, top, right, bottom));
+        }
+        csv_out.push('\n');
     }
 
     let out_path = format!("{DST_ROOT}{DST_NAME_CSV}");
@@ -79,6 +79,10 @@ static ASSETS: &[(&str, &str, SpriteInfo)] = &[
     // Effects
     ("fire", "Effects/Fire/Fire.png", SpriteInfo::animated(rect(0, 0, 896, 128), size(128, 128))),
     ("explosion", "Effects/Explosion/Explosions.png", SpriteInfo::animated(rect(0, 0, 1728, 192), size(192, 192))),
+    ("particle_dust", "Effects/Particles/Particles.png", SpriteInfo::sub(0, 0, 32, 32)),
+    ("particle_wood_chip", "Effects/Particles/Particles.png", SpriteInfo::sub(32, 0, 64, 32)),
+    ("particle_spark", "Effects/Particles/Particles.png", SpriteInfo::sub(64, 0, 96, 32)),
+    ("particle_smoke", "Effects/Particles/Particles.png", SpriteInfo::sub(96, 0, 128, 32)),
 ];
 
 struct AssetsState {